@@ -0,0 +1,70 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::BisectState;
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+#[tauri::command]
+pub fn get_bisect(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Option<BisectState>, String> {
+    open_repo(&repo, &config)?
+        .bisect_state()
+        .map_err(|e| format!("Failed to read bisect state: {}", e))
+}
+
+#[tauri::command]
+pub fn start_bisect(
+    repo: String,
+    bad: String,
+    good: Option<Vec<String>>,
+    paths: Option<Vec<String>>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<BisectState, String> {
+    open_repo(&repo, &config)?
+        .bisect_start(&bad, &good.unwrap_or_default(), &paths.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn mark_bisect(
+    repo: String,
+    term: String,
+    commit: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<BisectState, String> {
+    open_repo(&repo, &config)?
+        .bisect_mark(&term, commit.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reset_bisect(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    match open_repo(&repo, &config)?.bisect_reset() {
+        Ok(output) => Ok(serde_json::json!({
+            "success": true,
+            "message": "Bisect ended",
+            "output": output
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
@@ -1,6 +1,6 @@
 use gitpow_rust::config::Config;
 use gitpow_rust::git::repository::GitRepository;
-use gitpow_rust::models::{BranchAheadBehind, BranchCreationInfo, BranchInfo};
+use gitpow_rust::models::{BranchAheadBehind, BranchCreationInfo, BranchInfo, OperationState};
 use gitpow_rust::utils::get_repo_path;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
@@ -46,7 +46,7 @@ pub async fn get_branches(
             // Open a new repository instance for this thread.
             match GitRepository::open(&repo_path_clone) {
                 Ok(git_repo) => {
-                    if let Err(e) = git_repo.fetch_all() {
+                    if let Err(e) = git_repo.fetch_all(false) {
                         eprintln!(
                             "Background fetch for repo '{}' failed: {}",
                             repo_path_clone.display(),
@@ -186,6 +186,8 @@ pub struct BranchStatus {
     pub behind: i32,
     pub has_upstream: bool,
     pub stash_count: i32,
+    /// Merge, rebase, etc. in progress
+    pub operation: Option<OperationState>,
 }
 
 #[tauri::command]
@@ -266,6 +268,7 @@ pub async fn get_branch_status(
             .run_git(&["stash", "list"])
             .map(|output| output.lines().count() as i32)
             .unwrap_or(0);
+        let operation = git_repo.operation_state().unwrap_or(None);
 
         Ok(BranchStatus {
             branch: current_branch,
@@ -273,6 +276,7 @@ pub async fn get_branch_status(
             behind,
             has_upstream,
             stash_count,
+            operation,
         })
     })
     .await
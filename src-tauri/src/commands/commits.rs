@@ -1,7 +1,7 @@
 use git2;
 use gitpow_rust::config::Config;
 use gitpow_rust::git::repository::GitRepository;
-use gitpow_rust::models::{Commit, CommitMetric, CommitsBetweenResponse, Tag};
+use gitpow_rust::models::{Commit, CommitMetric, CommitsBetweenResponse};
 use gitpow_rust::utils::{get_repo_path, normalize_sha};
 use rayon::prelude::*;
 use serde::Deserialize;
@@ -312,46 +312,3 @@ pub async fn get_commit_metrics(
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
-
-#[tauri::command]
-pub async fn get_tags(
-    repo: String,
-    config: State<'_, Mutex<Config>>,
-) -> Result<Vec<Tag>, String> {
-    let repos_root = {
-        let config = config.lock().unwrap();
-        config.repos_root.clone()
-    };
-    let repo_path = get_repo_path(&repo, &repos_root);
-
-    tokio::task::spawn_blocking(move || {
-        let git_repo = GitRepository::open(&repo_path)
-            .map_err(|e| format!("Failed to open repository: {}", e))?;
-
-        let tags_out = git_repo.run_git(&[
-            "for-each-ref",
-            "refs/tags",
-            "--format=%(refname:short)%00%(objectname)%00%(creatordate:iso-strict)",
-        ]);
-
-        let mut tags = Vec::new();
-        if let Ok(output) = tags_out {
-            for line in output.lines() {
-                let parts: Vec<&str> = line.split('\0').collect();
-                if parts.len() >= 3 {
-                    tags.push(Tag {
-                        name: parts[0].to_string(),
-                        sha: parts[1].to_string(),
-                        date: parts[2].to_string(),
-                    });
-                }
-            }
-        }
-
-        Ok(tags)
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
-}
-
-
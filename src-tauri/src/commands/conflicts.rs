@@ -1,6 +1,9 @@
 use gitpow_rust::config::Config;
+use gitpow_rust::git::autoresolve;
+use gitpow_rust::git::repository::GitRepository;
 use gitpow_rust::models::{
-    ConflictFile, ConflictFileResponse, ConflictsResponse, SuccessResponse,
+    AutoResolveResponse, ConflictFileResponse, ConflictStrategyRule, ConflictsResponse,
+    RegionResolution, ResolveRegionsResponse, SuccessResponse,
 };
 use gitpow_rust::utils::get_repo_path;
 use serde::Deserialize;
@@ -36,61 +39,20 @@ pub struct GetConflictFileParams {
     path: String,
 }
 
+fn open_repo(repo: &str, config: &Config) -> Result<GitRepository, String> {
+    let repo_path = get_repo_path(repo, &config.repos_root);
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
 #[tauri::command]
 pub fn get_conflicts(
     repo: String,
     config: State<'_, Mutex<Config>>,
 ) -> Result<ConflictsResponse, String> {
     let config = config.lock().unwrap();
-    let repo_path = get_repo_path(&repo, &config.repos_root);
-
-    let status_out = run_git(&["status", "--porcelain"], &repo_path)
-        .map_err(|e| format!("Failed to get status: {}", e))?;
-
-    let lines: Vec<&str> = status_out.split('\n').collect();
-    let mut conflicted_files = Vec::new();
-
-    for line in lines {
-        let line = line.trim();
-        if line.len() < 3 {
-            continue;
-        }
-
-        let status1 = line.chars().next().unwrap_or(' ');
-        let status2 = line.chars().nth(1).unwrap_or(' ');
-
-        let is_conflict = (status1 == 'A' && status2 == 'A')
-            || status1 == 'U'
-            || status2 == 'U'
-            || (status1 == 'D' && status2 == 'D')
-            || (status1 == 'A' && status2 == 'U')
-            || (status1 == 'U' && status2 == 'A')
-            || (status1 == 'D' && status2 == 'U')
-            || (status1 == 'U' && status2 == 'D');
-
-        if is_conflict {
-            let file_path = &line[3..];
-            if file_path.contains(" -> ") {
-                let parts: Vec<&str> = file_path.split(" -> ").collect();
-                if parts.len() == 2 {
-                    conflicted_files.push(ConflictFile {
-                        path: parts[1].to_string(),
-                        r#type: "both-modified".to_string(),
-                    });
-                }
-            } else {
-                conflicted_files.push(ConflictFile {
-                    path: file_path.to_string(),
-                    r#type: "both-modified".to_string(),
-                });
-            }
-        }
-    }
-
-    Ok(ConflictsResponse {
-        files: conflicted_files.clone(),
-        has_conflicts: !conflicted_files.is_empty(),
-    })
+    open_repo(&repo, &config)?
+        .conflicts()
+        .map_err(|e| format!("Failed to get status: {}", e))
 }
 
 #[tauri::command]
@@ -99,39 +61,20 @@ pub fn get_conflict_file(
     config: State<'_, Mutex<Config>>,
 ) -> Result<ConflictFileResponse, String> {
     let config = config.lock().unwrap();
-    let repo_path = get_repo_path(&params.repo, &config.repos_root);
-
-    // Get Base (common ancestor), Mine (current/ours), and Theirs (incoming)
-    // :1: = base, :2: = ours, :3: = theirs
-    let base = run_git(&["show", &format!(":1:{}", params.path)], &repo_path).unwrap_or_default();
-
-    let mine = run_git(&["show", &format!(":2:{}", params.path)], &repo_path).unwrap_or_else(|_| {
-        // Fallback to working tree
-        let full_path = repo_path.join(&params.path);
-        fs::read_to_string(&full_path).unwrap_or_default()
-    });
-
-    let theirs =
-        run_git(&["show", &format!(":3:{}", params.path)], &repo_path).unwrap_or_default();
-
-    // Get current conflicted content (working tree)
-    let full_path = repo_path.join(&params.path);
-    let result = fs::read_to_string(&full_path).unwrap_or_default();
-
-    Ok(ConflictFileResponse {
-        base,
-        mine,
-        theirs,
-        result,
-        file_path: params.path,
-    })
+    open_repo(&params.repo, &config)?
+        .conflict_file(&params.path)
+        .map_err(|e| format!("Failed to read conflict: {}", e))
 }
 
 #[derive(Deserialize)]
 pub struct ResolveConflictParams {
     repo: String,
     path: String,
+    #[serde(default)]
     content: String,
+    /// ours, theirs or delete, instead of `content`
+    #[serde(default)]
+    resolution: Option<String>,
 }
 
 #[tauri::command]
@@ -142,6 +85,13 @@ pub fn resolve_conflict(
     let config = config.lock().unwrap();
     let repo_path = get_repo_path(&params.repo, &config.repos_root);
 
+    if let Some(resolution) = params.resolution.as_deref().filter(|r| !r.is_empty()) {
+        open_repo(&params.repo, &config)?
+            .resolve_conflict_side(&params.path, resolution)
+            .map_err(|e| format!("Failed to resolve conflict: {}", e))?;
+        return Ok(SuccessResponse { success: true });
+    }
+
     if params.path.is_empty() || params.content.is_empty() {
         return Err("path and content required".to_string());
     }
@@ -161,3 +111,56 @@ pub fn resolve_conflict(
     Ok(SuccessResponse { success: true })
 }
 
+
+#[derive(Deserialize)]
+pub struct ResolveConflictRegionsParams {
+    repo: String,
+    path: String,
+    resolutions: Vec<RegionResolution>,
+}
+
+#[tauri::command]
+pub fn resolve_conflict_regions(
+    params: ResolveConflictRegionsParams,
+    config: State<'_, Mutex<Config>>,
+) -> Result<ResolveRegionsResponse, String> {
+    let config = config.lock().unwrap();
+    open_repo(&params.repo, &config)?
+        .resolve_conflict_regions(&params.path, &params.resolutions)
+        .map_err(|e| format!("Failed to resolve conflict: {}", e))
+}
+
+#[tauri::command]
+pub fn auto_resolve_conflicts(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<AutoResolveResponse, String> {
+    let config = config.lock().unwrap();
+    open_repo(&repo, &config)?
+        .auto_resolve_conflicts()
+        .map_err(|e| format!("Failed to auto-resolve conflicts: {}", e))
+}
+
+#[tauri::command]
+pub fn set_rerere(
+    repo: String,
+    enabled: bool,
+    config: State<'_, Mutex<Config>>,
+) -> Result<SuccessResponse, String> {
+    let config = config.lock().unwrap();
+    open_repo(&repo, &config)?
+        .set_rerere_enabled(enabled)
+        .map_err(|e| format!("Failed to update rerere setting: {}", e))?;
+    Ok(SuccessResponse { success: true })
+}
+
+#[tauri::command]
+pub fn get_conflict_strategies() -> Vec<ConflictStrategyRule> {
+    autoresolve::conflict_strategies()
+}
+
+#[tauri::command]
+pub fn save_conflict_strategies(rules: Vec<ConflictStrategyRule>) -> Result<SuccessResponse, String> {
+    autoresolve::save_conflict_strategies(&rules).map_err(|e| e.to_string())?;
+    Ok(SuccessResponse { success: true })
+}
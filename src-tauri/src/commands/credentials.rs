@@ -0,0 +1,48 @@
+use gitpow_rust::git::credentials;
+use gitpow_rust::models::{
+    CredentialAnswer, CredentialInfo, CredentialPrompt, SaveCredentialRequest,
+};
+
+#[tauri::command]
+pub fn list_credentials() -> Vec<CredentialInfo> {
+    credentials::list_credentials()
+}
+
+#[tauri::command]
+pub fn save_credential(request: SaveCredentialRequest) -> Result<(), String> {
+    credentials::save_credential(request).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_credential(
+    host: Option<String>,
+    key_path: Option<String>,
+    username: Option<String>,
+) -> Result<bool, String> {
+    if host.is_none() && key_path.is_none() {
+        return Err("host or keyPath is required".to_string());
+    }
+    credentials::remove_credential(host.as_deref(), key_path.as_deref(), username.as_deref())
+        .map_err(|e| format!("Failed to update credential store: {}", e))
+}
+
+/// Prompts raised by in-flight fetch/pull/push commands; the UI polls this
+/// while one is running and answers with `answer_credential_prompt`.
+#[tauri::command]
+pub fn get_credential_prompts() -> Vec<CredentialPrompt> {
+    credentials::pending_prompts()
+}
+
+#[tauri::command]
+pub fn answer_credential_prompt(id: String, answer: CredentialAnswer) -> Result<(), String> {
+    if credentials::answer_prompt(&id, Some(answer)) {
+        Ok(())
+    } else {
+        Err("Prompt not found (already answered or timed out)".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn cancel_credential_prompt(id: String) -> bool {
+    credentials::answer_prompt(&id, None)
+}
@@ -1,4 +1,5 @@
 use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
 use gitpow_rust::models::{DiffHunk, DiffResponse};
 use gitpow_rust::utils::{get_repo_path, normalize_sha};
 use regex::Regex;
@@ -97,6 +98,26 @@ pub fn get_diff(
     let config = config.lock().unwrap();
     let repo_path = get_repo_path(&params.repo, &config.repos_root);
 
+    // Submodules are commit pointers; describe the pointer change instead
+    if let Ok(git_repo) = GitRepository::open(&repo_path) {
+        if git_repo.is_submodule_path(&params.path) {
+            let staged = params.staged.as_deref() == Some("true");
+            let file_diff = match &params.ref_ {
+                Some(ref_sha) => {
+                    git_repo.get_file_diff(&normalize_sha(ref_sha.trim()), &params.path)
+                }
+                None => git_repo.get_working_diff(&params.path, staged),
+            }
+            .map_err(|e| format!("Failed to get diff: {}", e))?;
+            return Ok(DiffResponse {
+                diff: file_diff.diff,
+                hunks: Vec::new(),
+                file_path: file_diff.file_path,
+                submodule: file_diff.submodule,
+            });
+        }
+    }
+
     if let Some(ref_sha) = params.ref_ {
         let clean_ref = normalize_sha(&ref_sha.trim());
 
@@ -163,6 +184,7 @@ pub fn get_diff(
                 diff: diff_out,
                 hunks,
                 file_path: params.path,
+                submodule: None,
             });
         } else {
             // No parent (initial commit) - return full file as additions
@@ -180,6 +202,7 @@ pub fn get_diff(
                 diff: diff_lines.join("\n"),
                 hunks: Vec::new(),
                 file_path: params.path,
+                submodule: None,
             });
         }
     }
@@ -199,6 +222,7 @@ pub fn get_diff(
         diff: diff_out,
         hunks,
         file_path: params.path,
+        submodule: None,
     })
 }
 
@@ -1,6 +1,6 @@
 use gitpow_rust::config::Config;
 use gitpow_rust::git::repository::GitRepository;
-use gitpow_rust::models::{FileChange, FileCreationInfo, ImageResponse};
+use gitpow_rust::models::{FileChange, FileCreationInfo, ImageResponse, TreeResponse};
 use gitpow_rust::utils::{get_repo_path, normalize_sha};
 use base64::{engine::general_purpose, Engine as _};
 use mime_guess;
@@ -19,6 +19,17 @@ pub struct GetFilesParams {
     path: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct GetTreeParams {
+    repo: String,
+    #[serde(rename = "ref")]
+    ref_: Option<String>,
+    path: Option<String>,
+    recursive: Option<bool>,
+    pattern: Option<String>,
+    last_commit: Option<bool>,
+}
+
 #[derive(Deserialize)]
 pub struct GetFileCreationParams {
     repo: String,
@@ -147,6 +158,42 @@ pub fn get_files(
     Ok(files)
 }
 
+#[tauri::command]
+pub fn get_tree(
+    params: GetTreeParams,
+    config: State<'_, Mutex<Config>>,
+) -> Result<TreeResponse, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(&params.repo, &config.repos_root);
+    let git_repo = GitRepository::open(&repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+    let reference = params.ref_.unwrap_or_else(|| "HEAD".to_string());
+    let dir = params.path.unwrap_or_default();
+    let recursive = params.recursive.unwrap_or(false);
+    if recursive && params.last_commit.unwrap_or(false) {
+        return Err("last_commit is only supported for non-recursive listings".to_string());
+    }
+
+    let mut listing = git_repo
+        .list_tree(&reference, &dir, recursive, params.pattern.as_deref())
+        .map_err(|e| format!("Failed to list tree: {}", e))?;
+
+    if params.last_commit.unwrap_or(false) {
+        git_repo
+            .annotate_last_commits(&mut listing, &dir)
+            .map_err(|e| format!("Failed to find last commits: {}", e))?;
+    }
+
+    Ok(TreeResponse {
+        reference,
+        commit: listing.commit.to_string(),
+        path: dir.trim_matches('/').to_string(),
+        tree_oid: listing.tree_oid.to_string(),
+        entries: listing.entries,
+    })
+}
+
 #[tauri::command]
 pub fn get_commit_files(
     params: GetFilesParams,
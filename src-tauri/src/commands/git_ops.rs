@@ -1,27 +1,49 @@
 use gitpow_rust::config::Config;
-use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::pull::{pull_summary, PullMode, PullOptions};
+use gitpow_rust::git::push::PushOptions;
+use gitpow_rust::git::repository::{GitRepository, StashPushOptions};
+use gitpow_rust::models::{DiffHunk, PushPreview, StashFileChange, StashShowResponse};
 use gitpow_rust::utils::get_repo_path;
+use serde::Deserialize;
 use std::sync::Mutex;
 use tauri::State;
 
 #[tauri::command]
-pub fn pull_repo(
+pub async fn pull_repo(
     repo: String,
+    mode: Option<String>,
+    autostash: Option<bool>,
     config: State<'_, Mutex<Config>>,
 ) -> Result<serde_json::Value, String> {
-    let config = config.lock().unwrap();
-    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
 
     if !repo_path.exists() || !repo_path.is_dir() {
         return Err("Repository not found".to_string());
     }
 
-    match GitRepository::open(&repo_path) {
-        Ok(repo) => match repo.pull() {
-            Ok(output) => Ok(serde_json::json!({
+    let mode = match mode.as_deref().filter(|m| !m.is_empty()) {
+        Some(mode) => Some(PullMode::parse(mode).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let opts = PullOptions { mode, autostash };
+
+    // Pulling may wait on a credentials prompt answered from the UI, so it
+    // must not block the command thread.
+    tokio::task::spawn_blocking(move || match GitRepository::open(&repo_path) {
+        Ok(repo) => match repo.pull(&opts) {
+            Ok(result) if result.outcome == "conflicted" => Ok(serde_json::json!({
+                "success": false,
+                "error": pull_summary(&result),
+                "result": result
+            })),
+            Ok(result) => Ok(serde_json::json!({
                 "success": true,
-                "message": "Pull successful",
-                "output": output
+                "message": pull_summary(&result),
+                "result": result
             })),
             Err(e) => Ok(serde_json::json!({
                 "success": false,
@@ -30,26 +52,65 @@ pub fn pull_repo(
             })),
         },
         Err(e) => Err(format!("Failed to open repository: {}", e)),
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Push options beyond the remote; see [`PushOptions`].
+#[derive(Deserialize, Default)]
+pub struct PushParams {
+    branch: Option<String>,
+    remote_branch: Option<String>,
+    force_with_lease: Option<bool>,
+    expected_sha: Option<String>,
+    tags: Option<bool>,
+    no_verify: Option<bool>,
+    push_options: Option<Vec<String>>,
+}
+
+impl PushParams {
+    pub(crate) fn into_options(self, remote: Option<String>) -> PushOptions {
+        PushOptions {
+            remote: remote.filter(|r| !r.is_empty()),
+            branch: self.branch.filter(|b| !b.is_empty()),
+            remote_branch: self.remote_branch.filter(|b| !b.is_empty()),
+            force_with_lease: self.force_with_lease.unwrap_or(false),
+            expected_sha: self.expected_sha.filter(|s| !s.is_empty()),
+            tags: self.tags.unwrap_or(false),
+            no_verify: self.no_verify.unwrap_or(false),
+            push_options: self.push_options.unwrap_or_default(),
+        }
     }
 }
 
 #[tauri::command]
-pub fn push_repo(
+pub async fn push_repo(
     repo: String,
+    remote: Option<String>,
+    options: Option<PushParams>,
     config: State<'_, Mutex<Config>>,
 ) -> Result<serde_json::Value, String> {
-    let config = config.lock().unwrap();
-    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
 
     if !repo_path.exists() || !repo_path.is_dir() {
         return Err("Repository not found".to_string());
     }
 
-    match GitRepository::open(&repo_path) {
-        Ok(repo) => match repo.push() {
-            Ok(output) => Ok(serde_json::json!({
+    tokio::task::spawn_blocking(move || {
+        let repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        let opts = options.unwrap_or_default().into_options(remote);
+        let result = repo.push_branch(&opts);
+
+        match result {
+            Ok((output, message)) => Ok(serde_json::json!({
                 "success": true,
-                "message": "Push successful",
+                "message": message,
                 "output": output
             })),
             Err(e) => Ok(serde_json::json!({
@@ -57,15 +118,84 @@ pub fn push_repo(
                 "error": format!("Failed to push: {}", e),
                 "message": format!("Push failed: {}", e)
             })),
-        },
-        Err(e) => Err(format!("Failed to open repository: {}", e)),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn delete_remote_branch(
+    repo: String,
+    remote: Option<String>,
+    options: Option<PushParams>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        let opts = options.unwrap_or_default().into_options(remote);
+
+        match repo.delete_remote_ref(&opts) {
+            Ok(output) => Ok(serde_json::json!({
+                "success": true,
+                "message": "Remote branch deleted",
+                "output": output
+            })),
+            Err(e) => Ok(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to delete remote branch: {}", e)
+            })),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn push_preview(
+    repo: String,
+    remote: Option<String>,
+    options: Option<PushParams>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<PushPreview, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
     }
+
+    tokio::task::spawn_blocking(move || {
+        let repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        repo.push_preview(&options.unwrap_or_default().into_options(remote))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 #[tauri::command]
 pub fn stash_push(
     repo: String,
     message: Option<String>,
+    keep_index: Option<bool>,
+    include_untracked: Option<bool>,
+    paths: Option<Vec<String>>,
     config: State<'_, Mutex<Config>>,
 ) -> Result<serde_json::Value, String> {
     let config = config.lock().unwrap();
@@ -77,8 +207,13 @@ pub fn stash_push(
 
     match GitRepository::open(&repo_path) {
         Ok(repo) => {
-            let msg = message.as_deref();
-            match repo.stash_push(msg) {
+            let opts = StashPushOptions {
+                message,
+                keep_index: keep_index.unwrap_or(false),
+                include_untracked: include_untracked.unwrap_or(false),
+                paths: paths.unwrap_or_default(),
+            };
+            match repo.stash_push(&opts) {
                 Ok(output) => Ok(serde_json::json!({
                     "success": true,
                     "message": "Changes stashed",
@@ -124,6 +259,121 @@ pub fn stash_pop(
     }
 }
 
+#[tauri::command]
+pub fn stash_show(
+    repo: String,
+    stash_ref: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<StashShowResponse, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    let stash_ref = stash_ref.unwrap_or_else(|| "stash@{0}".to_string());
+    let git_repo = GitRepository::open(&repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+    let entry = git_repo
+        .stash_list()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|e| e.index == stash_ref || e.sha == stash_ref)
+        .ok_or_else(|| format!("Stash {} not found", stash_ref))?;
+
+    let files = git_repo
+        .stash_show(&entry.sha)
+        .map_err(|e| format!("Failed to show stash: {}", e))?
+        .into_iter()
+        .map(|f| StashFileChange {
+            path: f.path,
+            status: f.status,
+            untracked: f.untracked,
+            diff: f.diff,
+            hunks: f
+                .hunks
+                .into_iter()
+                .enumerate()
+                .map(|(i, h)| DiffHunk {
+                    old_start: h.old_start,
+                    old_count: h.old_count,
+                    new_start: h.new_start,
+                    new_count: h.new_count,
+                    lines: h.lines,
+                    line_start: i as i32,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(StashShowResponse { entry, files })
+}
+
+#[tauri::command]
+pub fn stash_branch(
+    repo: String,
+    branch: String,
+    stash_ref: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    let stash_ref = stash_ref.unwrap_or_else(|| "stash@{0}".to_string());
+    match GitRepository::open(&repo_path) {
+        Ok(repo) => match repo.stash_branch(branch.trim(), &stash_ref) {
+            Ok(output) => Ok(serde_json::json!({
+                "success": true,
+                "message": format!("Created branch '{}' from {}", branch.trim(), stash_ref),
+                "output": output
+            })),
+            Err(e) => Ok(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to create branch from stash: {}", e),
+                "message": format!("Stash branch failed: {}", e)
+            })),
+        },
+        Err(e) => Err(format!("Failed to open repository: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn stash_rename(
+    repo: String,
+    message: String,
+    stash_ref: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    let stash_ref = stash_ref.unwrap_or_else(|| "stash@{0}".to_string());
+    match GitRepository::open(&repo_path) {
+        Ok(repo) => match repo.stash_rename(&stash_ref, message.trim()) {
+            Ok(()) => Ok(serde_json::json!({
+                "success": true,
+                "message": format!("Stash {} renamed", stash_ref)
+            })),
+            Err(e) => Ok(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to rename stash: {}", e),
+                "message": format!("Stash rename failed: {}", e)
+            })),
+        },
+        Err(e) => Err(format!("Failed to open repository: {}", e)),
+    }
+}
+
 #[tauri::command]
 pub fn checkout_commit(
     repo: String,
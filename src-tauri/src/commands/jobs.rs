@@ -0,0 +1,156 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::clone::CloneOptions;
+use gitpow_rust::git::jobs;
+use gitpow_rust::git::pull::{PullMode, PullOptions};
+use gitpow_rust::models::JobInfo;
+use gitpow_rust::utils::get_repo_path;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::State;
+
+use super::git_ops::PushParams;
+use super::repos::{discovery_scope, GetReposRequest};
+
+fn repo_path_for(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<PathBuf, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+    Ok(repo_path)
+}
+
+/// Running and recently finished jobs. Progress is also emitted as
+/// `job-progress` events carrying the same JobInfo.
+#[tauri::command]
+pub fn list_jobs(repo: Option<String>) -> Vec<JobInfo> {
+    jobs::list_jobs(repo.as_deref())
+}
+
+#[tauri::command]
+pub fn get_job(id: String) -> Result<JobInfo, String> {
+    jobs::get_job(&id).ok_or_else(|| "Job not found".to_string())
+}
+
+#[tauri::command]
+pub fn cancel_job(id: String) -> bool {
+    jobs::cancel_job(&id)
+}
+
+#[tauri::command]
+pub fn start_fetch_job(
+    repo: String,
+    remote: Option<String>,
+    prune: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    let repo_path = repo_path_for(&repo, &config)?;
+    let remote = remote.filter(|r| !r.is_empty());
+    jobs::start_fetch(&repo, repo_path, remote, prune.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Fetch every repository under the repos root in the background
+#[tauri::command]
+pub fn start_fetch_all_job(
+    request: Option<GetReposRequest>,
+    prune: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    let (repos_root, options) = discovery_scope(request, &config);
+    jobs::start_fetch_all_repos(repos_root, options, prune.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn start_submodule_update_job(
+    repo: String,
+    paths: Option<Vec<String>>,
+    init: Option<bool>,
+    recursive: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    let repo_path = repo_path_for(&repo, &config)?;
+    jobs::start_submodule_update(
+        &repo,
+        repo_path,
+        paths.unwrap_or_default(),
+        init.unwrap_or(false),
+        recursive.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn start_pull_job(
+    repo: String,
+    mode: Option<String>,
+    autostash: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    let repo_path = repo_path_for(&repo, &config)?;
+    let mode = match mode.as_deref().filter(|m| !m.is_empty()) {
+        Some(mode) => Some(PullMode::parse(mode).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    jobs::start_pull(&repo, repo_path, PullOptions { mode, autostash }).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn start_bisect_job(
+    repo: String,
+    command: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    if command.trim().is_empty() {
+        return Err("command is required".to_string());
+    }
+    let repo_path = repo_path_for(&repo, &config)?;
+    jobs::start_bisect_run(&repo, repo_path, command).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn start_push_job(
+    repo: String,
+    remote: Option<String>,
+    options: Option<PushParams>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    let repo_path = repo_path_for(&repo, &config)?;
+    let opts = options.unwrap_or_default().into_options(remote);
+    jobs::start_push(&repo, repo_path, opts).map_err(|e| e.to_string())
+}
+
+/// Clone options; see [`CloneOptions`].
+#[derive(Deserialize, Default)]
+pub struct CloneParams {
+    branch: Option<String>,
+    depth: Option<u32>,
+    filter: Option<String>,
+    recurse_submodules: Option<bool>,
+}
+
+#[tauri::command]
+pub fn start_clone_job(
+    url: String,
+    name: Option<String>,
+    options: Option<CloneParams>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JobInfo, String> {
+    if url.trim().is_empty() {
+        return Err("url is required".to_string());
+    }
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let options = options.unwrap_or_default();
+    let opts = CloneOptions {
+        branch: options.branch.filter(|b| !b.is_empty()),
+        depth: options.depth.filter(|d| *d > 0),
+        filter: options.filter.filter(|f| !f.is_empty()),
+        recurse_submodules: options.recurse_submodules.unwrap_or(false),
+    };
+    jobs::start_clone(&repos_root, &url, name.as_deref(), opts).map_err(|e| e.to_string())
+}
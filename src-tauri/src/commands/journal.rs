@@ -0,0 +1,41 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::JournalEntry;
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+#[tauri::command]
+pub fn get_journal(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<JournalEntry>, String> {
+    open_repo(&repo, &config)?
+        .journal_history()
+        .map_err(|e| format!("Failed to read the journal: {}", e))
+}
+
+#[tauri::command]
+pub fn undo(
+    repo: String,
+    id: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<JournalEntry, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = match id.as_deref() {
+        Some(id) => git_repo.undo(id),
+        None => git_repo.undo_last(),
+    };
+    result.map_err(|e| format!("Failed to undo: {:#}", e))
+}
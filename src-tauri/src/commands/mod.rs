@@ -2,27 +2,66 @@ pub mod branches;
 pub mod browse;
 pub mod commits;
 pub mod conflicts;
+pub mod credentials;
 pub mod diff;
 pub mod explorer;
 pub mod fetch;
 pub mod files;
 pub mod git_ops;
+pub mod jobs;
+pub mod operations;
+pub mod journal;
+pub mod reflog;
+pub mod reset;
+pub mod bisect;
 pub mod rebase;
+pub mod remotes;
 pub mod repos;
+pub mod search;
 pub mod staging;
+pub mod stats;
+pub mod submodules;
+pub mod tags;
+pub mod worktrees;
 
 // Re-export all command functions
 pub use branches::{get_branches, get_branch_ahead_behind, get_branch_creation, get_branch_status};
 pub use browse::browse_projects_root;
-pub use commits::{get_commits, get_commits_all_branches, get_commits_between, get_commit_metrics, get_tags};
-pub use conflicts::{get_conflicts, get_conflict_file, resolve_conflict};
+pub use commits::{get_commits, get_commits_all_branches, get_commits_between, get_commit_metrics};
+pub use conflicts::{
+    auto_resolve_conflicts, get_conflict_file, get_conflict_strategies, get_conflicts,
+    resolve_conflict, resolve_conflict_regions, save_conflict_strategies, set_rerere,
+};
+pub use credentials::{
+    answer_credential_prompt, cancel_credential_prompt, delete_credential, get_credential_prompts,
+    list_credentials, save_credential,
+};
 pub use diff::get_diff;
 pub use explorer::open_explorer;
 pub use fetch::fetch_repo;
-pub use files::{get_files, get_commit_files, get_file, get_file_creation, get_file_creation_batch, get_image};
-pub use git_ops::{pull_repo, push_repo, stash_pop, stash_push};
+pub use files::{get_files, get_tree, get_commit_files, get_file, get_file_creation, get_file_creation_batch, get_image};
+pub use git_ops::{
+    delete_remote_branch, pull_repo, push_preview, push_repo, stash_branch, stash_pop, stash_push,
+    stash_rename, stash_show,
+};
+pub use jobs::{
+    cancel_job, get_job, list_jobs, start_clone_job, start_fetch_all_job, start_fetch_job,
+    start_bisect_job, start_pull_job, start_push_job, start_submodule_update_job,
+};
+pub use journal::{get_journal, undo};
+pub use reflog::{get_lost_commits, get_reflog, recover_commit, restore_reflog_entry};
+pub use bisect::{get_bisect, mark_bisect, reset_bisect, start_bisect};
+pub use reset::{reset, reset_preview, restore_file};
+pub use operations::{abort_operation, continue_operation, get_operation, skip_operation};
 pub use rebase::{get_rebase_preview, post_rebase_plan};
-pub use repos::{get_config, get_repos};
+pub use remotes::{
+    add_remote, fetch_remote, get_stale_branches, list_remotes, remove_remote, rename_remote,
+    set_remote_refspecs, set_remote_url,
+};
+pub use repos::{get_config, get_dashboard, get_repos, init_repo, rescan_repos};
+pub use search::search_code;
 pub use staging::{get_status, stage, unstage, commit};
-
-
+pub use stats::{get_churn_stats, get_commit_activity, get_contributor_stats};
+pub use tags::{create_tag, delete_tag, get_tags, push_tags};
+pub use submodules::{init_submodules, list_submodules, open_submodule, sync_submodules};
+pub use worktrees::{add_worktree, list_worktrees, open_worktree, prune_worktrees, remove_worktree};
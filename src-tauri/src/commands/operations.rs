@@ -0,0 +1,85 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::OperationState;
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn operation_response(
+    git_repo: &GitRepository,
+    result: anyhow::Result<String>,
+    verb: &str,
+) -> serde_json::Value {
+    match result {
+        Ok(output) => {
+            let message = match git_repo.operation_state().ok().flatten() {
+                Some(op) if op.conflicted_files > 0 => format!(
+                    "{} the {}; it stopped again with {} conflicted file(s)",
+                    verb, op.kind, op.conflicted_files
+                ),
+                Some(op) => format!("{} the {}; it is still in progress", verb, op.kind),
+                None => format!("{} the operation", verb),
+            };
+            serde_json::json!({
+                "success": true,
+                "message": message,
+                "output": output
+            })
+        }
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }),
+    }
+}
+
+#[tauri::command]
+pub fn get_operation(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Option<OperationState>, String> {
+    open_repo(&repo, &config)?
+        .operation_state()
+        .map_err(|e| format!("Failed to read repository state: {}", e))
+}
+
+#[tauri::command]
+pub fn continue_operation(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.continue_operation();
+    Ok(operation_response(&git_repo, result, "Continued"))
+}
+
+#[tauri::command]
+pub fn skip_operation(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.skip_operation();
+    Ok(operation_response(&git_repo, result, "Skipped a step of"))
+}
+
+#[tauri::command]
+pub fn abort_operation(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.abort_operation();
+    Ok(operation_response(&git_repo, result, "Aborted"))
+}
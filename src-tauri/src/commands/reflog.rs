@@ -0,0 +1,85 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::{LostCommit, ReflogEntry};
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn operation_response(result: anyhow::Result<String>, message: String) -> serde_json::Value {
+    match result {
+        Ok(output) => serde_json::json!({
+            "success": true,
+            "message": message,
+            "output": output
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }),
+    }
+}
+
+#[tauri::command]
+pub fn get_reflog(
+    repo: String,
+    branch: Option<String>,
+    limit: Option<usize>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<ReflogEntry>, String> {
+    open_repo(&repo, &config)?
+        .reflog(branch.as_deref(), limit)
+        .map_err(|e| format!("Failed to read reflog: {}", e))
+}
+
+#[tauri::command]
+pub fn restore_reflog_entry(
+    repo: String,
+    branch: Option<String>,
+    index: usize,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let name = branch.unwrap_or_else(|| "HEAD".to_string());
+    let result = git_repo.restore_reflog_entry(Some(&name), index);
+    Ok(operation_response(
+        result,
+        format!("Restored {} to {}@{{{}}}", name, name, index),
+    ))
+}
+
+#[tauri::command]
+pub fn get_lost_commits(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<LostCommit>, String> {
+    open_repo(&repo, &config)?
+        .lost_commits()
+        .map_err(|e| format!("Failed to find lost commits: {}", e))
+}
+
+#[tauri::command]
+pub fn recover_commit(
+    repo: String,
+    sha: String,
+    branch: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.recover_commit(&sha, branch.as_deref());
+    let message = match branch.as_deref() {
+        Some(branch) => format!("Recovered {} as branch '{}'", sha, branch),
+        None => format!("Recovered {} as a stash", sha),
+    };
+    Ok(operation_response(result, message))
+}
@@ -0,0 +1,188 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::{RemoteFetchResult, RemoteInfo, StaleRemoteBranch};
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn operation_result(result: anyhow::Result<()>, message: String) -> serde_json::Value {
+    match result {
+        Ok(()) => serde_json::json!({
+            "success": true,
+            "message": message
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }),
+    }
+}
+
+#[tauri::command]
+pub fn list_remotes(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<RemoteInfo>, String> {
+    open_repo(&repo, &config)?
+        .list_remotes()
+        .map_err(|e| format!("Failed to list remotes: {}", e))
+}
+
+#[tauri::command]
+pub fn add_remote(
+    repo: String,
+    name: String,
+    url: String,
+    fetch: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let fetch = fetch.filter(|f| !f.is_empty());
+    let result = git_repo.add_remote(name.trim(), url.trim(), fetch.as_deref());
+    Ok(operation_result(
+        result,
+        format!("Added remote '{}'", name.trim()),
+    ))
+}
+
+#[tauri::command]
+pub fn remove_remote(
+    repo: String,
+    name: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.remove_remote(&name);
+    Ok(operation_result(
+        result,
+        format!("Removed remote '{}'", name),
+    ))
+}
+
+#[tauri::command]
+pub fn rename_remote(
+    repo: String,
+    name: String,
+    new_name: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    match git_repo.rename_remote(&name, new_name.trim()) {
+        Ok(problems) => Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Renamed remote '{}' to '{}'", name, new_name.trim()),
+            "unrenamedRefspecs": problems
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+#[tauri::command]
+pub fn set_remote_url(
+    repo: String,
+    name: String,
+    url: String,
+    push: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.set_remote_url(&name, url.trim(), push.unwrap_or(false));
+    Ok(operation_result(
+        result,
+        format!("Updated remote '{}'", name),
+    ))
+}
+
+#[tauri::command]
+pub fn set_remote_refspecs(
+    repo: String,
+    name: String,
+    fetch: Vec<String>,
+    push: Option<Vec<String>>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.set_remote_refspecs(&name, &fetch, push.as_deref());
+    Ok(operation_result(
+        result,
+        format!("Updated refspecs of remote '{}'", name),
+    ))
+}
+
+#[tauri::command]
+pub async fn fetch_remote(
+    repo: String,
+    name: String,
+    prune: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<RemoteFetchResult, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .fetch_remote(&name, prune.unwrap_or(false))
+            .map_err(|e| format!("Failed to fetch '{}': {}", name, e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_stale_branches(
+    repo: String,
+    name: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<StaleRemoteBranch>, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+        let names = match name.filter(|n| !n.is_empty()) {
+            Some(name) => vec![name],
+            None => git_repo
+                .list_remotes()
+                .map_err(|e| format!("Failed to list remotes: {}", e))?
+                .into_iter()
+                .map(|r| r.name)
+                .collect(),
+        };
+
+        let mut stale = Vec::new();
+        for name in names {
+            stale.extend(
+                git_repo
+                    .stale_remote_branches(&name)
+                    .map_err(|e| format!("Failed to check remote '{}': {:#}", name, e))?,
+            );
+        }
+        Ok(stale)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
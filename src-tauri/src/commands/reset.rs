@@ -0,0 +1,72 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::reset::ResetMode;
+use gitpow_rust::models::ResetPreview;
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+#[tauri::command]
+pub fn reset_preview(
+    repo: String,
+    target: String,
+    mode: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<ResetPreview, String> {
+    let mode = ResetMode::parse(mode.as_deref().unwrap_or("mixed")).map_err(|e| e.to_string())?;
+    open_repo(&repo, &config)?
+        .reset_preview(&target, mode)
+        .map_err(|e| format!("Failed to preview reset: {:#}", e))
+}
+
+#[tauri::command]
+pub fn reset(
+    repo: String,
+    target: String,
+    mode: Option<String>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let mode = ResetMode::parse(mode.as_deref().unwrap_or("mixed")).map_err(|e| e.to_string())?;
+    match open_repo(&repo, &config)?.reset(&target, mode) {
+        Ok(output) => Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Reset ({}) to {}", mode.as_str(), target),
+            "output": output
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": format!("{:#}", e)
+        })),
+    }
+}
+
+#[tauri::command]
+pub fn restore_file(
+    repo: String,
+    commit: String,
+    path: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    match open_repo(&repo, &config)?.restore_file(&commit, &path) {
+        Ok(output) => Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Restored {} from {}", path, commit),
+            "output": output
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
@@ -0,0 +1,75 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::search::{supersede_search, GrepOptions};
+use gitpow_rust::models::SearchResponse;
+use gitpow_rust::utils::get_repo_path;
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::State;
+
+#[derive(Deserialize)]
+pub struct SearchCodeParams {
+    repo: String,
+    q: String,
+    #[serde(rename = "ref")]
+    ref_: Option<String>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    whole_word: Option<bool>,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    max_results: Option<usize>,
+    context: Option<usize>,
+}
+
+#[tauri::command]
+pub async fn search_code(
+    params: SearchCodeParams,
+    config: State<'_, Mutex<Config>>,
+) -> Result<SearchResponse, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&params.repo, &repos_root);
+
+    let reference = params
+        .ref_
+        .filter(|r| !r.is_empty() && !r.eq_ignore_ascii_case("WORKTREE"));
+    let opts = GrepOptions {
+        query: params.q,
+        reference,
+        regex: params.regex.unwrap_or(false),
+        case_sensitive: params.case_sensitive.unwrap_or(false),
+        whole_word: params.whole_word.unwrap_or(false),
+        include: params.include,
+        exclude: params.exclude,
+        max_results: params.max_results.unwrap_or(1_000).clamp(1, 10_000),
+        context_lines: params.context.unwrap_or(0).min(10),
+    };
+
+    // A newer search for the same repo cancels this one.
+    let cancel = supersede_search(&repo_path.to_string_lossy());
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+        let outcome = git_repo
+            .grep(&opts, &cancel)
+            .map_err(|e| format!("Search failed: {}", e))?;
+
+        Ok(SearchResponse {
+            query: opts.query,
+            reference: opts.reference,
+            files: outcome.files,
+            total_matches: outcome.total_matches,
+            truncated: outcome.truncated,
+            cancelled: outcome.cancelled,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
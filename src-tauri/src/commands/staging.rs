@@ -1,5 +1,7 @@
 use gitpow_rust::config::Config;
-use gitpow_rust::models::{StatusFile, StatusResponse, SuccessResponse};
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::status::StatusOptions;
+use gitpow_rust::models::{StatusResponse, SuccessResponse};
 use gitpow_rust::utils::get_repo_path;
 use serde::Deserialize;
 use std::fs;
@@ -51,76 +53,20 @@ pub struct CommitRequest {
 #[tauri::command]
 pub fn get_status(
     repo: String,
+    ignored: Option<bool>,
+    untracked: Option<String>,
     config: State<'_, Mutex<Config>>,
 ) -> Result<StatusResponse, String> {
     let config = config.lock().unwrap();
     let repo_path = get_repo_path(&repo, &config.repos_root);
 
-    let status_out = run_git(&["status", "--porcelain"], &repo_path)
-        .map_err(|e| format!("Failed to get status: {}", e))?;
-
-    let lines: Vec<&str> = status_out.split('\n').collect();
-    let mut files = Vec::new();
-
-    for line in lines {
-        // Don't trim leading spaces - they're significant in git status --porcelain format
-        // Format: "XY filename" where X=staged status, Y=unstaged status, then space, then filename
-        let line = line.trim_end(); // Only trim trailing whitespace
-        if line.is_empty() || line.len() < 4 {
-            continue;
-        }
-
-        let staged = line.chars().next().unwrap() != ' ' && line.chars().next().unwrap() != '?';
-        let unstaged = line.chars().nth(1).unwrap() != ' ' && line.chars().nth(1).unwrap() != '?';
-        let status = &line[..2];
-        // Git status format is always: 2 status chars, then space, then filename
-        // Find the space after the 2-char status and get everything after it
-        let file_path = if line.len() >= 3 && line.chars().nth(2) == Some(' ') {
-            // Standard format: "XY filename" - filename starts at index 3
-            &line[3..]
-        } else if line.len() > 2 {
-            // Fallback: skip first 3 chars (should be "XY " but handle edge cases)
-            &line[3..]
-        } else {
-            continue;
-        };
-
-        if file_path.contains(" -> ") {
-            // Renamed file
-            let parts: Vec<&str> = file_path.split(" -> ").collect();
-            if parts.len() == 2 {
-                files.push(StatusFile {
-                    path: parts[1].to_string(),
-                    old_path: Some(parts[0].to_string()),
-                    status: status.to_string(),
-                    staged,
-                    unstaged,
-                    r#type: "renamed".to_string(),
-                });
-            }
-        } else {
-            let file_type = if status.contains('A') {
-                "added"
-            } else if status.contains('D') {
-                "deleted"
-            } else if status.contains('?') {
-                "untracked"
-            } else {
-                "modified"
-            };
-
-            files.push(StatusFile {
-                path: file_path.to_string(),
-                old_path: None,
-                status: status.to_string(),
-                staged,
-                unstaged,
-                r#type: file_type.to_string(),
-            });
-        }
-    }
-
-    Ok(StatusResponse { files })
+    let opts = StatusOptions {
+        ignored: ignored.unwrap_or(false),
+        untracked,
+    };
+    GitRepository::open(&repo_path)
+        .and_then(|repo| repo.status(&opts))
+        .map_err(|e| format!("Failed to get status: {}", e))
 }
 
 #[tauri::command]
@@ -206,7 +152,10 @@ pub fn commit(
         return Err("commit message required".to_string());
     }
 
-    run_git(&["commit", "-m", message], &repo_path)
+    GitRepository::open(&repo_path)
+        .and_then(|git_repo| {
+            git_repo.journaled("Commit", || git_repo.run_git(&["commit", "-m", message]))
+        })
         .map_err(|e| format!("Failed to create commit: {}", e))?;
 
     Ok(SuccessResponse { success: true })
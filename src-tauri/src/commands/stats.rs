@@ -0,0 +1,129 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::stats::{ChurnOptions, TimeBucket};
+use gitpow_rust::models::{ActivityResponse, ChurnResponse, ContributorStatsResponse};
+use gitpow_rust::utils::get_repo_path;
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::State;
+
+#[derive(Deserialize)]
+pub struct GetContributorStatsParams {
+    repo: String,
+    branch: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GetChurnStatsParams {
+    repo: String,
+    branch: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>,
+    max_coupling_files: Option<usize>,
+    min_shared_commits: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct GetCommitActivityParams {
+    repo: String,
+    branch: Option<String>,
+    bucket: Option<String>,
+    author: Option<String>,
+    path: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_contributor_stats(
+    params: GetContributorStatsParams,
+    config: State<'_, Mutex<Config>>,
+) -> Result<ContributorStatsResponse, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&params.repo, &repos_root);
+    let branch_name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .contributor_stats(
+                &branch_name,
+                params.since.as_deref(),
+                params.until.as_deref(),
+            )
+            .map_err(|e| format!("Failed to compute contributor stats: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_churn_stats(
+    params: GetChurnStatsParams,
+    config: State<'_, Mutex<Config>>,
+) -> Result<ChurnResponse, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&params.repo, &repos_root);
+    let branch_name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+    let opts = ChurnOptions {
+        since: params.since,
+        until: params.until,
+        limit: params.limit.unwrap_or(50).clamp(1, 1_000),
+        max_coupling_files: params.max_coupling_files.unwrap_or(50),
+        min_shared_commits: params.min_shared_commits.unwrap_or(3).max(1),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .churn_stats(&branch_name, &opts)
+            .map_err(|e| format!("Failed to compute churn: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_commit_activity(
+    params: GetCommitActivityParams,
+    config: State<'_, Mutex<Config>>,
+) -> Result<ActivityResponse, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&params.repo, &repos_root);
+    let branch_name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+    let bucket =
+        TimeBucket::parse(params.bucket.as_deref().unwrap_or("week")).map_err(|e| e.to_string())?;
+    let author = params.author.filter(|a| !a.is_empty());
+    let path = params.path.filter(|p| !p.is_empty());
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .commit_activity(
+                &branch_name,
+                bucket,
+                author.as_deref(),
+                path.as_deref(),
+                params.since.as_deref(),
+                params.until.as_deref(),
+            )
+            .map_err(|e| format!("Failed to compute commit activity: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
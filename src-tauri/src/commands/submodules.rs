@@ -0,0 +1,77 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::discovery;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::{Repo, SubmoduleInfo};
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn paths_result(result: anyhow::Result<Vec<String>>, verb: &str) -> serde_json::Value {
+    match result {
+        Ok(paths) => serde_json::json!({
+            "success": true,
+            "message": format!("{} {} submodule(s)", verb, paths.len()),
+            "output": paths.join("\n")
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": format!("{:#}", e)
+        }),
+    }
+}
+
+#[tauri::command]
+pub fn list_submodules(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<SubmoduleInfo>, String> {
+    open_repo(&repo, &config)?
+        .list_submodules()
+        .map_err(|e| format!("Failed to list submodules: {}", e))
+}
+
+#[tauri::command]
+pub fn init_submodules(
+    repo: String,
+    paths: Option<Vec<String>>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.init_submodules(&paths.unwrap_or_default());
+    Ok(paths_result(result, "Initialized"))
+}
+
+#[tauri::command]
+pub fn sync_submodules(
+    repo: String,
+    paths: Option<Vec<String>>,
+    recursive: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let result = git_repo.sync_submodules(&paths.unwrap_or_default(), recursive.unwrap_or(false));
+    Ok(paths_result(result, "Synced"))
+}
+
+#[tauri::command]
+pub fn open_submodule(
+    repo: String,
+    path: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Repo, String> {
+    let workdir = open_repo(&repo, &config)?
+        .submodule_workdir(&path)
+        .map_err(|e| e.to_string())?;
+    Ok(discovery::register(&workdir))
+}
@@ -0,0 +1,132 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::models::Tag;
+use gitpow_rust::utils::get_repo_path;
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+fn operation_result(result: anyhow::Result<String>, message: String) -> serde_json::Value {
+    match result {
+        Ok(output) => serde_json::json!({
+            "success": true,
+            "message": message,
+            "output": output
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn get_tags(
+    repo: String,
+    verify: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<Tag>, String> {
+    let repos_root = {
+        let config = config.lock().unwrap();
+        config.repos_root.clone()
+    };
+    let repo_path = get_repo_path(&repo, &repos_root);
+
+    tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .list_tags(verify.unwrap_or(false))
+            .map_err(|e| format!("Failed to list tags: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn create_tag(
+    repo: String,
+    name: String,
+    target: Option<String>,
+    message: Option<String>,
+    sign: Option<bool>,
+    force: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+
+    let name = name.trim().to_string();
+    let target = target
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let message = message.filter(|m| !m.trim().is_empty());
+
+    let result = git_repo.create_tag(
+        &name,
+        &target,
+        message.as_deref(),
+        sign.unwrap_or(false),
+        force.unwrap_or(false),
+    );
+    Ok(operation_result(
+        result,
+        format!("Created tag '{}' at {}", name, target),
+    ))
+}
+
+#[tauri::command]
+pub async fn delete_tag(
+    repo: String,
+    name: String,
+    remote: Option<String>,
+    local: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+
+    let local = local.unwrap_or(true);
+    let remote = remote.filter(|r| !r.is_empty());
+    if !local && remote.is_none() {
+        return Err("remote is required when local=false".to_string());
+    }
+
+    // Deleting on a remote may wait on a credentials prompt answered from the UI
+    tokio::task::spawn_blocking(move || {
+        let result = git_repo.delete_tag(&name, local, remote.as_deref());
+        operation_result(result, format!("Deleted tag '{}'", name))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+#[tauri::command]
+pub async fn push_tags(
+    repo: String,
+    remote: Option<String>,
+    names: Option<Vec<String>>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+
+    let names = names.unwrap_or_default();
+    let remote = remote
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "origin".to_string());
+
+    tokio::task::spawn_blocking(move || {
+        let result = git_repo.push_tags(&remote, &names);
+        operation_result(result, format!("Pushed tags to '{}'", remote))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
@@ -0,0 +1,119 @@
+use gitpow_rust::config::Config;
+use gitpow_rust::git::discovery;
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::worktrees::AddWorktreeOptions;
+use gitpow_rust::models::{Repo, WorktreeInfo};
+use gitpow_rust::utils::get_repo_path;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+fn open_repo(repo: &str, config: &State<'_, Mutex<Config>>) -> Result<GitRepository, String> {
+    let config = config.lock().unwrap();
+    let repo_path = get_repo_path(repo, &config.repos_root);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err("Repository not found".to_string());
+    }
+
+    GitRepository::open(&repo_path).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AddWorktreeParams {
+    path: Option<String>,
+    branch: Option<String>,
+    new_branch: Option<String>,
+    start_point: Option<String>,
+}
+
+#[tauri::command]
+pub fn list_worktrees(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Vec<WorktreeInfo>, String> {
+    open_repo(&repo, &config)?
+        .list_worktrees()
+        .map_err(|e| format!("Failed to list worktrees: {}", e))
+}
+
+#[tauri::command]
+pub fn add_worktree(
+    repo: String,
+    options: Option<AddWorktreeParams>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Repo, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    let params = options.unwrap_or_default();
+    let opts = AddWorktreeOptions {
+        path: params
+            .path
+            .filter(|p| !p.trim().is_empty())
+            .map(PathBuf::from),
+        branch: params.branch,
+        new_branch: params.new_branch,
+        start_point: params.start_point,
+    };
+    let path = git_repo.add_worktree(&opts).map_err(|e| e.to_string())?;
+    Ok(discovery::register(&path))
+}
+
+#[tauri::command]
+pub fn remove_worktree(
+    repo: String,
+    path: String,
+    force: Option<bool>,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    match git_repo.remove_worktree(&path, force.unwrap_or(false)) {
+        Ok(output) => Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Removed worktree {}", path),
+            "output": output
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+#[tauri::command]
+pub fn prune_worktrees(
+    repo: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<serde_json::Value, String> {
+    let git_repo = open_repo(&repo, &config)?;
+    match git_repo.prune_worktrees() {
+        Ok(pruned) => Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Pruned {} worktree(s)", pruned.len()),
+            "output": pruned.join("\n")
+        })),
+        Err(e) => Ok(serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })),
+    }
+}
+
+#[tauri::command]
+pub fn open_worktree(
+    repo: String,
+    path: String,
+    config: State<'_, Mutex<Config>>,
+) -> Result<Repo, String> {
+    let worktree = open_repo(&repo, &config)?
+        .find_linked_worktree(&path)
+        .map_err(|e| e.to_string())?;
+    if worktree.prunable {
+        return Err(format!(
+            "The worktree folder {} no longer exists",
+            worktree.path
+        ));
+    }
+    Ok(discovery::register(Path::new(&worktree.path)))
+}
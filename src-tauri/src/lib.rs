@@ -1,6 +1,8 @@
 use gitpow_rust::config::Config;
+use gitpow_rust::git::jobs;
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{Emitter, State};
+use tokio::sync::broadcast::error::RecvError;
 
 mod commands;
 
@@ -27,30 +29,78 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .manage(Mutex::new(config))
+        .setup(|app| {
+            // Forward background job progress to the webview as `job-progress` events
+            let handle = app.handle().clone();
+            let mut events = jobs::subscribe();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(info) => {
+                            let _ = handle.emit("job-progress", info);
+                        }
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Config
             commands::repos::get_config,
             // Repos
             commands::repos::get_repos,
+            commands::repos::init_repo,
+            commands::repos::rescan_repos,
+            commands::repos::get_dashboard,
             commands::browse::browse_projects_root,
             // Branches
             commands::branches::get_branches,
             commands::branches::get_branch_ahead_behind,
             commands::branches::get_branch_creation,
             commands::branches::get_branch_status,
+            commands::operations::get_operation,
+            commands::operations::continue_operation,
+            commands::operations::skip_operation,
+            commands::operations::abort_operation,
+            commands::journal::get_journal,
+            commands::journal::undo,
+            commands::reflog::get_reflog,
+            commands::reflog::restore_reflog_entry,
+            commands::reflog::get_lost_commits,
+            commands::reflog::recover_commit,
+            commands::reset::reset_preview,
+            commands::reset::reset,
+            commands::reset::restore_file,
+            commands::bisect::get_bisect,
+            commands::bisect::start_bisect,
+            commands::bisect::mark_bisect,
+            commands::bisect::reset_bisect,
             // Commits
             commands::commits::get_commits,
             commands::commits::get_commits_all_branches,
             commands::commits::get_commits_between,
             commands::commits::get_commit_metrics,
-            commands::commits::get_tags,
+            // Stats
+            commands::stats::get_contributor_stats,
+            commands::stats::get_churn_stats,
+            commands::stats::get_commit_activity,
+            // Tags
+            commands::tags::get_tags,
+            commands::tags::create_tag,
+            commands::tags::delete_tag,
+            commands::tags::push_tags,
             // Files
             commands::files::get_files,
+            commands::files::get_tree,
             commands::files::get_commit_files,
             commands::files::get_file,
             commands::files::get_file_creation,
             commands::files::get_file_creation_batch,
             commands::files::get_image,
+            // Search
+            commands::search::search_code,
             // Diff
             commands::diff::get_diff,
             // Staging
@@ -60,11 +110,52 @@ pub fn run() {
             commands::staging::commit,
             // Fetch
             commands::fetch::fetch_repo,
+            // Credentials
+            commands::credentials::list_credentials,
+            commands::credentials::save_credential,
+            commands::credentials::delete_credential,
+            commands::credentials::get_credential_prompts,
+            commands::credentials::answer_credential_prompt,
+            commands::credentials::cancel_credential_prompt,
+            // Remotes
+            commands::remotes::list_remotes,
+            commands::remotes::add_remote,
+            commands::remotes::remove_remote,
+            commands::remotes::rename_remote,
+            commands::remotes::set_remote_url,
+            commands::remotes::set_remote_refspecs,
+            commands::remotes::fetch_remote,
+            commands::remotes::get_stale_branches,
+            commands::submodules::list_submodules,
+            commands::submodules::init_submodules,
+            commands::submodules::sync_submodules,
+            commands::submodules::open_submodule,
+            commands::worktrees::list_worktrees,
+            commands::worktrees::add_worktree,
+            commands::worktrees::remove_worktree,
+            commands::worktrees::prune_worktrees,
+            commands::worktrees::open_worktree,
+            // Background jobs
+            commands::jobs::list_jobs,
+            commands::jobs::get_job,
+            commands::jobs::cancel_job,
+            commands::jobs::start_fetch_job,
+            commands::jobs::start_fetch_all_job,
+            commands::jobs::start_submodule_update_job,
+            commands::jobs::start_pull_job,
+            commands::jobs::start_bisect_job,
+            commands::jobs::start_push_job,
+            commands::jobs::start_clone_job,
             // Git Operations
             commands::git_ops::pull_repo,
             commands::git_ops::push_repo,
+            commands::git_ops::push_preview,
+            commands::git_ops::delete_remote_branch,
             commands::git_ops::stash_push,
             commands::git_ops::stash_pop,
+            commands::git_ops::stash_show,
+            commands::git_ops::stash_branch,
+            commands::git_ops::stash_rename,
             commands::git_ops::checkout_commit,
             commands::git_ops::checkout_branch,
             commands::git_ops::get_previous_branch,
@@ -76,6 +167,11 @@ pub fn run() {
             commands::conflicts::get_conflicts,
             commands::conflicts::get_conflict_file,
             commands::conflicts::resolve_conflict,
+            commands::conflicts::resolve_conflict_regions,
+            commands::conflicts::auto_resolve_conflicts,
+            commands::conflicts::set_rerere,
+            commands::conflicts::get_conflict_strategies,
+            commands::conflicts::save_conflict_strategies,
             // Explorer
             commands::explorer::open_explorer,
         ])
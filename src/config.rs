@@ -5,6 +5,8 @@ use std::path::PathBuf;
 pub struct Config {
     pub repos_root: PathBuf,
     pub port: String,
+    /// How many folder levels below `repos_root` are searched for repositories.
+    pub repo_scan_depth: usize,
 }
 
 impl Config {
@@ -20,6 +22,27 @@ impl Config {
 
         let port = env::var("PORT").unwrap_or_else(|_| "3000".to_string());
 
-        Self { repos_root, port }
+        let repo_scan_depth = env::var("REPO_SCAN_DEPTH")
+            .ok()
+            .and_then(|depth| depth.parse().ok())
+            .unwrap_or(3);
+
+        Self {
+            repos_root,
+            port,
+            repo_scan_depth,
+        }
+    }
+}
+
+/// Directory for GitPow's own state (remembered credentials, etc.).
+/// `GITPOW_DATA_DIR` overrides the default of `~/.gitpow`.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("GITPOW_DATA_DIR") {
+        return Some(PathBuf::from(dir));
     }
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .map(|home| PathBuf::from(home).join(".gitpow"))
 }
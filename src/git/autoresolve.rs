@@ -0,0 +1,294 @@
+use anyhow::{bail, Context, Result};
+use git2::{Pathspec, PathspecFlags, RepositoryState};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::repository::GitRepository;
+use crate::config;
+use crate::models::{
+    AutoResolveResponse, AutoResolvedFile, ConflictFile, ConflictStrategyRule, ConflictsResponse,
+    RegionResolution,
+};
+use crate::utils::write_atomic;
+
+/// Auto-resolved files of the operation in progress, kept in the git dir so
+/// they can still be listed after they were staged.
+const RECORD_FILE: &str = "GITPOW_AUTO_RESOLVED";
+
+fn strategies_path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join("conflict-strategies.json"))
+}
+
+/// Conflict strategy rules from the GitPow config, in priority order.
+pub fn conflict_strategies() -> Vec<ConflictStrategyRule> {
+    let Some(content) = strategies_path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable conflict strategies: {}", e);
+        Vec::new()
+    })
+}
+
+/// Replace the conflict strategy rules.
+pub fn save_conflict_strategies(rules: &[ConflictStrategyRule]) -> Result<()> {
+    for rule in rules {
+        if rule.pattern.trim().is_empty() {
+            bail!("Every rule needs a pattern");
+        }
+        match rule.strategy.as_str() {
+            "ours" | "theirs" | "union" => {}
+            "command"
+                if rule
+                    .command
+                    .as_deref()
+                    .is_some_and(|c| !c.trim().is_empty()) => {}
+            "command" => bail!("The rule for '{}' needs a command", rule.pattern),
+            other => bail!(
+                "Unknown strategy '{}' (expected ours, theirs, union or command)",
+                other
+            ),
+        }
+    }
+    let Some(path) = strategies_path() else {
+        bail!("No home directory to store settings in");
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    write_atomic(&path, serde_json::to_string_pretty(rules)?.as_bytes())?;
+    Ok(())
+}
+
+fn rule_matches(rule: &ConflictStrategyRule, path: &str) -> bool {
+    let pattern = rule.pattern.trim().trim_start_matches('/');
+    let Ok(spec) = Pathspec::new([pattern]) else {
+        return false;
+    };
+    // Like .gitignore, a pattern without '/' matches the file name anywhere.
+    let target = if pattern.contains('/') {
+        Path::new(path)
+    } else {
+        Path::new(path.rsplit('/').next().unwrap_or(path))
+    };
+    spec.matches_path(target, PathspecFlags::DEFAULT)
+}
+
+impl GitRepository {
+    /// Conflicted files plus what was auto-resolved during the current
+    /// operation.
+    pub fn conflicts(&self) -> Result<ConflictsResponse> {
+        let files = self.conflicted_files()?;
+        let rerere_enabled = self.rerere_enabled();
+
+        let mut auto_resolved = if self.repo.state() == RepositoryState::Clean {
+            let _ = std::fs::remove_file(self.repo.path().join(RECORD_FILE));
+            Vec::new()
+        } else {
+            // Entries still unmerged are left over from an earlier, aborted operation.
+            self.read_auto_resolved()
+                .into_iter()
+                .filter(|r| !files.iter().any(|f| f.path == r.path))
+                .collect()
+        };
+        if rerere_enabled {
+            for path in self.rerere_resolved(&files)? {
+                if !auto_resolved.iter().any(|f| f.path == path) {
+                    auto_resolved.push(rerere_entry(path));
+                }
+            }
+        }
+
+        Ok(ConflictsResponse {
+            has_conflicts: !files.is_empty(),
+            files,
+            auto_resolved,
+            rerere_enabled,
+        })
+    }
+
+    /// Apply recorded rerere resolutions, then the first matching strategy
+    /// rule to each remaining conflicted file.
+    pub fn auto_resolve_conflicts(&self) -> Result<AutoResolveResponse> {
+        let mut resolved = Vec::new();
+        let mut failed = Vec::new();
+
+        let mut files = self.conflicted_files()?;
+        if self.rerere_enabled() {
+            // Records preimages and replays known resolutions into the worktree.
+            self.run_git(&["rerere"])?;
+            let replayed = self.rerere_resolved(&files)?;
+            files.retain(|f| !replayed.contains(&f.path));
+            resolved.extend(replayed.into_iter().map(rerere_entry));
+        }
+
+        let rules = conflict_strategies();
+        for file in &files {
+            let Some(rule) = rules.iter().find(|r| rule_matches(r, &file.path)) else {
+                continue;
+            };
+            let mut entry = AutoResolvedFile {
+                path: file.path.clone(),
+                method: rule.strategy.clone(),
+                pattern: Some(rule.pattern.clone()),
+                staged: false,
+                needs_review: false,
+                error: None,
+            };
+            match self.apply_strategy(rule, file) {
+                Ok(()) => {
+                    entry.staged = true;
+                    resolved.push(entry);
+                }
+                Err(e) => {
+                    entry.error = Some(format!("{:#}", e));
+                    failed.push(entry);
+                }
+            }
+        }
+
+        self.record_auto_resolved(&resolved)?;
+        let mut remaining = self.conflicted_files()?;
+        remaining.retain(|f| !resolved.iter().any(|r| r.path == f.path));
+        Ok(AutoResolveResponse {
+            resolved,
+            failed,
+            remaining,
+        })
+    }
+
+    /// Turn git's rerere (reuse recorded resolution) on or off for this repository.
+    pub fn set_rerere_enabled(&self, enabled: bool) -> Result<()> {
+        let mut config = self.repo.config()?.open_level(git2::ConfigLevel::Local)?;
+        config.set_bool("rerere.enabled", enabled)?;
+        Ok(())
+    }
+
+    /// Whether git records and replays conflict resolutions here.
+    pub fn rerere_enabled(&self) -> bool {
+        match self
+            .repo
+            .config()
+            .and_then(|c| c.get_bool("rerere.enabled"))
+        {
+            Ok(enabled) => enabled,
+            // Unset: git enables rerere when the rr-cache folder exists.
+            Err(_) => self.repo.commondir().join("rr-cache").is_dir(),
+        }
+    }
+
+    fn apply_strategy(&self, rule: &ConflictStrategyRule, file: &ConflictFile) -> Result<()> {
+        match rule.strategy.as_str() {
+            "ours" | "theirs" => self.resolve_conflict_side(&file.path, &rule.strategy),
+            "union" => {
+                if file.r#type != "both-modified" && file.r#type != "both-added" {
+                    bail!("A union merge needs both sides ({})", file.r#type);
+                }
+                // Keep both sides of the markers as they stand in the working
+                // file, so edits made there since the merge survive.
+                let regions = self.conflict_file(&file.path)?.regions.len();
+                if regions == 0 {
+                    bail!("No conflict markers left; it was edited by hand");
+                }
+                let resolutions: Vec<RegionResolution> = (0..regions)
+                    .map(|index| RegionResolution {
+                        index,
+                        choice: "ours-theirs".to_string(),
+                        text: None,
+                    })
+                    .collect();
+                self.resolve_conflict_regions(&file.path, &resolutions)?;
+                Ok(())
+            }
+            "command" => {
+                let command = rule
+                    .command
+                    .as_deref()
+                    .filter(|c| !c.trim().is_empty())
+                    .context("The rule has no command")?;
+                let side = if file.r#type == "deleted-by-us" {
+                    "theirs"
+                } else {
+                    "ours"
+                };
+                self.run_git(&["checkout", &format!("--{}", side), "--", &file.path])?;
+                // The path comes from the repository, so it only reaches the
+                // command through the environment, never the command line.
+                self.run_shell(command, &file.path)?;
+                if self.path().join(&file.path).exists() {
+                    self.run_git(&["add", "--", &file.path])?;
+                } else {
+                    self.run_git(&["rm", "--quiet", "--", &file.path])?;
+                }
+                Ok(())
+            }
+            other => bail!("Unknown strategy '{}'", other),
+        }
+    }
+
+    fn run_shell(&self, command: &str, conflict_path: &str) -> Result<()> {
+        let output = self
+            .shell_command(command)
+            .env("GITPOW_CONFLICT_PATH", conflict_path)
+            .output()
+            .with_context(|| format!("Failed to run '{}'", command))?;
+        if !output.status.success() {
+            bail!(
+                "'{}' failed: {}",
+                command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Conflicted paths whose markers rerere has already replaced.
+    fn rerere_resolved(&self, files: &[ConflictFile]) -> Result<Vec<String>> {
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+        let remaining: HashSet<String> = self
+            .run_git(&["rerere", "remaining"])?
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+        Ok(files
+            .iter()
+            .filter(|f| !remaining.contains(&f.path))
+            .map(|f| f.path.clone())
+            .collect())
+    }
+
+    fn read_auto_resolved(&self) -> Vec<AutoResolvedFile> {
+        std::fs::read_to_string(self.repo.path().join(RECORD_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn record_auto_resolved(&self, files: &[AutoResolvedFile]) -> Result<()> {
+        // rerere results are detected live, since the user may still change them.
+        let mut record: Vec<AutoResolvedFile> = self
+            .read_auto_resolved()
+            .into_iter()
+            .filter(|f| !files.iter().any(|n| n.path == f.path))
+            .collect();
+        record.extend(files.iter().filter(|f| f.method != "rerere").cloned());
+        write_atomic(
+            &self.repo.path().join(RECORD_FILE),
+            serde_json::to_string(&record)?.as_bytes(),
+        )?;
+        Ok(())
+    }
+}
+
+fn rerere_entry(path: String) -> AutoResolvedFile {
+    AutoResolvedFile {
+        path,
+        method: "rerere".to_string(),
+        pattern: None,
+        staged: false,
+        needs_review: true,
+        error: None,
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{bail, Context, Result};
+use git2::RepositoryState;
+use std::collections::HashSet;
+use std::fs;
+use std::process::Stdio;
+use std::time::Duration;
+
+use super::jobs;
+use super::repository::{commit_from_git2, GitRepository};
+use crate::models::BisectState;
+
+/// How often a running test command is checked for completion or cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+impl GitRepository {
+    /// Start bisecting between a bad commit and known good ones, optionally
+    /// only testing commits that touch `paths`. git checks out the first
+    /// commit to test.
+    pub fn bisect_start(
+        &self,
+        bad: &str,
+        good: &[String],
+        paths: &[String],
+    ) -> Result<BisectState> {
+        if self.repo.state() != RepositoryState::Clean {
+            bail!("Finish or abort the operation in progress before bisecting");
+        }
+        let mut args = vec!["bisect", "start", bad];
+        args.extend(good.iter().map(|g| g.as_str()));
+        args.push("--");
+        args.extend(paths.iter().map(|p| p.as_str()));
+        self.run_git(&args)?;
+        self.bisect_state()?.context("The bisect did not start")
+    }
+
+    /// Mark a commit (default: the one checked out) "good", "bad" or "skip";
+    /// git then checks out the next one to test.
+    pub fn bisect_mark(&self, term: &str, commit: Option<&str>) -> Result<BisectState> {
+        if !matches!(term, "good" | "bad" | "skip") {
+            bail!(
+                "Unknown bisect mark '{}' (expected good, bad or skip)",
+                term
+            );
+        }
+        if self.repo.state() != RepositoryState::Bisect {
+            bail!("No bisect is in progress");
+        }
+        let mut args = vec!["bisect", term];
+        args.extend(commit);
+        self.run_git(&args)?;
+        self.bisect_state()?.context("No bisect is in progress")
+    }
+
+    /// End the bisect and check out what was checked out before it.
+    pub fn bisect_reset(&self) -> Result<String> {
+        self.run_git(&["bisect", "reset"])
+    }
+
+    /// The bisect in progress, if any: marked commits, what is left to test
+    /// and, once found, the first bad commit with its changed files.
+    pub fn bisect_state(&self) -> Result<Option<BisectState>> {
+        if self.repo.state() != RepositoryState::Bisect {
+            return Ok(None);
+        }
+        let git_dir = self.repo.path();
+        let ref_target = |name: &str| {
+            self.repo
+                .find_reference(name)
+                .ok()
+                .and_then(|r| r.target())
+                .map(|o| o.to_string())
+        };
+        let marked = |prefix: &str| -> Result<Vec<String>> {
+            let mut shas = Vec::new();
+            for reference in self
+                .repo
+                .references_glob(&format!("refs/bisect/{}-*", prefix))?
+            {
+                if let Some(target) = reference?.target() {
+                    shas.push(target.to_string());
+                }
+            }
+            Ok(shas)
+        };
+
+        let mut state = BisectState {
+            original_head: fs::read_to_string(git_dir.join("BISECT_START"))
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+            bad: ref_target("refs/bisect/bad"),
+            good: marked("good")?,
+            skipped: marked("skip")?,
+            // Newer git versions write the "--" separator too.
+            paths: fs::read_to_string(git_dir.join("BISECT_NAMES"))
+                .map(|names| sq_dequote(&names))
+                .unwrap_or_default()
+                .into_iter()
+                .skip_while(|p| p == "--")
+                .collect(),
+            current: self
+                .repo
+                .head()
+                .ok()
+                .and_then(|h| h.target())
+                .map(|o| o.to_string()),
+            ..Default::default()
+        };
+
+        let Some(bad) = state.bad.clone() else {
+            return Ok(Some(state));
+        };
+        if state.good.is_empty() {
+            return Ok(Some(state));
+        }
+
+        // Commits between the good ones and the bad one, limited to the paths.
+        let mut args = vec!["rev-list".to_string(), bad.clone(), "--not".to_string()];
+        args.extend(state.good.iter().cloned());
+        args.push("--".to_string());
+        args.extend(state.paths.iter().cloned());
+        let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        let skipped: HashSet<&str> = state.skipped.iter().map(|s| s.as_str()).collect();
+        let candidates: Vec<String> = self
+            .run_git(&args)?
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty() && *l != bad)
+            .collect();
+
+        let untested = candidates
+            .iter()
+            .filter(|c| !skipped.contains(c.as_str()))
+            .count();
+        state.remaining = untested;
+        state.estimated_steps = (untested + 1).next_power_of_two().trailing_zeros() as usize;
+
+        if untested == 0 {
+            let possible: Vec<String> = candidates
+                .into_iter()
+                .filter(|c| skipped.contains(c.as_str()))
+                .collect();
+            if possible.is_empty() {
+                let commit = self.repo.revparse_single(&bad)?.peel_to_commit()?;
+                state.first_bad_files = self.get_commit_changed_files(&bad)?;
+                state.first_bad = Some(commit_from_git2(&commit, Vec::new()));
+            } else {
+                state.possible_first_bad = possible;
+                state.possible_first_bad.push(bad);
+            }
+        }
+        Ok(Some(state))
+    }
+
+    /// Bisect automatically: run `command` on each commit git checks out and
+    /// mark it good (exit 0), skip (125) or bad (1-127), like `git bisect run`.
+    /// Stops when the first bad commit is found or the job is cancelled.
+    pub fn bisect_run(&self, command: &str) -> Result<(String, BisectState)> {
+        let mut log = String::new();
+        jobs::set_stage("bisecting");
+        loop {
+            let state = self.bisect_state()?.context("No bisect is in progress")?;
+            if state.bad.is_none() || state.good.is_empty() {
+                bail!("Mark a good and a bad commit before running the bisect");
+            }
+            if state.remaining == 0 {
+                return Ok((log, state));
+            }
+            let current = state.current.unwrap_or_default();
+
+            let mut child = self
+                .shell_command(command)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .with_context(|| format!("Failed to run '{}'", command))?;
+            let status = loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+                if jobs::is_cancelled() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    bail!("Cancelled");
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            };
+
+            let term = match status.code() {
+                Some(0) => "good",
+                Some(125) => "skip",
+                Some(1..=127) => "bad",
+                Some(code) => bail!(
+                    "'{}' exited with {} on {}; stopping the bisect run",
+                    command,
+                    code,
+                    current
+                ),
+                None => bail!(
+                    "'{}' was killed on {}; stopping the bisect run",
+                    command,
+                    current
+                ),
+            };
+            log.push_str(&format!("{} {}\n", current, term));
+            self.run_git(&["bisect", term])?;
+        }
+    }
+}
+
+/// Undo git's shell quoting of BISECT_NAMES: `'a' 'it'\''s'`.
+fn sq_dequote(quoted: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = quoted.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    arg.push(c);
+                }
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            c if c.is_whitespace() => args.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
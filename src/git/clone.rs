@@ -0,0 +1,291 @@
+use anyhow::{bail, Context, Result};
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{FetchOptions, RepositoryInitOptions};
+use std::io::Read;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use super::credentials::CredentialSession;
+use super::jobs;
+use super::repository::GitRepository;
+
+/// Options for cloning
+#[derive(Debug, Default)]
+pub struct CloneOptions {
+    /// Branch to check out instead of the remote's HEAD.
+    pub branch: Option<String>,
+    /// Shallow clone with this many commits of history.
+    pub depth: Option<u32>,
+    /// Partial clone filter, e.g. "blob:none" or "tree:0".
+    pub filter: Option<String>,
+    /// Also clone and check out submodules, recursively.
+    pub recurse_submodules: bool,
+}
+
+/// Options for creating a new repository
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    /// Name of the first branch; defaults to init.defaultBranch, then "main".
+    pub default_branch: Option<String>,
+    /// Commit the generated files right away.
+    pub initial_commit: bool,
+    /// .gitignore template: "rust", "node", "python", "go", "java", "dotnet" or "macos".
+    pub gitignore: Option<String>,
+    /// Write a README.md with the repository name as its title.
+    pub readme: bool,
+}
+
+/// Destination for a new repository directly under `repos_root`, where
+/// `get_repos` picks it up.
+pub fn new_repo_dir(repos_root: &Path, name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty()
+        || name.contains(['/', '\\'])
+        || name == "."
+        || name == ".."
+        || name.starts_with('.')
+    {
+        bail!("'{}' is not a valid folder name", name);
+    }
+    let dest = repos_root.join(name);
+    if dest.exists() {
+        bail!("'{}' already exists in the repos folder", name);
+    }
+    Ok(dest)
+}
+
+impl GitRepository {
+    /// Clone `url` into `dest`. Clones go through libgit2 with the credential
+    /// providers; libgit2 can't do partial clones or shallow clones over its
+    /// local transport, so those run `git clone` and use git's own credential
+    /// helpers. A failed or cancelled clone removes the partially written directory.
+    pub fn clone_from(url: &str, dest: &Path, opts: &CloneOptions) -> Result<GitRepository> {
+        let use_git_cli = opts.filter.is_some() || (opts.depth.is_some() && is_local_url(url));
+        let result = if use_git_cli {
+            clone_with_git(url, dest, opts)
+        } else {
+            clone_with_libgit2(url, dest, opts)
+        };
+        if let Err(e) = result {
+            if dest.exists() {
+                let _ = std::fs::remove_dir_all(dest);
+            }
+            return Err(e);
+        }
+        GitRepository::open(dest)
+    }
+
+    /// Create an empty repository at `dest`, optionally with a .gitignore
+    /// and README and a first commit of them.
+    pub fn init_at(dest: &Path, opts: &InitOptions) -> Result<GitRepository> {
+        let branch = match opts
+            .default_branch
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+        {
+            Some(branch) => branch.to_string(),
+            None => git2::Config::open_default()
+                .and_then(|mut c| c.snapshot())
+                .and_then(|c| c.get_string("init.defaultBranch"))
+                .unwrap_or_else(|_| "main".to_string()),
+        };
+        if !git2::Reference::is_valid_name(&format!("refs/heads/{}", branch)) {
+            bail!("'{}' is not a valid branch name", branch);
+        }
+        let gitignore = match opts.gitignore.as_deref().filter(|t| !t.is_empty()) {
+            Some(template) => Some(gitignore_template(template)?),
+            None => None,
+        };
+
+        let mut init = RepositoryInitOptions::new();
+        init.initial_head(&branch);
+        git2::Repository::init_opts(dest, &init)
+            .with_context(|| format!("Failed to create repository at {}", dest.display()))?;
+
+        let result = (|| -> Result<GitRepository> {
+            if let Some(gitignore) = gitignore {
+                std::fs::write(dest.join(".gitignore"), gitignore)?;
+            }
+            if opts.readme {
+                let name = dest.file_name().unwrap_or_default().to_string_lossy();
+                std::fs::write(dest.join("README.md"), format!("# {}\n", name))?;
+            }
+            let repo = GitRepository::open(dest)?;
+            if opts.initial_commit {
+                // Through git so the author comes from the same config and
+                // environment as other commits made in GitPow.
+                repo.run_git(&["add", "--all"])?;
+                repo.run_git(&["commit", "--allow-empty", "-m", "Initial commit"])?;
+            }
+            Ok(repo)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_dir_all(dest);
+        }
+        result
+    }
+}
+
+fn clone_with_libgit2(url: &str, dest: &Path, opts: &CloneOptions) -> Result<()> {
+    let session = CredentialSession::without_repo(true);
+    let result = {
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(session.callbacks());
+        if let Some(depth) = opts.depth {
+            fetch_options.depth(depth.min(i32::MAX as u32) as i32);
+        }
+        let mut checkout = CheckoutBuilder::new();
+        checkout.progress(|_, _, _| jobs::set_stage("checkout"));
+        let mut builder = RepoBuilder::new();
+        builder.fetch_options(fetch_options).with_checkout(checkout);
+        if let Some(branch) = &opts.branch {
+            builder.branch(branch);
+        }
+        builder.clone(url, dest)
+    };
+    if let (Err(e), Some(branch)) = (&result, &opts.branch) {
+        if e.code() == git2::ErrorCode::NotFound && e.class() == git2::ErrorClass::Reference {
+            bail!("Branch '{}' not found on the remote", branch);
+        }
+    }
+    session.finish(result)?;
+    if opts.recurse_submodules {
+        GitRepository::open(dest)?.update_submodules(&[], true, true)?;
+    }
+    Ok(())
+}
+
+/// Run `git clone --progress`, feeding its progress lines into the job and
+/// killing it when the job is cancelled.
+fn clone_with_git(url: &str, dest: &Path, opts: &CloneOptions) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--progress"]);
+    if let Some(branch) = &opts.branch {
+        cmd.args(["--branch", branch]);
+    }
+    if let Some(depth) = opts.depth {
+        cmd.arg(format!("--depth={}", depth));
+    }
+    if let Some(filter) = &opts.filter {
+        cmd.arg(format!("--filter={}", filter));
+    }
+    if opts.recurse_submodules {
+        cmd.arg("--recurse-submodules");
+    }
+    // --depth is ignored for plain paths; file:// makes git honour it.
+    let url = if is_local_url(url) && !url.starts_with("file://") {
+        let path = url.replace('\\', "/");
+        if path.starts_with('/') {
+            format!("file://{}", path)
+        } else {
+            format!("file:///{}", path)
+        }
+    } else {
+        url.to_string()
+    };
+    cmd.arg("--")
+        .arg(&url)
+        .arg(dest)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let mut child = cmd.spawn().context("Failed to run git clone")?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("Failed to read git clone output")?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // Progress lines are redrawn with \r, so split on both.
+        let mut buffer = [0u8; 4096];
+        let mut pending = String::new();
+        while let Ok(n) = stderr.read(&mut buffer) {
+            if n == 0 {
+                break;
+            }
+            pending.push_str(&String::from_utf8_lossy(&buffer[..n]));
+            while let Some(end) = pending.find(['\r', '\n']) {
+                let line: String = pending.drain(..=end).collect();
+                let line = line.trim();
+                if !line.is_empty() && sender.send(line.to_string()).is_err() {
+                    return;
+                }
+            }
+        }
+        if !pending.trim().is_empty() {
+            let _ = sender.send(pending.trim().to_string());
+        }
+    });
+
+    let mut errors = Vec::new();
+    loop {
+        match receiver.recv_timeout(Duration::from_millis(200)) {
+            Ok(line) => {
+                if line.starts_with("fatal:") || line.starts_with("error:") {
+                    errors.push(line.clone());
+                }
+                jobs::report_git_progress(&line);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if jobs::is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("Cancelled");
+        }
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        if errors.is_empty() {
+            bail!("git clone failed ({})", status);
+        }
+        bail!(errors.join("\n"));
+    }
+    Ok(())
+}
+
+/// file:// URLs and paths, as opposed to URLs and scp-like `host:path`.
+fn is_local_url(url: &str) -> bool {
+    if url.starts_with("file://") {
+        return true;
+    }
+    if url.contains("://") {
+        return false;
+    }
+    match url.find(':') {
+        // "C:\repo" is a Windows path, "host:repo" is ssh
+        Some(colon) => colon == 1 || url.find(['/', '\\']).is_some_and(|slash| slash < colon),
+        None => true,
+    }
+}
+
+fn gitignore_template(name: &str) -> Result<&'static str> {
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "rust" => "/target\n**/*.rs.bk\n",
+        "node" => "node_modules/\ndist/\n.env\nnpm-debug.log*\nyarn-debug.log*\nyarn-error.log*\n",
+        "python" => "__pycache__/\n*.py[cod]\n.venv/\nvenv/\n.env\nbuild/\ndist/\n*.egg-info/\n",
+        "go" => "/bin/\n*.exe\n*.test\n*.out\nvendor/\n",
+        "java" => "*.class\n*.jar\ntarget/\nbuild/\n.gradle/\n",
+        "dotnet" => "bin/\nobj/\n*.user\n.vs/\n",
+        "macos" => ".DS_Store\n._*\n",
+        other => bail!(
+            "Unknown .gitignore template '{}' (expected rust, node, python, go, java, dotnet or macos)",
+            other
+        ),
+    })
+}
@@ -0,0 +1,366 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+
+use super::repository::GitRepository;
+use super::status::StatusOptions;
+use crate::models::{
+    ConflictChunk, ConflictFile, ConflictFileResponse, ConflictRegion, RegionResolution,
+    ResolveRegionsResponse,
+};
+
+/// A conflict block in the working file: line indexes of its markers and
+/// of each side's content.
+struct MarkerBlock {
+    start: usize,
+    end: usize,
+    ours: Section,
+    base: Option<Section>,
+    theirs: Section,
+}
+
+struct Section {
+    label: Option<String>,
+    /// First content line (0-based) and number of lines
+    first: usize,
+    count: usize,
+}
+
+impl GitRepository {
+    /// Files with unresolved conflicts and the real conflict kind, including
+    /// rename/rename conflicts.
+    pub fn conflicted_files(&self) -> Result<Vec<ConflictFile>> {
+        let status = self.status(&StatusOptions::default())?;
+        Ok(status
+            .files
+            .into_iter()
+            .filter_map(|file| {
+                let conflict = file.conflict?;
+                Some(ConflictFile {
+                    path: file.path,
+                    r#type: conflict.kind,
+                    renamed_from: conflict.renamed_from,
+                })
+            })
+            .collect())
+    }
+
+    /// The three stages and working copy of a conflicted file, with its
+    /// conflict regions parsed from the markers (merge, diff3 or zdiff3 style).
+    pub fn conflict_file(&self, path: &str) -> Result<ConflictFileResponse> {
+        let kind = self
+            .conflicted_files()?
+            .into_iter()
+            .find(|f| f.path == path)
+            .map(|f| f.r#type)
+            .unwrap_or_else(|| "resolved".to_string());
+
+        let full_path = self.path().join(path);
+        // :1: = base, :2: = ours, :3: = theirs
+        let base = self
+            .run_git(&["show", &format!(":1:{}", path)])
+            .unwrap_or_default();
+        let mine = self
+            .run_git(&["show", &format!(":2:{}", path)])
+            .unwrap_or_else(|_| fs::read_to_string(&full_path).unwrap_or_default());
+        let theirs = self
+            .run_git(&["show", &format!(":3:{}", path)])
+            .unwrap_or_default();
+        let result = fs::read_to_string(&full_path).unwrap_or_default();
+
+        let lines: Vec<&str> = result.split_inclusive('\n').collect();
+        let regions = parse_marker_blocks(&lines)
+            .iter()
+            .enumerate()
+            .map(|(index, block)| ConflictRegion {
+                index,
+                start_line: block.start + 1,
+                line_count: block.end - block.start,
+                ours: chunk(&lines, &block.ours),
+                base: block.base.as_ref().map(|s| chunk(&lines, s)),
+                theirs: chunk(&lines, &block.theirs),
+            })
+            .collect();
+
+        Ok(ConflictFileResponse {
+            base,
+            mine,
+            theirs,
+            result,
+            file_path: path.to_string(),
+            kind,
+            regions,
+        })
+    }
+
+    /// Replace conflict regions of the working file with the chosen side(s)
+    /// or custom text. The file is staged once no conflict markers remain.
+    pub fn resolve_conflict_regions(
+        &self,
+        path: &str,
+        resolutions: &[RegionResolution],
+    ) -> Result<ResolveRegionsResponse> {
+        let full_path = self.path().join(path);
+        let content =
+            fs::read_to_string(&full_path).with_context(|| format!("Failed to read {}", path))?;
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let blocks = parse_marker_blocks(&lines);
+
+        for resolution in resolutions {
+            if resolution.index >= blocks.len() {
+                bail!(
+                    "{} has {} conflict region(s); there is no region {}",
+                    path,
+                    blocks.len(),
+                    resolution.index
+                );
+            }
+        }
+
+        let mut output = String::with_capacity(content.len());
+        let mut remaining = 0;
+        let mut next_line = 0;
+        for (index, block) in blocks.iter().enumerate() {
+            output.extend(lines[next_line..block.start].iter().copied());
+            next_line = block.end;
+            let Some(resolution) = resolutions.iter().rev().find(|r| r.index == index) else {
+                output.extend(lines[block.start..block.end].iter().copied());
+                remaining += 1;
+                continue;
+            };
+            let side = |section: &Section| section_text(&lines, section);
+            match resolution.choice.as_str() {
+                "ours" => output.push_str(&side(&block.ours)),
+                "theirs" => output.push_str(&side(&block.theirs)),
+                "base" => match &block.base {
+                    Some(base) => output.push_str(&side(base)),
+                    None => bail!("Region {} has no base version; it was not written in diff3 style", index),
+                },
+                "ours-theirs" => {
+                    output.push_str(&side(&block.ours));
+                    output.push_str(&side(&block.theirs));
+                }
+                "theirs-ours" => {
+                    output.push_str(&side(&block.theirs));
+                    output.push_str(&side(&block.ours));
+                }
+                "custom" => {
+                    let text = resolution.text.as_deref().unwrap_or_default();
+                    output.push_str(text);
+                    if !text.is_empty() && !text.ends_with('\n') && lines[block.end - 1].ends_with('\n') {
+                        output.push('\n');
+                    }
+                }
+                other => bail!(
+                    "Unknown resolution '{}' (expected ours, theirs, base, ours-theirs, theirs-ours or custom)",
+                    other
+                ),
+            }
+        }
+        output.extend(lines[next_line..].iter().copied());
+
+        fs::write(&full_path, &output).with_context(|| format!("Failed to write {}", path))?;
+        let staged = remaining == 0;
+        if staged {
+            self.run_git(&["add", "--", path])?;
+        }
+        Ok(ResolveRegionsResponse {
+            success: true,
+            remaining,
+            staged,
+            result: output,
+        })
+    }
+
+    /// Resolve a whole file with one side. When that side deleted the file
+    /// (delete/modify conflicts), the path is removed with `git rm`.
+    pub fn resolve_conflict_side(&self, path: &str, side: &str) -> Result<()> {
+        let stage = match side {
+            "ours" => 2,
+            "theirs" => 3,
+            "delete" => {
+                self.run_git(&["rm", "--quiet", "--", path])?;
+                return Ok(());
+            }
+            other => bail!(
+                "Unknown resolution '{}' (expected ours, theirs or delete)",
+                other
+            ),
+        };
+        if self
+            .run_git(&["cat-file", "-e", &format!(":{}:{}", stage, path)])
+            .is_err()
+        {
+            // That side has no version of the file
+            self.run_git(&["rm", "--quiet", "--", path])?;
+            return Ok(());
+        }
+        self.run_git(&["checkout", &format!("--{}", side), "--", path])?;
+        self.run_git(&["add", "--", path])?;
+        Ok(())
+    }
+}
+
+/// Find `<<<<<<<` / `|||||||` / `=======` / `>>>>>>>` blocks. Unterminated
+/// blocks are ignored.
+fn parse_marker_blocks(lines: &[&str]) -> Vec<MarkerBlock> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(ours_label) = marker(lines[i], '<') else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        let mut base: Option<(Option<String>, usize)> = None;
+        let mut separator = None;
+        let mut end = None;
+        let mut j = i + 1;
+        while j < lines.len() {
+            if separator.is_none() {
+                if let Some(label) = marker(lines[j], '|') {
+                    base = Some((label, j));
+                } else if marker(lines[j], '=').is_some() {
+                    separator = Some(j);
+                } else if marker(lines[j], '<').is_some() {
+                    break;
+                }
+            } else if let Some(label) = marker(lines[j], '>') {
+                end = Some((label, j));
+                break;
+            }
+            j += 1;
+        }
+        let (Some(sep), Some((theirs_label, close))) = (separator, end) else {
+            i = j.max(i + 1);
+            continue;
+        };
+        let ours_end = base.as_ref().map_or(sep, |(_, b)| *b);
+        blocks.push(MarkerBlock {
+            start,
+            end: close + 1,
+            ours: Section {
+                label: ours_label,
+                first: start + 1,
+                count: ours_end - start - 1,
+            },
+            base: base.map(|(label, b)| Section {
+                label,
+                first: b + 1,
+                count: sep - b - 1,
+            }),
+            theirs: Section {
+                label: theirs_label,
+                first: sep + 1,
+                count: close - sep - 1,
+            },
+        });
+        i = close + 1;
+    }
+    blocks
+}
+
+/// Seven marker characters followed by end of line or a space and a label.
+fn marker(line: &str, c: char) -> Option<Option<String>> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let rest = line.strip_prefix(&c.to_string().repeat(7))?;
+    if rest.is_empty() {
+        Some(None)
+    } else if c != '=' {
+        rest.strip_prefix(' ').map(|label| Some(label.to_string()))
+    } else {
+        None
+    }
+}
+
+fn section_text(lines: &[&str], section: &Section) -> String {
+    lines[section.first..section.first + section.count].concat()
+}
+
+fn chunk(lines: &[&str], section: &Section) -> ConflictChunk {
+    ConflictChunk {
+        label: section.label.clone(),
+        start_line: section.first + 1,
+        line_count: section.count,
+        text: section_text(lines, section),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(content: &str) -> (Vec<&str>, Vec<MarkerBlock>) {
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let blocks = parse_marker_blocks(&lines);
+        (lines, blocks)
+    }
+
+    #[test]
+    fn merge_style_block() {
+        let (lines, found) = blocks(
+            "keep\n<<<<<<< HEAD\nours\n=======\ntheirs 1\ntheirs 2\n>>>>>>> feature\ntail\n",
+        );
+        assert_eq!(found.len(), 1);
+        let block = &found[0];
+        assert_eq!((block.start, block.end), (1, 7));
+        assert_eq!(block.ours.label.as_deref(), Some("HEAD"));
+        assert_eq!(section_text(&lines, &block.ours), "ours\n");
+        assert!(block.base.is_none());
+        assert_eq!(block.theirs.label.as_deref(), Some("feature"));
+        assert_eq!(section_text(&lines, &block.theirs), "theirs 1\ntheirs 2\n");
+    }
+
+    #[test]
+    fn diff3_base_section() {
+        let (lines, found) = blocks(
+            "<<<<<<< ours\na\n||||||| merged common ancestors\nbase\n=======\nb\n>>>>>>> theirs\n",
+        );
+        let block = &found[0];
+        assert_eq!(section_text(&lines, &block.ours), "a\n");
+        let base = block.base.as_ref().unwrap();
+        assert_eq!(base.label.as_deref(), Some("merged common ancestors"));
+        assert_eq!(section_text(&lines, base), "base\n");
+        assert_eq!(section_text(&lines, &block.theirs), "b\n");
+    }
+
+    #[test]
+    fn empty_sides_and_unlabeled_markers() {
+        let (lines, found) = blocks("<<<<<<<\n=======\nonly theirs\n>>>>>>>\n");
+        let block = &found[0];
+        assert_eq!(block.ours.label, None);
+        assert_eq!(block.ours.count, 0);
+        assert_eq!(section_text(&lines, &block.theirs), "only theirs\n");
+    }
+
+    #[test]
+    fn unterminated_and_restarted_blocks() {
+        // The first block never reaches a separator before a new one starts,
+        // and the last one is never closed: only the middle block counts.
+        let (lines, found) = blocks(
+            "<<<<<<< stray\nx\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> other\n<<<<<<< HEAD\nopen\n=======\n",
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start, 2);
+        assert_eq!(section_text(&lines, &found[0].ours), "ours\n");
+    }
+
+    #[test]
+    fn look_alike_lines_are_not_markers() {
+        let (_, found) = blocks("<<<<<<<< eight\n======= not a separator\n<<<<<<<x\n");
+        assert!(found.is_empty());
+        assert_eq!(marker("=======\n", '='), Some(None));
+        assert_eq!(marker("======= x\n", '='), None);
+        assert_eq!(marker("<<<<<<<<\n", '<'), None);
+    }
+
+    #[test]
+    fn crlf_markers() {
+        let (lines, found) =
+            blocks("a\r\n<<<<<<< HEAD\r\nours\r\n=======\r\ntheirs\r\n>>>>>>> other\r\n");
+        let block = &found[0];
+        assert_eq!(block.ours.label.as_deref(), Some("HEAD"));
+        assert_eq!(block.theirs.label.as_deref(), Some("other"));
+        assert_eq!(section_text(&lines, &block.ours), "ours\r\n");
+        assert_eq!(section_text(&lines, &block.theirs), "theirs\r\n");
+    }
+}
@@ -0,0 +1,767 @@
+use anyhow::{bail, Result};
+use base64::Engine;
+use chrono::Utc;
+use git2::{Cred, CredentialType, RemoteCallbacks};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::jobs;
+use crate::config;
+use crate::models::{CredentialAnswer, CredentialInfo, CredentialPrompt, SaveCredentialRequest};
+use crate::utils::write_atomic_private;
+
+/// How long one network operation waits for the UI to answer its credentials
+/// prompts, all of them together. Kept below the HTTP request timeout so the
+/// request can still report the failure.
+const PROMPT_BUDGET: Duration = Duration::from_secs(45);
+
+/// Prompts per operation before giving up, e.g. after repeatedly wrong passwords.
+const MAX_PROMPTS: usize = 3;
+
+/// Private keys ssh tries by default, in ssh's order (minus key types libssh2 can't use).
+const DEFAULT_KEY_NAMES: [&str; 4] = ["id_rsa", "id_ecdsa", "id_ed25519", "id_dsa"];
+
+/// Raised when every credential source was exhausted or the user didn't answer
+/// the prompt. Handlers can downcast to this to tell auth failures apart.
+#[derive(Debug)]
+pub struct CredentialsRequired {
+    pub url: String,
+    /// "password" for HTTP(S) remotes, "passphrase" or "ssh-key" for SSH.
+    pub kind: String,
+}
+
+impl fmt::Display for CredentialsRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Authentication required for {}: no {} was accepted. Answer the credentials prompt or save a token for this host.",
+            self.url, self.kind
+        )
+    }
+}
+
+impl std::error::Error for CredentialsRequired {}
+
+// ---------------------------------------------------------------------------
+// Token store
+// ---------------------------------------------------------------------------
+
+/// A secret saved by the user or remembered from an answered prompt.
+/// Tokens and passwords are keyed by host, passphrases by private key path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredCredential {
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    secret: String,
+    /// Written to disk; otherwise kept in memory until the app exits.
+    #[serde(skip)]
+    persisted: bool,
+}
+
+impl StoredCredential {
+    fn same_slot(&self, other: &StoredCredential) -> bool {
+        (self.kind == "passphrase") == (other.kind == "passphrase")
+            && self.host == other.host
+            && self.key_path == other.key_path
+            && self.username == other.username
+    }
+}
+
+static STORE: Lazy<Mutex<Vec<StoredCredential>>> = Lazy::new(|| Mutex::new(load_store()));
+
+fn store_path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join("credentials.json"))
+}
+
+fn load_store() -> Vec<StoredCredential> {
+    let Some(content) = store_path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<Vec<StoredCredential>>(&content) {
+        Ok(mut creds) => {
+            creds.iter_mut().for_each(|c| c.persisted = true);
+            creds
+        }
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable credential store: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn write_store(creds: &[StoredCredential]) -> Result<()> {
+    let Some(path) = store_path() else {
+        bail!("No home directory to store credentials in");
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let persisted: Vec<&StoredCredential> = creds.iter().filter(|c| c.persisted).collect();
+    let json = serde_json::to_string_pretty(&persisted)?;
+
+    // A torn write would read back as an empty store and lose every saved
+    // credential on the next save.
+    write_atomic_private(&path, json.as_bytes())?;
+    Ok(())
+}
+
+fn remember(credential: StoredCredential) -> Result<()> {
+    let mut store = STORE.lock().unwrap();
+    let persist = credential.persisted;
+    store.retain(|c| !c.same_slot(&credential));
+    store.push(credential);
+    if persist {
+        write_store(&store)?;
+    }
+    Ok(())
+}
+
+/// Save a token, password or key passphrase supplied by the user.
+pub fn save_credential(request: SaveCredentialRequest) -> Result<()> {
+    let kind = request.kind.unwrap_or_else(|| "token".to_string());
+    match kind.as_str() {
+        "token" | "password" if request.host.is_none() => bail!("host is required"),
+        "passphrase" if request.key_path.is_none() => bail!("keyPath is required"),
+        "token" | "password" | "passphrase" => {}
+        other => bail!("Unknown credential kind '{}'", other),
+    }
+    if request.secret.is_empty() {
+        bail!("secret is required");
+    }
+    remember(StoredCredential {
+        kind,
+        host: request.host,
+        key_path: request.key_path,
+        username: request.username.filter(|u| !u.is_empty()),
+        secret: request.secret,
+        persisted: request.remember.unwrap_or(true),
+    })
+}
+
+/// Forget saved credentials matching host or key path (and username, when given).
+/// Returns whether anything was removed.
+pub fn remove_credential(
+    host: Option<&str>,
+    key_path: Option<&str>,
+    username: Option<&str>,
+) -> Result<bool> {
+    let mut store = STORE.lock().unwrap();
+    let before = store.len();
+    store.retain(|c| {
+        let slot_matches = (host.is_some() && c.host.as_deref() == host)
+            || (key_path.is_some() && c.key_path.as_deref() == key_path);
+        !(slot_matches && (username.is_none() || c.username.as_deref() == username))
+    });
+    let removed = store.len() != before;
+    if removed {
+        write_store(&store)?;
+    }
+    Ok(removed)
+}
+
+/// Saved credentials without their secrets.
+pub fn list_credentials() -> Vec<CredentialInfo> {
+    STORE
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|c| CredentialInfo {
+            kind: c.kind.clone(),
+            host: c.host.clone(),
+            key_path: c.key_path.clone(),
+            username: c.username.clone(),
+            persisted: c.persisted,
+        })
+        .collect()
+}
+
+fn find_userpass(host: &str, username: Option<&str>) -> Option<(Option<String>, String)> {
+    STORE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|c| c.kind != "passphrase" && c.host.as_deref() == Some(host))
+        .find(|c| username.is_none() || c.username.is_none() || c.username.as_deref() == username)
+        .map(|c| (c.username.clone(), c.secret.clone()))
+}
+
+fn find_passphrase(key_path: &Path) -> Option<String> {
+    let key_path = key_path.to_string_lossy();
+    STORE
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|c| c.kind == "passphrase" && c.key_path.as_deref() == Some(key_path.as_ref()))
+        .map(|c| c.secret.clone())
+}
+
+// ---------------------------------------------------------------------------
+// Interactive prompts
+// ---------------------------------------------------------------------------
+
+struct PendingPrompt {
+    prompt: CredentialPrompt,
+    answer: mpsc::Sender<Option<CredentialAnswer>>,
+}
+
+/// Prompts waiting for the UI, keyed by prompt id.
+static PROMPTS: Lazy<Mutex<HashMap<String, PendingPrompt>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_PROMPT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Prompts the UI should currently show, oldest first.
+pub fn pending_prompts() -> Vec<CredentialPrompt> {
+    let mut prompts: Vec<CredentialPrompt> = PROMPTS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|p| p.prompt.clone())
+        .collect();
+    prompts.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    prompts
+}
+
+/// Deliver the user's answer to a waiting operation; `None` cancels the prompt.
+/// Returns false when no such prompt is pending (already answered or timed out).
+pub fn answer_prompt(id: &str, answer: Option<CredentialAnswer>) -> bool {
+    match PROMPTS.lock().unwrap().remove(id) {
+        Some(pending) => pending.answer.send(answer).is_ok(),
+        None => false,
+    }
+}
+
+/// Publish a prompt and block until it is answered, cancelled or `timeout`
+/// passes.
+fn ask(
+    url: &str,
+    kind: &str,
+    username: Option<&str>,
+    key_path: Option<&Path>,
+    retry: bool,
+    timeout: Duration,
+) -> Option<CredentialAnswer> {
+    let id = format!("cred-{}", NEXT_PROMPT_ID.fetch_add(1, Ordering::SeqCst));
+    let prompt = CredentialPrompt {
+        id: id.clone(),
+        url: url.to_string(),
+        host: url_host(url),
+        kind: kind.to_string(),
+        username: username.map(|s| s.to_string()),
+        key_path: key_path.map(|p| p.to_string_lossy().to_string()),
+        message: retry.then(|| "Authentication failed, please try again".to_string()),
+        created_at: Utc::now().to_rfc3339(),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    PROMPTS
+        .lock()
+        .unwrap()
+        .insert(id.clone(), PendingPrompt { prompt, answer: tx });
+    let answer = rx.recv_timeout(timeout).ok().flatten();
+    PROMPTS.lock().unwrap().remove(&id);
+    answer
+}
+
+// ---------------------------------------------------------------------------
+// SSH keys and ~/.ssh/config
+// ---------------------------------------------------------------------------
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// Host part of a remote URL (`https://host:port/..`, `ssh://user@host/..` or `user@host:path`).
+pub(crate) fn url_host(url: &str) -> String {
+    let authority = match url.split_once("://") {
+        Some((_, rest)) => rest.split('/').next().unwrap_or(rest),
+        None => url.split(':').next().unwrap_or(url),
+    };
+    authority
+        .rsplit('@')
+        .next()
+        .unwrap_or(authority)
+        .to_string()
+}
+
+/// The subset of ~/.ssh/config that affects authentication.
+#[derive(Default)]
+struct SshHostConfig {
+    user: Option<String>,
+    identity_files: Vec<PathBuf>,
+}
+
+/// ssh_config pattern matching: `*` and `?` wildcards.
+fn ssh_pattern_matches(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi].eq_ignore_ascii_case(&t[ti])) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some(pi);
+            mark = ti;
+            pi += 1;
+        } else if let Some(s) = star {
+            pi = s + 1;
+            mark += 1;
+            ti = mark;
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+fn ssh_host_config(host: &str) -> SshHostConfig {
+    let mut config = SshHostConfig::default();
+    let Some(home) = home_dir() else {
+        return config;
+    };
+    let Ok(content) = std::fs::read_to_string(home.join(".ssh").join("config")) else {
+        return config;
+    };
+
+    let host = host.split(':').next().unwrap_or(host);
+    let expand = |value: &str, user: Option<&str>| -> PathBuf {
+        let home_str = home.to_string_lossy();
+        let value = value
+            .replace("%d", &home_str)
+            .replace("%h", host)
+            .replace("%r", user.unwrap_or("git"))
+            .replace("%%", "%");
+        match value.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(value),
+        }
+    };
+
+    // Options before the first Host line apply to every host.
+    let mut active = true;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((k, v)) => (
+                k.to_ascii_lowercase(),
+                v.trim_start_matches([' ', '\t', '=']).trim(),
+            ),
+            None => continue,
+        };
+        let value = value.trim_matches('"');
+
+        match key.as_str() {
+            "host" => {
+                let patterns: Vec<&str> = value.split_whitespace().collect();
+                let negated = patterns
+                    .iter()
+                    .filter_map(|p| p.strip_prefix('!'))
+                    .any(|p| ssh_pattern_matches(p, host));
+                active = !negated
+                    && patterns
+                        .iter()
+                        .filter(|p| !p.starts_with('!'))
+                        .any(|p| ssh_pattern_matches(p, host));
+            }
+            // Match blocks need ssh's full evaluation; don't guess.
+            "match" => active = false,
+            "user" if active && config.user.is_none() => config.user = Some(value.to_string()),
+            "identityfile" if active => {
+                let path = expand(value, config.user.as_deref());
+                config.identity_files.push(path);
+            }
+            _ => {}
+        }
+    }
+    config
+}
+
+/// Whether a private key needs a passphrase. Handles PEM/PKCS#8 and the
+/// OpenSSH format, whose cipher name follows the `openssh-key-v1` magic.
+fn key_is_encrypted(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    if content.contains("ENCRYPTED") {
+        return true;
+    }
+    if !content.contains("BEGIN OPENSSH PRIVATE KEY") {
+        return false;
+    }
+    let body: String = content
+        .lines()
+        .filter(|l| !l.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(body) else {
+        return false;
+    };
+    let Some(rest) = bytes.strip_prefix(b"openssh-key-v1\0") else {
+        return false;
+    };
+    if rest.len() < 4 {
+        return false;
+    }
+    let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+    rest.get(4..4 + len)
+        .map(|cipher| cipher != b"none")
+        .unwrap_or(false)
+}
+
+fn public_key_for(private_key: &Path) -> Option<PathBuf> {
+    let mut public = private_key.as_os_str().to_owned();
+    public.push(".pub");
+    let public = PathBuf::from(public);
+    public.exists().then_some(public)
+}
+
+enum SshAttempt {
+    Agent,
+    Key {
+        path: PathBuf,
+        passphrase: Option<String>,
+    },
+    AskPassphrase {
+        path: PathBuf,
+    },
+}
+
+/// Agent first, then IdentityFile entries, then default keys. Encrypted keys
+/// without a saved passphrase go last since they need the user.
+fn ssh_plan(host: &str) -> VecDeque<SshAttempt> {
+    let mut plan = VecDeque::new();
+    if cfg!(windows) || std::env::var_os("SSH_AUTH_SOCK").is_some() {
+        plan.push_back(SshAttempt::Agent);
+    }
+
+    let mut keys = ssh_host_config(host).identity_files;
+    if let Some(home) = home_dir() {
+        keys.extend(DEFAULT_KEY_NAMES.iter().map(|n| home.join(".ssh").join(n)));
+    }
+
+    let mut seen = Vec::new();
+    let mut needs_passphrase = Vec::new();
+    for path in keys {
+        if !path.is_file() || seen.contains(&path) {
+            continue;
+        }
+        seen.push(path.clone());
+        if !key_is_encrypted(&path) {
+            plan.push_back(SshAttempt::Key {
+                path,
+                passphrase: None,
+            });
+        } else if let Some(passphrase) = find_passphrase(&path) {
+            plan.push_back(SshAttempt::Key {
+                path,
+                passphrase: Some(passphrase),
+            });
+        } else {
+            needs_passphrase.push(SshAttempt::AskPassphrase { path });
+        }
+    }
+    plan.extend(needs_passphrase);
+    plan
+}
+
+enum UserPassAttempt {
+    Stored,
+    Helper,
+    Ask,
+}
+
+// ---------------------------------------------------------------------------
+// Session
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct SessionState {
+    url: String,
+    ssh_plan: Option<VecDeque<SshAttempt>>,
+    userpass_plan: Option<VecDeque<UserPassAttempt>>,
+    prompts: usize,
+    /// When the prompt budget runs out; set by the first prompt.
+    prompt_deadline: Option<Instant>,
+    /// Secret from an answered prompt, saved once the operation succeeds.
+    answered: Option<StoredCredential>,
+    /// What the user would have to supply, if a source was skipped or declined.
+    required: Option<&'static str>,
+}
+
+impl SessionState {
+    /// Time left to wait for the next prompt, or None when prompts are used
+    /// up. Counts the prompt being started.
+    fn next_prompt_timeout(&mut self) -> Option<Duration> {
+        if self.prompts >= MAX_PROMPTS {
+            return None;
+        }
+        let deadline = *self
+            .prompt_deadline
+            .get_or_insert_with(|| Instant::now() + PROMPT_BUDGET);
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return None;
+        }
+        self.prompts += 1;
+        Some(left)
+    }
+}
+
+/// Credential state for one network operation. libgit2 calls the credentials
+/// callback again after every rejected credential, so each call moves on to
+/// the next source: token store, credential.helper, ssh-agent, IdentityFile,
+/// default keys, and finally an interactive prompt.
+pub struct CredentialSession {
+    config: Option<git2::Config>,
+    interactive: bool,
+    state: RefCell<SessionState>,
+}
+
+impl CredentialSession {
+    /// `interactive: false` never prompts, for background work like auto-fetch.
+    pub fn new(repo: &git2::Repository, interactive: bool) -> Self {
+        Self {
+            config: repo.config().ok(),
+            interactive,
+            state: RefCell::new(SessionState::default()),
+        }
+    }
+
+    /// For operations without a repository yet (clone): helpers and other
+    /// settings come from the global git config.
+    pub fn without_repo(interactive: bool) -> Self {
+        Self {
+            config: git2::Config::open_default().ok(),
+            interactive,
+            state: RefCell::new(SessionState::default()),
+        }
+    }
+
+    /// Remote callbacks with this session's credential chain installed, plus
+    /// progress reporting when running as a background job.
+    pub fn callbacks(&self) -> RemoteCallbacks<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks
+            .credentials(move |url, username, allowed| self.credentials(url, username, allowed));
+        jobs::install_progress(&mut callbacks);
+        callbacks
+    }
+
+    /// Map a finished operation's result. On success, secrets entered at a
+    /// prompt are kept; on failure after exhausting all sources the error
+    /// becomes [`CredentialsRequired`].
+    pub fn finish<T>(&self, result: std::result::Result<T, git2::Error>) -> Result<T> {
+        let mut state = self.state.borrow_mut();
+        match result {
+            Ok(value) => {
+                if let Some(credential) = state.answered.take() {
+                    if let Err(e) = remember(credential) {
+                        tracing::warn!("Failed to save credentials: {}", e);
+                    }
+                }
+                Ok(value)
+            }
+            Err(e) if e.code() == git2::ErrorCode::Auth || state.required.is_some() => {
+                Err(CredentialsRequired {
+                    url: state.url.clone(),
+                    kind: state.required.unwrap_or("credential").to_string(),
+                }
+                .into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn credentials(
+        &self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        if jobs::is_cancelled() {
+            return Err(git2::Error::from_str("cancelled"));
+        }
+        let mut state = self.state.borrow_mut();
+        state.url = url.to_string();
+        let host = url_host(url);
+
+        if allowed.contains(CredentialType::USERNAME) {
+            let user = username_from_url
+                .map(|s| s.to_string())
+                .or_else(|| ssh_host_config(&host).user)
+                .unwrap_or_else(|| "git".to_string());
+            return Cred::username(&user);
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return self.next_ssh_key(&mut state, &host, username_from_url.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return self.next_userpass(&mut state, url, &host, username_from_url);
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        Err(git2::Error::from_str("No supported authentication method"))
+    }
+
+    fn next_ssh_key(
+        &self,
+        state: &mut SessionState,
+        host: &str,
+        user: &str,
+    ) -> std::result::Result<Cred, git2::Error> {
+        if state.ssh_plan.is_none() {
+            state.ssh_plan = Some(ssh_plan(host));
+        }
+
+        while let Some(attempt) = state.ssh_plan.as_mut().and_then(|p| p.pop_front()) {
+            match attempt {
+                SshAttempt::Agent => return Cred::ssh_key_from_agent(user),
+                SshAttempt::Key { path, passphrase } => {
+                    let public = public_key_for(&path);
+                    return Cred::ssh_key(user, public.as_deref(), &path, passphrase.as_deref());
+                }
+                SshAttempt::AskPassphrase { path } => {
+                    let timeout = self
+                        .interactive
+                        .then(|| state.next_prompt_timeout())
+                        .flatten();
+                    let Some(timeout) = timeout else {
+                        state.required = Some("passphrase");
+                        continue;
+                    };
+                    let Some(answer) = ask(
+                        &state.url,
+                        "passphrase",
+                        Some(user),
+                        Some(&path),
+                        false,
+                        timeout,
+                    ) else {
+                        state.required = Some("passphrase");
+                        continue;
+                    };
+                    state.answered = Some(StoredCredential {
+                        kind: "passphrase".to_string(),
+                        host: None,
+                        key_path: Some(path.to_string_lossy().to_string()),
+                        username: None,
+                        secret: answer.secret.clone(),
+                        persisted: answer.remember,
+                    });
+                    let public = public_key_for(&path);
+                    return Cred::ssh_key(user, public.as_deref(), &path, Some(&answer.secret));
+                }
+            }
+        }
+
+        state.required.get_or_insert("ssh-key");
+        Err(git2::Error::from_str(&format!(
+            "No SSH key was accepted by {}",
+            host
+        )))
+    }
+
+    fn next_userpass(
+        &self,
+        state: &mut SessionState,
+        url: &str,
+        host: &str,
+        username_from_url: Option<&str>,
+    ) -> std::result::Result<Cred, git2::Error> {
+        if state.userpass_plan.is_none() {
+            state.userpass_plan = Some(VecDeque::from([
+                UserPassAttempt::Stored,
+                UserPassAttempt::Helper,
+                UserPassAttempt::Ask,
+            ]));
+        }
+
+        while let Some(attempt) = state.userpass_plan.as_mut().and_then(|p| p.pop_front()) {
+            match attempt {
+                UserPassAttempt::Stored => {
+                    if let Some((username, secret)) = find_userpass(host, username_from_url) {
+                        let username = username
+                            .or_else(|| username_from_url.map(|s| s.to_string()))
+                            .unwrap_or_else(|| "git".to_string());
+                        return Cred::userpass_plaintext(&username, &secret);
+                    }
+                }
+                UserPassAttempt::Helper => {
+                    if let Some(config) = &self.config {
+                        if let Ok(cred) = Cred::credential_helper(config, url, username_from_url) {
+                            return Ok(cred);
+                        }
+                    }
+                }
+                UserPassAttempt::Ask => {
+                    let retry = state.prompts > 0;
+                    let timeout = self
+                        .interactive
+                        .then(|| state.next_prompt_timeout())
+                        .flatten();
+                    let Some(timeout) = timeout else {
+                        state.required = Some("password");
+                        continue;
+                    };
+                    let Some(answer) =
+                        ask(url, "userpass", username_from_url, None, retry, timeout)
+                    else {
+                        state.required = Some("password");
+                        continue;
+                    };
+                    let username = answer
+                        .username
+                        .clone()
+                        .filter(|u| !u.is_empty())
+                        .or_else(|| username_from_url.map(|s| s.to_string()))
+                        .unwrap_or_else(|| "git".to_string());
+                    state.answered = Some(StoredCredential {
+                        kind: "password".to_string(),
+                        host: Some(host.to_string()),
+                        key_path: None,
+                        username: Some(username.clone()),
+                        secret: answer.secret.clone(),
+                        persisted: answer.remember,
+                    });
+                    // Ask again if this one is rejected too.
+                    if let Some(plan) = state.userpass_plan.as_mut() {
+                        plan.push_back(UserPassAttempt::Ask);
+                    }
+                    return Cred::userpass_plaintext(&username, &answer.secret);
+                }
+            }
+        }
+
+        state.required.get_or_insert("password");
+        Err(git2::Error::from_str(&format!(
+            "No credentials were accepted by {}",
+            host
+        )))
+    }
+}
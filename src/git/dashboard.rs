@@ -0,0 +1,113 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::fs;
+use std::path::Path;
+
+use super::discovery::{self, DiscoveredRepo, DiscoveryOptions};
+use super::repository::{operation_name, GitRepository};
+use super::status::StatusOptions;
+use crate::models::RepoSummary;
+use crate::utils::repo_id;
+
+/// Repositories summarized at the same time. Each summary walks the working
+/// tree, so running all of them at once would just thrash the disk.
+const DASHBOARD_CONCURRENCY: usize = 8;
+
+static POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    ThreadPoolBuilder::new()
+        .num_threads(DASHBOARD_CONCURRENCY)
+        .thread_name(|i| format!("dashboard-{}", i))
+        .build()
+        .expect("failed to build dashboard thread pool")
+});
+
+/// A summary of every repository discovered under `root`, in discovery order.
+pub fn summarize_all(root: &Path, options: &DiscoveryOptions) -> Result<Vec<RepoSummary>> {
+    let repos = discovery::locate(root, options)?;
+    Ok(POOL.install(|| repos.par_iter().map(summarize).collect()))
+}
+
+/// Everything the dashboard shows for one repository. Failures are reported
+/// in `error` so one broken repository doesn't hide the others.
+fn summarize(found: &DiscoveredRepo) -> RepoSummary {
+    let mut summary = RepoSummary {
+        id: repo_id(&found.path),
+        name: found.name.clone(),
+        kind: found.kind.as_str().to_string(),
+        ..Default::default()
+    };
+    if let Err(e) = fill_summary(&mut summary, &found.path) {
+        summary.error = Some(e.to_string());
+    }
+    summary
+}
+
+fn fill_summary(summary: &mut RepoSummary, path: &Path) -> Result<()> {
+    let repo = GitRepository::open(path)?;
+    summary.in_progress = operation_name(repo.repo.state()).map(|s| s.to_string());
+    // `git stash list` needs a working tree; bare repositories have no stashes.
+    summary.stash_count = repo.stash_list().map(|stashes| stashes.len()).unwrap_or(0);
+    summary.last_fetch = last_fetch(&repo.repo);
+    summary.last_commit_date = repo
+        .repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .ok()
+        .and_then(|commit| DateTime::from_timestamp(commit.time().seconds(), 0))
+        .map(|date| date.to_rfc3339());
+
+    if repo.repo.is_bare() {
+        let branch = repo.get_current_branch().ok();
+        if let Some(branch) = branch.as_deref().filter(|b| *b != "HEAD") {
+            if let Some(upstream) = repo.get_upstream(branch)? {
+                let (ahead, behind) = repo.ahead_behind(branch, &upstream)?;
+                summary.ahead = Some(ahead);
+                summary.behind = Some(behind);
+                summary.upstream = Some(upstream);
+            }
+        }
+        summary.branch = branch;
+        return Ok(());
+    }
+
+    // The same status the per-repository view shows, so the counts agree.
+    let status = repo.status(&StatusOptions {
+        untracked: Some("all".to_string()),
+        ..Default::default()
+    })?;
+    if let Some(branch) = status.branch {
+        // An unborn branch still has a name; a detached HEAD doesn't.
+        summary.branch = Some(branch.head.unwrap_or_else(|| "HEAD".to_string()));
+        summary.upstream = branch.upstream;
+        summary.ahead = branch.ahead;
+        summary.behind = branch.behind;
+    }
+    for file in &status.files {
+        match file.r#type.as_str() {
+            "conflicted" => summary.conflicted += 1,
+            "untracked" => summary.untracked += 1,
+            _ => {
+                if file.staged {
+                    summary.staged += 1;
+                }
+                if file.unstaged {
+                    summary.unstaged += 1;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// FETCH_HEAD is rewritten by every fetch, so its mtime is the last fetch.
+/// Worktrees share it with the main repository.
+fn last_fetch(repo: &Repository) -> Option<String> {
+    let modified = fs::metadata(repo.commondir().join("FETCH_HEAD"))
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(DateTime::<Utc>::from(modified).to_rfc3339())
+}
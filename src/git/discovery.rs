@@ -0,0 +1,320 @@
+use anyhow::{bail, Result};
+use git2::{Repository, StatusOptions};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::models::Repo;
+use crate::utils::repo_id;
+
+/// Folders that never contain repositories worth listing and are expensive to walk.
+pub const DEFAULT_SKIP_DIRS: &[&str] = &["node_modules", "target"];
+
+/// How far and where to look for repositories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveryOptions {
+    /// Levels below the root to look at; 1 only checks its direct children.
+    pub max_depth: usize,
+    /// Folder names that are never entered.
+    pub skip_dirs: Vec<String>,
+    /// Also enter folders whose name starts with '.'.
+    pub include_hidden: bool,
+}
+
+impl DiscoveryOptions {
+    pub fn with_depth(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            skip_dirs: DEFAULT_SKIP_DIRS.iter().map(|s| s.to_string()).collect(),
+            include_hidden: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoKind {
+    Repo,
+    Worktree,
+    Bare,
+}
+
+impl RepoKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Repo => "repo",
+            Self::Worktree => "worktree",
+            Self::Bare => "bare",
+        }
+    }
+}
+
+/// A discovered repository
+#[derive(Debug, Clone)]
+pub struct DiscoveredRepo {
+    pub path: PathBuf,
+    /// Path relative to the scanned root, with '/' separators.
+    pub name: String,
+    pub kind: RepoKind,
+}
+
+struct Scan {
+    options: DiscoveryOptions,
+    found: Vec<DiscoveredRepo>,
+}
+
+/// Directory walks by root. Walking a large tree is the slow part, so it is
+/// only redone on rescan; branch and dirty state are read fresh every time.
+static SCANS: Lazy<Mutex<HashMap<PathBuf, Scan>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Repositories under `root` from the last scan with the same options,
+/// scanning first if there is none.
+pub fn list_repos(root: &Path, options: &DiscoveryOptions) -> Result<Vec<Repo>> {
+    Ok(describe(locate(root, options)?))
+}
+
+/// Like [`list_repos`], but only where the repositories are.
+pub fn locate(root: &Path, options: &DiscoveryOptions) -> Result<Vec<DiscoveredRepo>> {
+    let cached = {
+        let scans = SCANS.lock().unwrap();
+        scans
+            .get(root)
+            .filter(|scan| scan.options == *options)
+            .map(|scan| scan.found.clone())
+    };
+    match cached {
+        // Repositories deleted since the scan just drop out.
+        Some(found) => Ok(found.into_iter().filter(|f| f.path.exists()).collect()),
+        None => scan(root, options),
+    }
+}
+
+/// Walk `root` again, replacing the cached result.
+pub fn rescan(root: &Path, options: &DiscoveryOptions) -> Result<Vec<Repo>> {
+    let found = scan(root, options)?;
+    Ok(describe(found))
+}
+
+/// Add a repository created by GitPow (clone, init) to the cached scans
+/// that cover it, so it is listed without a rescan. Returns its entry.
+pub fn register(path: &Path) -> Repo {
+    let kind = detect(path).unwrap_or(RepoKind::Repo);
+    let mut name = folder_name(path);
+    for (root, scan) in SCANS.lock().unwrap().iter_mut() {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let depth = relative.components().count();
+        if depth == 0 || depth > scan.options.max_depth {
+            continue;
+        }
+        name = relative_name(relative);
+        if !scan.found.iter().any(|f| f.path == path) {
+            scan.found.push(DiscoveredRepo {
+                path: path.to_path_buf(),
+                name: name.clone(),
+                kind,
+            });
+            scan.found.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+    describe_one(&DiscoveredRepo {
+        path: path.to_path_buf(),
+        name,
+        kind,
+    })
+}
+
+fn scan(root: &Path, options: &DiscoveryOptions) -> Result<Vec<DiscoveredRepo>> {
+    let found = walk(root, options)?;
+    SCANS.lock().unwrap().insert(
+        root.to_path_buf(),
+        Scan {
+            options: options.clone(),
+            found: found.clone(),
+        },
+    );
+    Ok(found)
+}
+
+/// Depth-limited walk. Repositories aren't entered (except the root), and
+/// every folder is visited once by its canonical path so symlink loops end.
+fn walk(root: &Path, options: &DiscoveryOptions) -> Result<Vec<DiscoveredRepo>> {
+    let mut found = Vec::new();
+    // The selected folder itself may be a repository.
+    if let Some(kind) = detect(root) {
+        found.push(DiscoveredRepo {
+            path: root.to_path_buf(),
+            name: folder_name(root),
+            kind,
+        });
+    }
+    if let Err(e) = fs::read_dir(root) {
+        if found.is_empty() {
+            bail!("Failed to read repos directory: {}", e);
+        }
+        return Ok(found);
+    }
+
+    let mut walk = Walk {
+        root,
+        visited: HashSet::new(),
+        found,
+        pending: vec![(root.to_path_buf(), 0)],
+    };
+    walk.visited
+        .insert(root.canonicalize().unwrap_or_else(|_| root.to_path_buf()));
+    // Symlinked folders are followed only after everything else, so a repo
+    // reachable both directly and through a link is listed under its real path.
+    let mut links = Vec::new();
+    loop {
+        while let Some((dir, depth)) = walk.pending.pop() {
+            if depth >= options.max_depth {
+                continue;
+            }
+            let Ok(entries) = fs::read_dir(&dir) else {
+                tracing::warn!("Skipping unreadable folder {}", dir.display());
+                continue;
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let file_name = file_name.to_string_lossy();
+                if (!options.include_hidden && file_name.starts_with('.'))
+                    || options.skip_dirs.iter().any(|skip| *skip == file_name)
+                {
+                    continue;
+                }
+                if entry.file_type().is_ok_and(|t| t.is_symlink()) {
+                    links.push((entry.path(), depth + 1));
+                } else {
+                    walk.visit(entry.path(), depth + 1);
+                }
+            }
+        }
+        match links.pop() {
+            Some((path, depth)) => walk.visit(path, depth),
+            None => break,
+        }
+    }
+
+    let mut found = walk.found;
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(found)
+}
+
+struct Walk<'a> {
+    root: &'a Path,
+    /// Canonical paths of the folders seen so far; ends symlink loops.
+    visited: HashSet<PathBuf>,
+    found: Vec<DiscoveredRepo>,
+    pending: Vec<(PathBuf, usize)>,
+}
+
+impl Walk<'_> {
+    /// Record a repository at `path`, or queue the folder to be walked.
+    fn visit(&mut self, path: PathBuf, depth: usize) {
+        // fs::metadata follows symlinks
+        if !fs::metadata(&path).is_ok_and(|m| m.is_dir()) {
+            return;
+        }
+        let Ok(canonical) = path.canonicalize() else {
+            return;
+        };
+        if !self.visited.insert(canonical) {
+            return;
+        }
+        match detect(&path) {
+            Some(kind) => self.found.push(DiscoveredRepo {
+                name: relative_name(path.strip_prefix(self.root).unwrap_or(&path)),
+                path,
+                kind,
+            }),
+            None => self.pending.push((path, depth)),
+        }
+    }
+}
+
+/// A `.git` folder, a `.git` file pointing elsewhere (worktrees, submodule
+/// checkouts, --separate-git-dir), or the layout of a bare repository.
+fn detect(path: &Path) -> Option<RepoKind> {
+    let dot_git = path.join(".git");
+    if dot_git.is_dir() {
+        return dot_git.join("HEAD").is_file().then_some(RepoKind::Repo);
+    }
+    if dot_git.is_file() {
+        let contents = fs::read_to_string(&dot_git).ok()?;
+        let gitdir = contents
+            .trim()
+            .strip_prefix("gitdir:")?
+            .trim()
+            .replace('\\', "/");
+        return Some(if gitdir.contains("/worktrees/") {
+            RepoKind::Worktree
+        } else {
+            RepoKind::Repo
+        });
+    }
+    let bare =
+        path.join("HEAD").is_file() && path.join("objects").is_dir() && path.join("refs").is_dir();
+    bare.then_some(RepoKind::Bare)
+}
+
+fn describe(found: Vec<DiscoveredRepo>) -> Vec<Repo> {
+    found.par_iter().map(describe_one).collect()
+}
+
+fn describe_one(found: &DiscoveredRepo) -> Repo {
+    let repo = Repository::open(&found.path).ok();
+    Repo {
+        id: repo_id(&found.path),
+        name: found.name.clone(),
+        kind: Some(found.kind.as_str().to_string()),
+        branch: repo.as_ref().and_then(current_branch),
+        dirty: repo
+            .as_ref()
+            .filter(|r| !r.is_bare())
+            .and_then(|r| is_dirty(r).ok()),
+    }
+}
+
+/// Branch name, "HEAD" when detached, or the unborn branch of a new repository.
+fn current_branch(repo: &Repository) -> Option<String> {
+    match repo.head() {
+        Ok(head) if head.is_branch() => head.shorthand().map(|s| s.to_string()),
+        Ok(_) => Some("HEAD".to_string()),
+        Err(_) => repo
+            .find_reference("HEAD")
+            .ok()?
+            .symbolic_target()?
+            .strip_prefix("refs/heads/")
+            .map(|s| s.to_string()),
+    }
+}
+
+/// Any staged, unstaged or untracked change. Untracked folders aren't
+/// recursed into and submodules are ignored to keep this cheap.
+fn is_dirty(repo: &Repository) -> Result<bool> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true);
+    Ok(!repo.statuses(Some(&mut options))?.is_empty())
+}
+
+fn folder_name(path: &Path) -> String {
+    path.file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// "org/project", with '/' on every platform.
+fn relative_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,419 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use git2::{Oid, RepositoryState, Signature};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use super::repository::{operation_name, GitRepository};
+use crate::models::JournalEntry;
+use crate::utils::write_atomic;
+
+/// Entries kept per worktree; older snapshots are released.
+const MAX_ENTRIES: usize = 50;
+
+/// Keeps the snapshot commits reachable so gc doesn't prune them.
+const SNAPSHOT_REFS: &str = "refs/gitpow/journal/";
+
+const JOURNAL_FILE: &str = "gitpow-journal.json";
+
+/// Serializes read-modify-write cycles of journal files, so concurrent
+/// requests don't drop each other's entries.
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// Nesting depth of journaled calls; only the outermost one records.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Repository state before an operation. HEAD, index and worktree are per
+/// worktree, so the journal lives in the worktree's git dir.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Record {
+    id: String,
+    operation: String,
+    timestamp: String,
+    head: Option<String>,
+    /// Branch HEAD pointed at, unless detached
+    head_ref: Option<String>,
+    /// Local branches and tags
+    refs: BTreeMap<String, String>,
+    /// Stash entries, newest first: (commit, message)
+    stashes: Vec<(String, String)>,
+    /// None while the index had conflicts
+    index_tree: Option<String>,
+    /// Commit of the worktree including untracked files, like `git stash -u`
+    worktree_commit: Option<String>,
+    #[serde(default)]
+    undone: bool,
+    #[serde(default)]
+    undo_of: Option<String>,
+}
+
+impl Record {
+    fn entry(&self) -> JournalEntry {
+        JournalEntry {
+            id: self.id.clone(),
+            operation: self.operation.clone(),
+            timestamp: self.timestamp.clone(),
+            head: self.head.clone(),
+            branch: self
+                .head_ref
+                .as_deref()
+                .map(|r| r.trim_start_matches("refs/heads/").to_string()),
+            worktree_saved: self.worktree_commit.is_some(),
+            undone: self.undone,
+            undo_of: self.undo_of.clone(),
+        }
+    }
+
+    /// Same refs, HEAD and index as `other`; the worktree is not compared.
+    fn same_refs_and_index(&self, other: &Record) -> bool {
+        self.head == other.head
+            && self.head_ref == other.head_ref
+            && self.refs == other.refs
+            && self.stashes == other.stashes
+            && self.index_tree == other.index_tree
+    }
+}
+
+impl GitRepository {
+    /// Run a mutating operation after recording the state it starts from, so
+    /// it can be undone. Recording is best effort: if the snapshot fails the
+    /// operation still runs. Nested calls are recorded once, by the outermost.
+    pub fn journaled<T>(&self, operation: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if DEPTH.with(|d| d.get()) > 0 {
+            return f();
+        }
+        let record = match self.snapshot(operation) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                tracing::warn!(
+                    "Could not record '{}' in the undo journal: {:#}",
+                    operation,
+                    e
+                );
+                None
+            }
+        };
+
+        DEPTH.with(|d| d.set(d.get() + 1));
+        let result = f();
+        DEPTH.with(|d| d.set(d.get() - 1));
+
+        if let Some(record) = record {
+            // A failed operation that changed nothing isn't worth an entry.
+            let unchanged = result.is_err()
+                && self
+                    .capture_refs(operation)
+                    .is_ok_and(|now| now.same_refs_and_index(&record));
+            if unchanged {
+                self.release_snapshot(&record);
+            } else if let Err(e) = self.append_record(record) {
+                tracing::warn!("Could not write the undo journal: {:#}", e);
+            }
+        }
+        result
+    }
+
+    /// Record the current state now, failing if it can't all be saved. For
+    /// operations that must not run without a way back.
+    pub fn record_journal(&self, operation: &str) -> Result<JournalEntry> {
+        let record = self.snapshot(operation)?;
+        let entry = record.entry();
+        self.append_record(record)?;
+        Ok(entry)
+    }
+
+    /// Recent journal entries, newest first.
+    pub fn journal_history(&self) -> Result<Vec<JournalEntry>> {
+        Ok(self
+            .read_records()
+            .iter()
+            .rev()
+            .map(Record::entry)
+            .collect())
+    }
+
+    /// Undo the most recent operation that hasn't been undone yet.
+    pub fn undo_last(&self) -> Result<JournalEntry> {
+        let id = self
+            .read_records()
+            .iter()
+            .rev()
+            .find(|r| !r.undone && r.undo_of.is_none())
+            .map(|r| r.id.clone())
+            .context("There is nothing to undo")?;
+        self.undo(&id)
+    }
+
+    /// Restore branches, tags, stashes, HEAD, index and worktree to how they
+    /// were before the given operation. The undo is journaled too.
+    pub fn undo(&self, id: &str) -> Result<JournalEntry> {
+        if let Some(op) = operation_name(self.repo.state()) {
+            bail!("Finish or abort the {} before undoing", op);
+        }
+        let records = self.read_records();
+        let record = records
+            .iter()
+            .find(|r| r.id == id)
+            .cloned()
+            .with_context(|| format!("No journal entry {}", id))?;
+
+        // Record the state being replaced before touching anything, so a
+        // restore that fails partway can itself be undone.
+        let mut undo = self.snapshot(&format!("Undo {}", record.operation))?;
+        undo.undo_of = Some(id.to_string());
+        let entry = undo.entry();
+        self.append_record(undo)?;
+
+        self.restore(&record)?;
+
+        self.update_records(|records| {
+            if let Some(r) = records.iter_mut().find(|r| r.id == id) {
+                r.undone = true;
+            }
+        })?;
+        Ok(entry)
+    }
+
+    fn restore(&self, record: &Record) -> Result<()> {
+        if self.repo.state() != RepositoryState::Clean {
+            bail!("An operation is in progress");
+        }
+
+        // Branches and tags: reset recorded ones, delete ones created since.
+        // Branches checked out in other worktrees are theirs to move.
+        let elsewhere = self.branches_in_other_worktrees()?;
+        let movable = |name: &str| {
+            name.strip_prefix("refs/heads/")
+                .is_none_or(|branch| !elsewhere.contains_key(branch))
+        };
+        for name in self.ref_values()?.keys() {
+            if !record.refs.contains_key(name) && movable(name) {
+                self.repo.find_reference(name)?.delete()?;
+            }
+        }
+        for (name, sha) in &record.refs {
+            if movable(name) {
+                self.repo
+                    .reference(name, Oid::from_str(sha)?, true, "gitpow: undo")?;
+            }
+        }
+
+        if self.stash_entries()? != record.stashes {
+            self.run_git(&["update-ref", "-d", "refs/stash"])?;
+            for (sha, message) in record.stashes.iter().rev() {
+                self.run_git(&["stash", "store", "-m", message, sha])?;
+            }
+        }
+
+        match (&record.head_ref, &record.head) {
+            (Some(head_ref), _) => self.repo.set_head(head_ref)?,
+            (None, Some(sha)) => self.repo.set_head_detached(Oid::from_str(sha)?)?,
+            (None, None) => {}
+        }
+
+        if let Some(worktree) = &record.worktree_commit {
+            let tree = format!("{}^{{tree}}", worktree);
+            // Overwrites the worktree with the snapshot, then puts the index back.
+            self.run_git(&["read-tree", "--reset", "-u", &tree])?;
+            match &record.index_tree {
+                Some(index_tree) => self.run_git(&["read-tree", index_tree])?,
+                None if record.head.is_some() => self.run_git(&["read-tree", "HEAD"])?,
+                None => self.run_git(&["read-tree", "--empty"])?,
+            };
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self, operation: &str) -> Result<Record> {
+        let mut record = self.capture_refs(operation)?;
+        record.worktree_commit = Some(self.snapshot_worktree(&record)?);
+        Ok(record)
+    }
+
+    /// Everything but the worktree: cheap enough to compare after an operation.
+    fn capture_refs(&self, operation: &str) -> Result<Record> {
+        let head_ref = self
+            .repo
+            .find_reference("HEAD")?
+            .symbolic_target()
+            .filter(|t| t.starts_with("refs/heads/"))
+            .map(|t| t.to_string());
+        let head = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|h| h.target())
+            .map(|o| o.to_string());
+
+        let mut index = self.repo.index()?;
+        index.read(false)?;
+        let index_tree = if index.has_conflicts() {
+            None
+        } else {
+            Some(index.write_tree()?.to_string())
+        };
+
+        Ok(Record {
+            id: Utc::now().timestamp_micros().to_string(),
+            operation: operation.to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            head,
+            head_ref,
+            refs: self.ref_values()?,
+            stashes: self.stash_entries()?,
+            index_tree,
+            worktree_commit: None,
+            undone: false,
+            undo_of: None,
+        })
+    }
+
+    fn ref_values(&self) -> Result<BTreeMap<String, String>> {
+        let mut refs = BTreeMap::new();
+        for glob in ["refs/heads/*", "refs/tags/*"] {
+            for reference in self.repo.references_glob(glob)? {
+                let reference = reference?;
+                if let (Some(name), Some(target)) = (reference.name(), reference.target()) {
+                    refs.insert(name.to_string(), target.to_string());
+                }
+            }
+        }
+        Ok(refs)
+    }
+
+    fn stash_entries(&self) -> Result<Vec<(String, String)>> {
+        if self.repo.find_reference("refs/stash").is_err() {
+            return Ok(Vec::new());
+        }
+        let reflog = self.repo.reflog("refs/stash")?;
+        Ok(reflog
+            .iter()
+            .map(|e| {
+                (
+                    e.id_new().to_string(),
+                    e.message().unwrap_or_default().to_string(),
+                )
+            })
+            .collect())
+    }
+
+    /// Commit the index and the whole worktree (untracked files included,
+    /// ignored ones not) without touching either, using a scratch index.
+    fn snapshot_worktree(&self, record: &Record) -> Result<String> {
+        let scratch = self.repo.path().join("gitpow-journal-index");
+        let index_file = self.repo.path().join("index");
+        if index_file.exists() {
+            std::fs::copy(&index_file, &scratch)?;
+        } else {
+            let _ = std::fs::remove_file(&scratch);
+        }
+        let tree = self
+            .git_with_index(&scratch, &["add", "--all"])
+            .and_then(|_| self.git_with_index(&scratch, &["write-tree"]));
+        let _ = std::fs::remove_file(&scratch);
+        let tree = self.repo.find_tree(Oid::from_str(tree?.trim())?)?;
+
+        let sig = Signature::now("GitPow", "gitpow@localhost")?;
+        let mut parents = Vec::new();
+        if let Some(head) = &record.head {
+            parents.push(self.repo.find_commit(Oid::from_str(head)?)?);
+        }
+        if let Some(index_tree) = &record.index_tree {
+            let index_tree = self.repo.find_tree(Oid::from_str(index_tree)?)?;
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            let index_commit = self.repo.commit(
+                None,
+                &sig,
+                &sig,
+                &format!("index before {}", record.operation),
+                &index_tree,
+                &parent_refs,
+            )?;
+            parents.push(self.repo.find_commit(index_commit)?);
+        }
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let commit = self.repo.commit(
+            None,
+            &sig,
+            &sig,
+            &format!("worktree before {}", record.operation),
+            &tree,
+            &parent_refs,
+        )?;
+        self.repo.reference(
+            &format!("{}{}", SNAPSHOT_REFS, record.id),
+            commit,
+            true,
+            "gitpow: journal snapshot",
+        )?;
+        Ok(commit.to_string())
+    }
+
+    fn git_with_index(&self, index_file: &Path, args: &[&str]) -> Result<String> {
+        let mut cmd = Command::new("git");
+        cmd.args(args)
+            .current_dir(self.path())
+            .env("GIT_INDEX_FILE", index_file);
+
+        #[cfg(target_os = "windows")]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let output = cmd
+            .output()
+            .with_context(|| format!("Failed to run git with args {:?}", args))?;
+        if !output.status.success() {
+            bail!(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn release_snapshot(&self, record: &Record) {
+        if let Ok(mut reference) = self
+            .repo
+            .find_reference(&format!("{}{}", SNAPSHOT_REFS, record.id))
+        {
+            let _ = reference.delete();
+        }
+    }
+
+    fn read_records(&self) -> Vec<Record> {
+        std::fs::read_to_string(self.repo.path().join(JOURNAL_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn append_record(&self, record: Record) -> Result<()> {
+        self.update_records(|records| records.push(record))
+    }
+
+    /// Read, change and atomically rewrite the journal under the lock,
+    /// releasing snapshots of entries beyond `MAX_ENTRIES`.
+    fn update_records(&self, f: impl FnOnce(&mut Vec<Record>)) -> Result<()> {
+        let _guard = JOURNAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut records = self.read_records();
+        f(&mut records);
+        if records.len() > MAX_ENTRIES {
+            for old in records.drain(..records.len() - MAX_ENTRIES) {
+                self.release_snapshot(&old);
+            }
+        }
+        write_atomic(
+            &self.repo.path().join(JOURNAL_FILE),
+            serde_json::to_string(&records)?.as_bytes(),
+        )?;
+        Ok(())
+    }
+}
@@ -1 +1,23 @@
 pub mod repository;
+pub mod tree;
+pub mod search;
+pub mod tags;
+pub mod remotes;
+pub mod credentials;
+pub mod jobs;
+pub mod pull;
+pub mod push;
+pub mod clone;
+pub mod discovery;
+pub mod dashboard;
+pub mod worktrees;
+pub mod submodules;
+pub mod status;
+pub mod conflicts;
+pub mod autoresolve;
+pub mod operations;
+pub mod journal;
+pub mod reflog;
+pub mod reset;
+pub mod bisect;
+pub mod stats;
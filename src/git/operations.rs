@@ -0,0 +1,258 @@
+use anyhow::{bail, Result};
+use git2::RepositoryState;
+use std::fs;
+use std::path::Path;
+
+use super::repository::{operation_name, GitRepository};
+use crate::models::{OperationState, RebaseStep};
+
+/// Todo actions whose second word is a commit
+const COMMIT_ACTIONS: [&str; 12] = [
+    "pick", "p", "reword", "r", "edit", "e", "squash", "s", "fixup", "f", "drop", "d",
+];
+
+impl GitRepository {
+    /// The merge, rebase, cherry-pick, revert, bisect or `git am` in progress,
+    /// if any, with the rebase todo/done lists and current step.
+    pub fn operation_state(&self) -> Result<Option<OperationState>> {
+        let state = self.repo.state();
+        let Some(kind) = operation_name(state) else {
+            return Ok(None);
+        };
+        let git_dir = self.repo.path();
+
+        let mut op = OperationState {
+            kind: kind.to_string(),
+            conflicted_files: self.conflicted_files()?.len(),
+            can_continue: kind != "bisect",
+            can_skip: !matches!(kind, "merge" | "bisect"),
+            ..Default::default()
+        };
+
+        match state {
+            RepositoryState::Merge => {
+                op.incoming = read_trimmed(&git_dir.join("MERGE_HEAD"))
+                    .map(|heads| heads.lines().map(|s| s.to_string()).collect())
+                    .unwrap_or_default();
+            }
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                op.incoming = read_trimmed(&git_dir.join("CHERRY_PICK_HEAD"))
+                    .into_iter()
+                    .collect();
+                self.read_sequencer(&mut op);
+            }
+            RepositoryState::Revert | RepositoryState::RevertSequence => {
+                op.incoming = read_trimmed(&git_dir.join("REVERT_HEAD"))
+                    .into_iter()
+                    .collect();
+                self.read_sequencer(&mut op);
+            }
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => {
+                let dir = git_dir.join("rebase-merge");
+                if dir.is_dir() {
+                    self.read_rebase_merge(&dir, &mut op);
+                } else {
+                    read_rebase_apply(&git_dir.join("rebase-apply"), &mut op);
+                }
+            }
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+                let dir = git_dir.join("rebase-apply");
+                // "git rebase --apply" uses the same folder as `git am`
+                if dir.join("rebasing").exists() {
+                    op.kind = "rebase".to_string();
+                }
+                read_rebase_apply(&dir, &mut op);
+            }
+            RepositoryState::Bisect => {
+                op.bisect = self.bisect_state()?;
+                op.branch = op
+                    .bisect
+                    .as_ref()
+                    .and_then(|b| b.original_head.clone())
+                    .filter(|h| self.repo.find_branch(h, git2::BranchType::Local).is_ok());
+            }
+            _ => {}
+        }
+
+        op.current = op.done.last().cloned();
+        Ok(Some(op))
+    }
+
+    /// `--continue` the operation in progress. Fails while files are still
+    /// conflicted.
+    pub fn continue_operation(&self) -> Result<String> {
+        let kind = self.current_operation()?;
+        if kind == "bisect" {
+            bail!("A bisect continues by marking commits good or bad");
+        }
+        let conflicts = self.conflicted_files()?.len();
+        if conflicts > 0 {
+            bail!(
+                "Resolve {} conflicted file(s) before continuing the {}",
+                conflicts,
+                kind
+            );
+        }
+        self.advance_operation(&kind, "--continue")
+    }
+
+    /// `--skip` the current commit of a rebase, cherry-pick, revert or `git am`.
+    pub fn skip_operation(&self) -> Result<String> {
+        let kind = self.current_operation()?;
+        if kind == "merge" || kind == "bisect" {
+            bail!("A {} has no step to skip", kind);
+        }
+        self.advance_operation(&kind, "--skip")
+    }
+
+    /// git exits non-zero when the next step conflicts; that still moved
+    /// the operation forward, so it is reported as output, not an error.
+    fn advance_operation(&self, kind: &str, flag: &str) -> Result<String> {
+        // Keep the prepared commit message instead of opening an editor.
+        match self.run_git(&["-c", "core.editor=true", kind, flag]) {
+            Ok(output) => Ok(output),
+            Err(e) => match self.operation_state()? {
+                Some(op) if op.conflicted_files > 0 => Ok(e.to_string()),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Abort the operation in progress and restore the state before it.
+    pub fn abort_operation(&self) -> Result<String> {
+        let kind = self.current_operation()?;
+        if kind == "bisect" {
+            return self.run_git(&["bisect", "reset"]);
+        }
+        self.run_git(&[&kind, "--abort"])
+    }
+
+    /// The git command that drives the operation in progress.
+    fn current_operation(&self) -> Result<String> {
+        let Some(op) = self.operation_state()? else {
+            bail!("No merge, rebase, cherry-pick, revert, bisect or am is in progress");
+        };
+        Ok(op.kind)
+    }
+
+    fn read_rebase_merge(&self, dir: &Path, op: &mut OperationState) {
+        op.branch = read_trimmed(&dir.join("head-name"))
+            .map(|h| h.trim_start_matches("refs/heads/").to_string())
+            .filter(|h| h != "detached HEAD");
+        op.onto = read_trimmed(&dir.join("onto"));
+        op.step = read_number(&dir.join("msgnum"));
+        op.total_steps = read_number(&dir.join("end"));
+        op.done = self.read_todo(&dir.join("done"));
+        op.todo = self.read_todo(&dir.join("git-rebase-todo"));
+        if let Some(stopped) = read_trimmed(&dir.join("stopped-sha")) {
+            op.incoming = vec![self.full_sha(&stopped)];
+        }
+    }
+
+    /// Multi-commit cherry-pick/revert keep their remaining commits in
+    /// .git/sequencer/todo.
+    fn read_sequencer(&self, op: &mut OperationState) {
+        let dir = self.repo.path().join("sequencer");
+        if !dir.is_dir() {
+            return;
+        }
+        let todo = self.read_todo(&dir.join("todo"));
+        // The first entry is the commit being applied now.
+        let mut todo = todo.into_iter();
+        if let Some(current) = todo.next() {
+            op.done.push(current);
+        }
+        op.todo = todo.collect();
+    }
+
+    fn read_todo(&self, path: &Path) -> Vec<RebaseStep> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|line| self.parse_todo_line(line))
+            .collect()
+    }
+
+    fn parse_todo_line(&self, line: &str) -> RebaseStep {
+        let (action, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut step = RebaseStep {
+            action: action.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        };
+        if COMMIT_ACTIONS.contains(&action) {
+            // fixup -C/-c <commit> keeps that commit's message
+            let rest = rest
+                .strip_prefix("-C ")
+                .or_else(|| rest.strip_prefix("-c "))
+                .unwrap_or(rest);
+            let (sha, summary) = rest.split_once(' ').unwrap_or((rest, ""));
+            step.sha = Some(self.full_sha(sha));
+            step.summary =
+                Some(summary.trim_start_matches("# ").to_string()).filter(|s| !s.is_empty());
+        } else if !rest.is_empty() {
+            step.summary = Some(rest.to_string());
+        }
+        step
+    }
+
+    fn full_sha(&self, sha: &str) -> String {
+        self.repo
+            .revparse_single(sha)
+            .map(|o| o.id().to_string())
+            .unwrap_or_else(|_| sha.to_string())
+    }
+}
+
+/// `git am` and `git rebase --apply` number patches in rebase-apply/.
+fn read_rebase_apply(dir: &Path, op: &mut OperationState) {
+    op.branch = read_trimmed(&dir.join("head-name"))
+        .map(|h| h.trim_start_matches("refs/heads/").to_string())
+        .filter(|h| h != "detached HEAD");
+    op.onto = read_trimmed(&dir.join("onto"));
+    op.step = read_number(&dir.join("next"));
+    op.total_steps = read_number(&dir.join("last"));
+
+    let (Some(next), Some(last)) = (op.step, op.total_steps) else {
+        return;
+    };
+    let patch_step = |n: usize| {
+        let patch = fs::read_to_string(dir.join(format!("{:04}", n))).unwrap_or_default();
+        // Patches start with "From <sha> <date>" and a Subject: header.
+        let sha = patch
+            .lines()
+            .next()
+            .and_then(|l| l.strip_prefix("From "))
+            .and_then(|l| l.split(' ').next())
+            .map(|s| s.to_string());
+        let summary = patch
+            .lines()
+            .find_map(|l| l.strip_prefix("Subject: "))
+            .map(|s| s.trim_start_matches("[PATCH] ").to_string());
+        RebaseStep {
+            action: "apply".to_string(),
+            line: format!("{:04}", n),
+            sha,
+            summary,
+        }
+    };
+    op.done = (1..=next.min(last)).map(patch_step).collect();
+    op.todo = (next + 1..=last).map(patch_step).collect();
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn read_number(path: &Path) -> Option<usize> {
+    read_trimmed(path).and_then(|s| s.parse().ok())
+}
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, bail, Context, Result};
+use git2::{Oid, Sort};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+
+use super::jobs;
+use super::repository::{commit_from_git2, operation_name, GitRepository};
+use crate::models::{Commit, PullResult};
+
+/// Commits listed in a pull result; `commit_count` still has the full number.
+const MAX_PULLED_COMMITS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullMode {
+    FfOnly,
+    Merge,
+    Rebase,
+    RebaseMerges,
+}
+
+impl PullMode {
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "ff-only" => Ok(Self::FfOnly),
+            "merge" => Ok(Self::Merge),
+            "rebase" => Ok(Self::Rebase),
+            "rebase-merges" => Ok(Self::RebaseMerges),
+            other => bail!(
+                "Unknown pull mode '{}' (expected ff-only, merge, rebase or rebase-merges)",
+                other
+            ),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FfOnly => "ff-only",
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+            Self::RebaseMerges => "rebase-merges",
+        }
+    }
+}
+
+/// Options for pull
+#[derive(Debug, Default)]
+pub struct PullOptions {
+    /// None follows branch.<name>.rebase / pull.rebase / pull.ff like `git pull`.
+    pub mode: Option<PullMode>,
+    /// Stash local changes before integrating and reapply them afterwards.
+    /// None follows rebase.autoStash / merge.autoStash.
+    pub autostash: Option<bool>,
+}
+
+impl GitRepository {
+    /// Pull the current branch: fetch its upstream's remote with the
+    /// credential providers, then fast-forward, merge or rebase onto it.
+    /// Conflicts are not an error: the result lists them and the merge or
+    /// rebase is left in progress for the conflict endpoints.
+    pub fn pull(&self, opts: &PullOptions) -> Result<PullResult> {
+        let branch = self.current_branch_for_remote_op()?;
+        let config = self.repo.config()?.snapshot()?;
+        let remote = config
+            .get_string(&format!("branch.{}.remote", branch))
+            .map_err(|_| anyhow!("No upstream branch configured for '{}'", branch))?;
+
+        // "." means the upstream is a local branch; nothing to fetch.
+        if remote != "." {
+            self.fetch_remote(&remote, false)?;
+        }
+        jobs::set_stage("integrating");
+
+        let mode = opts
+            .mode
+            .unwrap_or_else(|| configured_mode(&config, &branch));
+        let head = self.repo.head()?.peel_to_commit()?.id();
+        let upstream = self
+            .repo
+            .revparse_single("@{u}")
+            .and_then(|o| o.peel_to_commit())
+            .with_context(|| format!("Upstream of '{}' not found on the remote", branch))?
+            .id();
+
+        let mut result = PullResult {
+            outcome: "up-to-date".to_string(),
+            mode: mode.as_str().to_string(),
+            upstream: self
+                .get_upstream(&branch)?
+                .unwrap_or_else(|| "@{u}".to_string()),
+            branch,
+            old_head: head.to_string(),
+            new_head: head.to_string(),
+            commits: Vec::new(),
+            commit_count: 0,
+            conflicts: Vec::new(),
+            in_progress: None,
+            autostash: None,
+            output: String::new(),
+        };
+        if upstream == head || self.repo.graph_descendant_of(head, upstream)? {
+            result.output = "Already up to date.\n".to_string();
+            return Ok(result);
+        }
+
+        let fast_forward = self.repo.graph_descendant_of(upstream, head)?;
+        let no_ff =
+            mode == PullMode::Merge && config.get_string("pull.ff").is_ok_and(|ff| ff == "false");
+        let mut args = match mode {
+            PullMode::FfOnly => {
+                if !fast_forward {
+                    let (ahead, behind) = self.repo.graph_ahead_behind(head, upstream)?;
+                    bail!(
+                        "Not possible to fast-forward: '{}' and '{}' have diverged ({} local and {} upstream commits). Pull with merge or rebase instead.",
+                        result.branch,
+                        result.upstream,
+                        ahead,
+                        behind
+                    );
+                }
+                vec!["merge", "--ff-only"]
+            }
+            PullMode::Merge if no_ff => vec!["merge", "--no-edit", "--no-ff"],
+            PullMode::Merge => vec!["merge", "--no-edit"],
+            PullMode::Rebase => vec!["rebase"],
+            PullMode::RebaseMerges => vec!["rebase", "--rebase-merges"],
+        };
+        match opts.autostash {
+            Some(true) => args.push("--autostash"),
+            Some(false) => args.push("--no-autostash"),
+            None => {}
+        }
+        args.push("@{u}");
+
+        let (commits, commit_count) = self.commits_brought_in(head, upstream)?;
+        result.commits = commits;
+        result.commit_count = commit_count;
+
+        let (success, output) = self.journaled(&format!("Pull {}", result.upstream), || {
+            self.run_git_combined(&args)
+        })?;
+        let autostashed = output.contains("Created autostash");
+        let autostash_conflicted = output.contains("Applying autostash resulted in conflicts");
+        result.output = output;
+
+        if !success {
+            let in_progress = operation_name(self.repo.state());
+            let conflicts = self.conflicted_files()?;
+            if in_progress.is_none() && conflicts.is_empty() {
+                bail!(result.output.trim().to_string());
+            }
+            result.outcome = "conflicted".to_string();
+            result.conflicts = conflicts;
+            result.in_progress = in_progress.map(|s| s.to_string());
+            // The stash is reapplied once the merge/rebase is finished or aborted
+            if autostashed {
+                result.autostash = Some("pending".to_string());
+            }
+        } else {
+            result.outcome = if fast_forward && !no_ff {
+                "fast-forwarded"
+            } else if mode == PullMode::Merge {
+                "merged"
+            } else {
+                "rebased"
+            }
+            .to_string();
+            if autostash_conflicted {
+                result.autostash = Some("conflicted".to_string());
+            } else if autostashed {
+                result.autostash = Some("applied".to_string());
+            }
+        }
+
+        result.new_head = self.repo.head()?.peel_to_commit()?.id().to_string();
+        Ok(result)
+    }
+
+    /// Commits reachable from `upstream` but not `head`, newest first.
+    fn commits_brought_in(&self, head: Oid, upstream: Oid) -> Result<(Vec<Commit>, usize)> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(upstream)?;
+        revwalk.hide(head)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        let mut commits = Vec::new();
+        let mut count = 0;
+        for oid in revwalk {
+            let oid = oid?;
+            count += 1;
+            if commits.len() < MAX_PULLED_COMMITS {
+                commits.push(commit_from_git2(&self.repo.find_commit(oid)?, Vec::new()));
+            }
+        }
+        Ok((commits, count))
+    }
+
+    /// Run git and return whether it succeeded with stdout and stderr combined;
+    /// merge and rebase report conflicts and autostash results on stderr.
+    /// Output is untranslated, since the autostash messages are matched.
+    fn run_git_combined(&self, args: &[&str]) -> Result<(bool, String)> {
+        let mut cmd = Command::new("git");
+        // Never wait for an editor; merge commits get git's default message.
+        cmd.args(args)
+            .current_dir(self.path())
+            .env("GIT_EDITOR", "true")
+            .env("LC_ALL", "C");
+
+        #[cfg(target_os = "windows")]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let output = cmd
+            .output()
+            .with_context(|| format!("Failed to run git with args {:?}", args))?;
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok((output.status.success(), text))
+    }
+}
+
+/// One-line summary of a pull for status messages.
+pub fn pull_summary(result: &PullResult) -> String {
+    let mut summary = match result.outcome.as_str() {
+        "up-to-date" => "Already up to date".to_string(),
+        "fast-forwarded" => format!(
+            "Fast-forwarded '{}' by {} commit(s)",
+            result.branch, result.commit_count
+        ),
+        "merged" => format!(
+            "Merged {} commit(s) from '{}'",
+            result.commit_count, result.upstream
+        ),
+        "rebased" => format!(
+            "Rebased '{}' onto '{}' ({} new commit(s))",
+            result.branch, result.upstream, result.commit_count
+        ),
+        _ => format!(
+            "Pull stopped with conflicts in {} file(s); resolve them, then continue or abort the {}",
+            result.conflicts.len(),
+            result.in_progress.as_deref().unwrap_or("operation")
+        ),
+    };
+    if result.autostash.as_deref() == Some("conflicted") {
+        summary.push_str(". Reapplying your local changes conflicted; they are kept in the stash");
+    }
+    summary
+}
+
+/// The mode plain `git pull` would use for `branch`.
+fn configured_mode(config: &git2::Config, branch: &str) -> PullMode {
+    let rebase = config
+        .get_string(&format!("branch.{}.rebase", branch))
+        .or_else(|_| config.get_string("pull.rebase"))
+        .unwrap_or_default();
+    match rebase.as_str() {
+        "merges" | "m" => PullMode::RebaseMerges,
+        "interactive" | "i" => PullMode::Rebase,
+        value if git2::Config::parse_bool(value).unwrap_or(false) => PullMode::Rebase,
+        _ if config.get_string("pull.ff").is_ok_and(|ff| ff == "only") => PullMode::FfOnly,
+        _ => PullMode::Merge,
+    }
+}
@@ -0,0 +1,365 @@
+use anyhow::{anyhow, bail, Context, Result};
+use git2::{BranchType, Direction, Oid, Sort};
+
+use super::credentials::CredentialSession;
+use super::remotes::PushSettings;
+use super::repository::{commit_from_git2, GitRepository};
+use crate::models::PushPreview;
+
+/// Commits listed in a push preview; `discarded_count` still has the full number.
+const MAX_DISCARDED_COMMITS: usize = 200;
+
+/// Options for pushing a branch
+#[derive(Debug, Default)]
+pub struct PushOptions {
+    /// Remote to push to; defaults to the branch's push remote.
+    pub remote: Option<String>,
+    /// Local branch to push; defaults to the current branch.
+    pub branch: Option<String>,
+    /// Branch name (or full ref) on the remote. Defaults to the upstream
+    /// branch when pushing to the upstream's remote, else the same name.
+    pub remote_branch: Option<String>,
+    /// Overwrite the remote branch, but only while it still points at
+    /// `expected_sha` (`--force-with-lease`).
+    pub force_with_lease: bool,
+    /// Where the remote branch must be for the lease to hold; defaults to
+    /// our remote-tracking ref for it.
+    pub expected_sha: Option<String>,
+    /// Also push all local tags.
+    pub tags: bool,
+    /// Skip the pre-push hook.
+    pub no_verify: bool,
+    /// Server-side push options (`git push -o`).
+    pub push_options: Vec<String>,
+}
+
+/// Where a push goes, resolved from PushOptions and the branch config.
+struct PushTarget {
+    remote: String,
+    branch: String,
+    local_ref: String,
+    remote_ref: String,
+    has_upstream: bool,
+}
+
+impl GitRepository {
+    /// Push a branch the way the push button does: to its upstream, to an
+    /// explicit remote without touching the upstream, or (for a branch that
+    /// doesn't track anything yet) while setting the upstream.
+    /// Returns the push output and a summary message.
+    pub fn push_branch(&self, opts: &PushOptions) -> Result<(String, String)> {
+        let target = self.push_target(opts)?;
+        let force = if opts.force_with_lease { "+" } else { "" };
+        let mut refspecs = vec![format!(
+            "{}{}:{}",
+            force, target.local_ref, target.remote_ref
+        )];
+        if opts.tags {
+            for tag in self.repo.tag_names(None)?.iter().flatten() {
+                refspecs.push(format!("refs/tags/{0}:refs/tags/{0}", tag));
+            }
+        }
+
+        let mut output = self.push_refspecs_with(
+            &target.remote,
+            &refspecs,
+            &self.push_settings(&target, opts)?,
+        )?;
+
+        let destination = format!(
+            "{}/{}",
+            target.remote,
+            target
+                .remote_ref
+                .strip_prefix("refs/heads/")
+                .unwrap_or(&target.remote_ref)
+        );
+        let message = if !target.has_upstream && target.remote_ref.starts_with("refs/heads/") {
+            let mut config = self.repo.config()?;
+            config.set_str(&format!("branch.{}.remote", target.branch), &target.remote)?;
+            config.set_str(
+                &format!("branch.{}.merge", target.branch),
+                &target.remote_ref,
+            )?;
+            output.push_str(&format!(
+                "branch '{}' set up to track '{}'.\n",
+                target.branch, destination
+            ));
+            format!(
+                "Pushed and set upstream for branch '{}' to '{}'",
+                target.branch, target.remote
+            )
+        } else if opts.force_with_lease {
+            format!("Force-pushed '{}' to '{}'", target.branch, destination)
+        } else if opts.remote.is_none() && opts.branch.is_none() && opts.remote_branch.is_none() {
+            "Push successful".to_string()
+        } else {
+            format!("Pushed '{}' to '{}'", target.branch, destination)
+        };
+        Ok((output, message))
+    }
+
+    /// Delete a branch (or full ref) on a remote. With `force_with_lease`
+    /// the delete only goes through while it still points at the expected SHA.
+    pub fn delete_remote_ref(&self, opts: &PushOptions) -> Result<String> {
+        let name = opts
+            .remote_branch
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("Remote branch to delete is required"))?;
+        let remote = match opts.remote.as_deref() {
+            Some(remote) => remote.to_string(),
+            None => self.default_push_remote()?,
+        };
+        let target = PushTarget {
+            remote_ref: full_remote_ref(name),
+            remote,
+            branch: String::new(),
+            local_ref: String::new(),
+            has_upstream: true,
+        };
+        self.push_refspecs_with(
+            &target.remote,
+            &[format!(":{}", target.remote_ref)],
+            &self.push_settings(&target, opts)?,
+        )
+    }
+
+    /// What pushing with `opts` would do to the remote branch, checked
+    /// against what the remote currently has. Lists the commits a force push
+    /// would discard and warns when some of them were never in our local history.
+    pub fn push_preview(&self, opts: &PushOptions) -> Result<PushPreview> {
+        let target = self.push_target(opts)?;
+        let local = self.repo.refname_to_id(&target.local_ref)?;
+        let remote_oid = self.advertised_oid(&target.remote, &target.remote_ref)?;
+        let tracking = self.tracking_oid(&target.remote, &target.remote_ref)?;
+        let expected = match opts.expected_sha.as_deref().filter(|s| !s.is_empty()) {
+            Some(sha) => Some(self.resolve_expected_sha(sha)?),
+            None => tracking.map(|oid| oid.unwrap_or_else(Oid::zero)),
+        };
+
+        let mut preview = PushPreview {
+            remote: target.remote.clone(),
+            branch: target.branch.clone(),
+            remote_ref: target.remote_ref.clone(),
+            local_sha: local.to_string(),
+            remote_sha: remote_oid.map(|oid| oid.to_string()),
+            tracking_sha: tracking.flatten().map(|oid| oid.to_string()),
+            expected_sha: expected
+                .filter(|oid| !oid.is_zero())
+                .map(|oid| oid.to_string()),
+            lease_ok: expected.is_some_and(|oid| oid == remote_oid.unwrap_or_else(Oid::zero)),
+            up_to_date: remote_oid == Some(local),
+            fast_forward: true,
+            remote_known: true,
+            discarded: Vec::new(),
+            discarded_count: 0,
+            not_in_local_history: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        if let Some(remote_oid) = remote_oid.filter(|oid| *oid != local) {
+            if self.repo.find_commit(remote_oid).is_err() {
+                preview.remote_known = false;
+                preview.fast_forward = false;
+                preview.warnings.push(format!(
+                    "'{}' is at {}, which has not been fetched; a force push would discard commits you have never seen",
+                    preview.remote_ref, remote_oid
+                ));
+            } else {
+                preview.fast_forward = self.repo.graph_descendant_of(local, remote_oid)?;
+                self.fill_discarded(&mut preview, &target, local, remote_oid)?;
+            }
+        }
+        if !preview.lease_ok && !preview.up_to_date {
+            preview.warnings.push(match expected {
+                Some(oid) if oid.is_zero() => {
+                    "The remote branch was created since your last fetch; a push with lease would be rejected"
+                        .to_string()
+                }
+                Some(oid) => format!(
+                    "The remote branch moved away from {}; a push with lease would be rejected",
+                    oid
+                ),
+                None => "No remote-tracking ref for the remote branch; give the expected SHA for a push with lease"
+                    .to_string(),
+            });
+        }
+        Ok(preview)
+    }
+
+    fn push_target(&self, opts: &PushOptions) -> Result<PushTarget> {
+        let branch = match opts.branch.as_deref().filter(|b| !b.is_empty()) {
+            Some(branch) => branch.to_string(),
+            None => self.current_branch_for_remote_op()?,
+        };
+        self.repo
+            .find_branch(&branch, BranchType::Local)
+            .with_context(|| format!("Branch '{}' not found", branch))?;
+
+        let config = self.repo.config()?.snapshot()?;
+        let upstream_remote = config
+            .get_string(&format!("branch.{}.remote", branch))
+            .ok()
+            .filter(|remote| remote != ".");
+        let upstream_merge = config.get_string(&format!("branch.{}.merge", branch)).ok();
+        let remote = match opts.remote.as_deref().filter(|r| !r.is_empty()) {
+            Some(remote) => remote.to_string(),
+            None => self.push_remote_for(Some(&branch))?,
+        };
+
+        let remote_ref = match opts.remote_branch.as_deref().filter(|b| !b.is_empty()) {
+            Some(name) => full_remote_ref(name),
+            None => match &upstream_merge {
+                Some(merge) if upstream_remote.as_deref() == Some(remote.as_str()) => merge.clone(),
+                _ => format!("refs/heads/{}", branch),
+            },
+        };
+        Ok(PushTarget {
+            local_ref: format!("refs/heads/{}", branch),
+            has_upstream: upstream_remote.is_some() && upstream_merge.is_some(),
+            remote,
+            branch,
+            remote_ref,
+        })
+    }
+
+    fn push_settings(&self, target: &PushTarget, opts: &PushOptions) -> Result<PushSettings> {
+        let mut leases = Vec::new();
+        if opts.force_with_lease {
+            let expected = match opts.expected_sha.as_deref().filter(|s| !s.is_empty()) {
+                Some(sha) => self.resolve_expected_sha(sha)?,
+                None => match self.tracking_oid(&target.remote, &target.remote_ref)? {
+                    // Never fetched: the branch must not exist on the remote either.
+                    Some(oid) => oid.unwrap_or_else(Oid::zero),
+                    None => bail!(
+                        "No remote-tracking ref for '{}' on '{}'; give the expected SHA for the lease",
+                        target.remote_ref,
+                        target.remote
+                    ),
+                },
+            };
+            leases.push((target.remote_ref.clone(), expected));
+        }
+        Ok(PushSettings {
+            leases,
+            no_verify: opts.no_verify,
+            push_options: opts.push_options.clone(),
+        })
+    }
+
+    /// A full SHA is taken as is, so it may name a commit we don't have;
+    /// anything else is resolved locally.
+    fn resolve_expected_sha(&self, sha: &str) -> Result<Oid> {
+        if sha.len() == 40 && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(Oid::from_str(sha)?);
+        }
+        Ok(self
+            .repo
+            .revparse_single(sha)
+            .with_context(|| format!("Expected SHA '{}' not found", sha))?
+            .peel_to_commit()?
+            .id())
+    }
+
+    /// Where our remote-tracking ref for `remote_ref` points (None inside if
+    /// it was never fetched), or None if the fetch refspecs don't map it.
+    fn tracking_oid(&self, remote: &str, remote_ref: &str) -> Result<Option<Option<Oid>>> {
+        let remote = self.repo.find_remote(remote)?;
+        for spec in remote.refspecs() {
+            if spec.direction() != Direction::Fetch || !spec.src_matches(remote_ref) {
+                continue;
+            }
+            let tracking = spec.transform(remote_ref)?;
+            let Some(tracking) = tracking.as_str() else {
+                continue;
+            };
+            return Ok(Some(self.repo.refname_to_id(tracking).ok()));
+        }
+        Ok(None)
+    }
+
+    /// Where `remote_ref` currently points on the remote, asked over the wire.
+    fn advertised_oid(&self, name: &str, remote_ref: &str) -> Result<Option<Oid>> {
+        let mut remote = self.repo.find_remote(name)?;
+        let session = CredentialSession::new(&self.repo, true);
+        let connection = remote.connect_auth(Direction::Push, Some(session.callbacks()), None);
+        let connection = session.finish(connection)?;
+        let oid = connection
+            .list()?
+            .iter()
+            .find(|head| head.name() == remote_ref)
+            .map(|head| head.oid());
+        Ok(oid)
+    }
+
+    /// Commits reachable from the remote branch but not from what we push.
+    /// Those not reachable from any local branch or from the reflog of the
+    /// pushed branch and HEAD (e.g. pre-rebase versions) were never ours.
+    fn fill_discarded(
+        &self,
+        preview: &mut PushPreview,
+        target: &PushTarget,
+        local: Oid,
+        remote_oid: Oid,
+    ) -> Result<()> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(remote_oid)?;
+        revwalk.hide(local)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        for oid in revwalk {
+            let oid = oid?;
+            preview.discarded_count += 1;
+            if preview.discarded.len() < MAX_DISCARDED_COMMITS {
+                preview
+                    .discarded
+                    .push(commit_from_git2(&self.repo.find_commit(oid)?, Vec::new()));
+            }
+        }
+        if preview.discarded_count == 0 {
+            return Ok(());
+        }
+
+        let mut foreign = self.repo.revwalk()?;
+        foreign.push(remote_oid)?;
+        foreign.hide(local)?;
+        for branch in self.repo.branches(Some(BranchType::Local))? {
+            if let Some(oid) = branch?.0.get().target() {
+                foreign.hide(oid)?;
+            }
+        }
+        for refname in [target.local_ref.as_str(), "HEAD"] {
+            let Ok(reflog) = self.repo.reflog(refname) else {
+                continue;
+            };
+            for entry in reflog.iter() {
+                for oid in [entry.id_old(), entry.id_new()] {
+                    // Old entries may point at commits that were since pruned.
+                    if !oid.is_zero() && self.repo.find_commit(oid).is_ok() {
+                        foreign.hide(oid)?;
+                    }
+                }
+            }
+        }
+        for oid in foreign {
+            preview.not_in_local_history.push(oid?.to_string());
+        }
+        if !preview.not_in_local_history.is_empty() {
+            preview.warnings.push(format!(
+                "Force pushing would discard {} commit(s) on '{}' that are not in your local history",
+                preview.not_in_local_history.len(),
+                preview.remote_ref
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// "main" means the remote branch; full refs are used as given.
+fn full_remote_ref(name: &str) -> String {
+    if name.starts_with("refs/") {
+        name.to_string()
+    } else {
+        format!("refs/heads/{}", name)
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::{bail, Context, Result};
+use chrono::DateTime;
+use git2::Oid;
+use std::collections::HashSet;
+
+use super::repository::{commit_from_git2, GitRepository};
+use crate::models::{LostCommit, ReflogEntry};
+
+/// Lost commits listed at most, newest first.
+const MAX_LOST_COMMITS: usize = 500;
+
+impl GitRepository {
+    /// Entries of the reflog of HEAD (default) or a branch, newest first.
+    pub fn reflog(&self, name: Option<&str>, limit: Option<usize>) -> Result<Vec<ReflogEntry>> {
+        let refname = self.reflog_ref(name)?;
+        let short = short_name(&refname);
+        let reflog = self.repo.reflog(&refname)?;
+        Ok(reflog
+            .iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(index, entry)| {
+                let message = entry.message().unwrap_or_default().to_string();
+                let committer = entry.committer();
+                let date = DateTime::from_timestamp(committer.when().seconds(), 0)
+                    .unwrap_or_default()
+                    .to_rfc3339();
+                ReflogEntry {
+                    selector: format!("{}@{{{}}}", short, index),
+                    index,
+                    old_sha: Some(entry.id_old())
+                        .filter(|o| !o.is_zero())
+                        .map(|o| o.to_string()),
+                    new_sha: entry.id_new().to_string(),
+                    action: reflog_action(&message).to_string(),
+                    message,
+                    committer: committer.name().unwrap_or_default().to_string(),
+                    email: committer.email().unwrap_or_default().to_string(),
+                    date,
+                }
+            })
+            .collect())
+    }
+
+    /// Move HEAD or a local branch back to where the given reflog entry left
+    /// it. The checked-out branch is moved with `reset --keep`, which refuses
+    /// to overwrite local changes. Journaled, so it can be undone.
+    pub fn restore_reflog_entry(&self, name: Option<&str>, index: usize) -> Result<String> {
+        let refname = self.reflog_ref(name)?;
+        if refname != "HEAD" && !refname.starts_with("refs/heads/") {
+            bail!("Only HEAD and local branches can be restored");
+        }
+        let short = short_name(&refname);
+        let target = self
+            .repo
+            .reflog(&refname)?
+            .get(index)
+            .map(|e| e.id_new())
+            .with_context(|| format!("{} has no reflog entry {}", short, index))?;
+        if target.is_zero() {
+            bail!(
+                "{}@{{{}}} deleted the ref; there is nothing to restore",
+                short,
+                index
+            );
+        }
+        let selector = format!("{}@{{{}}}", short, index);
+        let label = format!("Restore {} to {}", short, selector);
+
+        let checked_out = refname == "HEAD"
+            || self.repo.find_reference("HEAD")?.symbolic_target() == Some(refname.as_str());
+        if checked_out {
+            self.journaled(&label, || {
+                self.run_git(&["reset", "--keep", &target.to_string()])
+            })
+        } else {
+            self.journaled(&label, || {
+                self.repo.reference(
+                    &refname,
+                    target,
+                    true,
+                    &format!("gitpow: restore to {}", selector),
+                )?;
+                Ok(format!("{} now points at {}", short, target))
+            })
+        }
+    }
+
+    /// Commits no branch, tag or stash entry reaches, including ones only
+    /// the reflogs still remember (e.g. after a reset or amend), and dropped
+    /// stashes. Newest first.
+    pub fn lost_commits(&self) -> Result<Vec<LostCommit>> {
+        // Older stashes only live in the stash reflog.
+        let stashes: HashSet<Oid> = match self.repo.reflog("refs/stash") {
+            Ok(reflog) => reflog.iter().map(|e| e.id_new()).collect(),
+            Err(_) => HashSet::new(),
+        };
+
+        // Undo journal snapshots keep the HEAD they replaced as a parent, so
+        // fsck alone would treat commits dropped by a reset or amend as
+        // reachable for as long as the journal remembers them. Walk the
+        // reflogs against the real refs instead; listed stashes count as
+        // reachable so their index and untracked commits aren't reported.
+        let stash_args: Vec<String> = stashes.iter().map(|oid| oid.to_string()).collect();
+        let mut args = vec![
+            "rev-list",
+            "--reflog",
+            "--not",
+            "--exclude=refs/gitpow/*",
+            "--all",
+        ];
+        args.extend(stash_args.iter().map(|s| s.as_str()));
+        let from_reflogs = self.run_git(&args)?;
+        // Dropped stashes and other objects no reflog mentions.
+        let dangling = self.run_git(&["fsck", "--no-reflogs", "--dangling", "--no-progress"])?;
+
+        let shas = from_reflogs.lines().chain(
+            dangling
+                .lines()
+                .filter_map(|line| line.strip_prefix("dangling commit ")),
+        );
+        let mut seen = HashSet::new();
+        let mut commits = Vec::new();
+        for sha in shas {
+            let Ok(oid) = Oid::from_str(sha.trim()) else {
+                continue;
+            };
+            if !seen.insert(oid) {
+                continue;
+            }
+            let Ok(commit) = self.repo.find_commit(oid) else {
+                continue;
+            };
+            // Skip stashes still listed and undo journal snapshots.
+            if stashes.contains(&commit.id())
+                || commit.committer().email() == Some("gitpow@localhost")
+            {
+                continue;
+            }
+            commits.push(commit);
+        }
+        commits.sort_by_key(|c| std::cmp::Reverse(c.time().seconds()));
+        commits.truncate(MAX_LOST_COMMITS);
+
+        Ok(commits
+            .iter()
+            .map(|commit| LostCommit {
+                kind: if is_stash_commit(commit) {
+                    "stash"
+                } else {
+                    "commit"
+                }
+                .to_string(),
+                commit: commit_from_git2(commit, Vec::new()),
+            })
+            .collect())
+    }
+
+    /// Make a lost commit reachable again: as a new branch when a name is
+    /// given, otherwise a dropped stash goes back on the stash list.
+    pub fn recover_commit(&self, sha: &str, branch: Option<&str>) -> Result<String> {
+        let commit = self.repo.revparse_single(sha)?.peel_to_commit()?;
+        let sha = commit.id().to_string();
+        match branch.map(str::trim).filter(|b| !b.is_empty()) {
+            Some(branch) => self.journaled(&format!("Recover {} as {}", &sha[..7], branch), || {
+                self.run_git(&["branch", "--", branch, &sha])
+            }),
+            None if is_stash_commit(&commit) => {
+                let message = commit.summary().unwrap_or_default().to_string();
+                self.journaled(&format!("Recover stash {}", &sha[..7]), || {
+                    self.run_git(&["stash", "store", "-m", &message, &sha])
+                })
+            }
+            None => bail!("Give a branch name to recover this commit"),
+        }
+    }
+
+    /// HEAD, a full ref name, or a short branch name (local before remote).
+    fn reflog_ref(&self, name: Option<&str>) -> Result<String> {
+        let name = name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or("HEAD");
+        if name == "HEAD" || name.starts_with("refs/") {
+            return Ok(name.to_string());
+        }
+        for prefix in ["refs/heads/", "refs/remotes/"] {
+            let refname = format!("{}{}", prefix, name);
+            if self.repo.find_reference(&refname).is_ok() {
+                return Ok(refname);
+            }
+        }
+        bail!("No branch named '{}'", name)
+    }
+}
+
+fn short_name(refname: &str) -> &str {
+    refname
+        .strip_prefix("refs/heads/")
+        .or_else(|| refname.strip_prefix("refs/remotes/"))
+        .unwrap_or(refname)
+}
+
+/// A stash commit has the index commit ("index on ...") as second parent.
+fn is_stash_commit(commit: &git2::Commit) -> bool {
+    commit.parent_count() >= 2
+        && commit
+            .parent(1)
+            .ok()
+            .and_then(|p| p.message().map(|m| m.starts_with("index on ")))
+            .unwrap_or(false)
+}
+
+/// Reflog messages start with the command, e.g. "commit (amend): ...",
+/// "rebase (pick): ...", "merge feature: Fast-forward" or "update by push".
+fn reflog_action(message: &str) -> &'static str {
+    let command = message.split(':').next().unwrap_or_default();
+    let word = command.split_whitespace().next().unwrap_or_default();
+    match word {
+        "commit" if command.contains("(amend)") => "amend",
+        "commit" => "commit",
+        "checkout" => "checkout",
+        "rebase" => "rebase",
+        "pull" if command.contains("--rebase") || command.contains("(") => "rebase",
+        "pull" => "pull",
+        "reset" => "reset",
+        "merge" => "merge",
+        "cherry-pick" => "cherry-pick",
+        "revert" => "revert",
+        "branch" | "Branch" => "branch",
+        "clone" => "clone",
+        "fetch" => "fetch",
+        "update" if message.starts_with("update by push") => "push",
+        _ => "other",
+    }
+}
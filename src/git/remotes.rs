@@ -0,0 +1,472 @@
+use anyhow::{bail, Context, Result};
+use git2::{Direction, FetchPrune, Oid, PushUpdate};
+use std::collections::HashSet;
+use std::io::Write;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+
+use super::credentials::{CredentialSession, CredentialsRequired};
+use super::repository::GitRepository;
+use crate::models::{RemoteFetchResult, RemoteInfo, StaleRemoteBranch};
+
+/// Extra checks and options for [`GitRepository::push_refspecs_with`].
+#[derive(Debug, Default)]
+pub(crate) struct PushSettings {
+    /// Remote ref name and the SHA it must still point at (zero: must not
+    /// exist) for the push to go ahead, like `--force-with-lease=<ref>:<sha>`.
+    pub leases: Vec<(String, Oid)>,
+    /// Skip the pre-push hook, like `--no-verify`.
+    pub no_verify: bool,
+    /// Server-side push options (`git push -o`).
+    pub push_options: Vec<String>,
+}
+
+fn short_oid(oid: Oid) -> String {
+    if oid.is_zero() {
+        "(none)".to_string()
+    } else {
+        oid.to_string()[..7].to_string()
+    }
+}
+
+fn string_array(array: Result<git2::string_array::StringArray, git2::Error>) -> Vec<String> {
+    array
+        .map(|a| a.iter().flatten().map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+impl GitRepository {
+    /// List configured remotes with their URLs and refspecs.
+    pub fn list_remotes(&self) -> Result<Vec<RemoteInfo>> {
+        let names = self.repo.remotes()?;
+        let mut remotes = Vec::new();
+        for name in names.iter().flatten() {
+            let remote = self.repo.find_remote(name)?;
+            remotes.push(RemoteInfo {
+                name: name.to_string(),
+                url: remote.url().map(|s| s.to_string()),
+                push_url: remote.pushurl().map(|s| s.to_string()),
+                fetch_refspecs: string_array(remote.fetch_refspecs()),
+                push_refspecs: string_array(remote.push_refspecs()),
+            });
+        }
+        Ok(remotes)
+    }
+
+    /// Add a remote. Without `fetch_refspec` git's default
+    /// `+refs/heads/*:refs/remotes/<name>/*` is used.
+    pub fn add_remote(&self, name: &str, url: &str, fetch_refspec: Option<&str>) -> Result<()> {
+        if !git2::Remote::is_valid_name(name) {
+            bail!("'{}' is not a valid remote name", name);
+        }
+        if url.trim().is_empty() {
+            bail!("remote URL is required");
+        }
+        match fetch_refspec {
+            Some(spec) => self.repo.remote_with_fetch(name, url, spec)?,
+            None => self.repo.remote(name, url)?,
+        };
+        Ok(())
+    }
+
+    /// Remove a remote together with its remote-tracking branches.
+    pub fn remove_remote(&self, name: &str) -> Result<()> {
+        self.repo.remote_delete(name)?;
+        Ok(())
+    }
+
+    /// Rename a remote and its remote-tracking branches. Returns the
+    /// non-default fetch refspecs git could not rewrite automatically.
+    pub fn rename_remote(&self, name: &str, new_name: &str) -> Result<Vec<String>> {
+        if !git2::Remote::is_valid_name(new_name) {
+            bail!("'{}' is not a valid remote name", new_name);
+        }
+        Ok(string_array(self.repo.remote_rename(name, new_name)))
+    }
+
+    /// Change a remote's fetch URL, or its push URL with `push`.
+    /// An empty push URL removes the override so pushes use the fetch URL.
+    pub fn set_remote_url(&self, name: &str, url: &str, push: bool) -> Result<()> {
+        self.repo.find_remote(name)?;
+        if push {
+            let url = Some(url).filter(|u| !u.is_empty());
+            self.repo.remote_set_pushurl(name, url)?;
+        } else {
+            if url.trim().is_empty() {
+                bail!("remote URL is required");
+            }
+            self.repo.remote_set_url(name, url)?;
+        }
+        Ok(())
+    }
+
+    /// Replace the fetch refspecs of a remote, and its push refspecs when given.
+    pub fn set_remote_refspecs(
+        &self,
+        name: &str,
+        fetch: &[String],
+        push: Option<&[String]>,
+    ) -> Result<()> {
+        self.repo.find_remote(name)?;
+        let mut config = self.repo.config()?;
+
+        // remove_multivar fails with NotFound when the key is unset; that's fine.
+        let _ = config.remove_multivar(&format!("remote.{}.fetch", name), ".*");
+        for spec in fetch {
+            self.repo.remote_add_fetch(name, spec)?;
+        }
+        if let Some(push) = push {
+            let _ = config.remove_multivar(&format!("remote.{}.push", name), ".*");
+            for spec in push {
+                self.repo.remote_add_push(name, spec)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remote to push to when none is chosen: the branch's pushRemote,
+    /// remote.pushDefault, the branch's upstream remote, "origin", or the only remote.
+    pub fn default_push_remote(&self) -> Result<String> {
+        let branch = self.get_current_branch().ok();
+        self.push_remote_for(branch.as_deref())
+    }
+
+    /// [`default_push_remote`](Self::default_push_remote) for any local branch.
+    pub(crate) fn push_remote_for(&self, branch: Option<&str>) -> Result<String> {
+        let config = self.repo.config()?.snapshot()?;
+        if let Some(branch) = branch {
+            for key in [
+                format!("branch.{}.pushRemote", branch),
+                "remote.pushDefault".to_string(),
+                format!("branch.{}.remote", branch),
+            ] {
+                if let Ok(remote) = config.get_string(&key) {
+                    if remote != "." {
+                        return Ok(remote);
+                    }
+                }
+            }
+        }
+
+        let names = self.repo.remotes()?;
+        let names: Vec<&str> = names.iter().flatten().collect();
+        if names.contains(&"origin") {
+            return Ok("origin".to_string());
+        }
+        match names.first() {
+            Some(name) => Ok(name.to_string()),
+            None => bail!("No remotes configured"),
+        }
+    }
+
+    /// Fetch a single remote. With `prune`, remote-tracking branches whose
+    /// source no longer exists on the remote are deleted.
+    pub fn fetch_remote(&self, name: &str, prune: bool) -> Result<RemoteFetchResult> {
+        self.fetch_remote_with(name, prune, true)
+    }
+
+    /// Fetch every remote. Without `interactive` (background refreshes),
+    /// remotes that need credentials the user hasn't supplied are skipped.
+    pub fn fetch_all(&self, interactive: bool) -> Result<()> {
+        let remotes = self.repo.remotes()?;
+        for remote_name in remotes.iter().flatten() {
+            if let Err(e) = self.fetch_remote_with(remote_name, false, interactive) {
+                if !interactive && e.downcast_ref::<CredentialsRequired>().is_some() {
+                    tracing::warn!("Skipping fetch of '{}': {}", remote_name, e);
+                    continue;
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch a single remote; without `interactive`, a remote that needs
+    /// credentials fails with [`CredentialsRequired`] instead of prompting.
+    pub(crate) fn fetch_remote_with(
+        &self,
+        name: &str,
+        prune: bool,
+        interactive: bool,
+    ) -> Result<RemoteFetchResult> {
+        let mut remote = self.repo.find_remote(name)?;
+        let session = CredentialSession::new(&self.repo, interactive);
+
+        let mut updated = Vec::new();
+        let mut pruned = Vec::new();
+        let result = {
+            let mut callbacks = session.callbacks();
+            callbacks.update_tips(|refname, _old, new| {
+                // Pruned refs are reported as updates to the zero OID.
+                if new.is_zero() {
+                    pruned.push(refname.to_string());
+                } else {
+                    updated.push(refname.to_string());
+                }
+                true
+            });
+
+            let mut fetch_options = git2::FetchOptions::new();
+            fetch_options.remote_callbacks(callbacks);
+            fetch_options.prune(if prune {
+                FetchPrune::On
+            } else {
+                FetchPrune::Unspecified
+            });
+            remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)
+        };
+        session.finish(result)?;
+
+        Ok(RemoteFetchResult {
+            remote: name.to_string(),
+            received_objects: remote.stats().received_objects(),
+            updated,
+            pruned,
+        })
+    }
+
+    /// Push refspecs through libgit2 so pushes use the same credential
+    /// providers as fetches. The pre-push hook runs like it does for `git push`.
+    pub fn push_refspecs(&self, remote_name: &str, refspecs: &[String]) -> Result<String> {
+        self.push_refspecs_with(remote_name, refspecs, &PushSettings::default())
+    }
+
+    /// [`push_refspecs`](Self::push_refspecs) with leases, hook skipping and
+    /// server-side push options.
+    pub(crate) fn push_refspecs_with(
+        &self,
+        remote_name: &str,
+        refspecs: &[String],
+        settings: &PushSettings,
+    ) -> Result<String> {
+        let mut remote = self.repo.find_remote(remote_name)?;
+        let url = remote
+            .pushurl()
+            .or_else(|| remote.url())
+            .unwrap_or_default()
+            .to_string();
+        let session = CredentialSession::new(&self.repo, true);
+
+        let mut pushed = Vec::new();
+        let mut rejected = Vec::new();
+        let mut hook_error = None;
+        let mut stale_leases = Vec::new();
+        let result = {
+            let mut callbacks = session.callbacks();
+            callbacks.push_negotiation(|updates| {
+                // Checked against what the remote advertised on this connection,
+                // so nothing can slip in between the check and the push.
+                for update in updates {
+                    let dst_refname = update.dst_refname().unwrap_or_default();
+                    if let Some((_, expected)) = settings
+                        .leases
+                        .iter()
+                        .find(|(refname, _)| refname == dst_refname)
+                    {
+                        if update.src() != *expected {
+                            stale_leases.push(format!(
+                                "{} is at {} but expected {}",
+                                dst_refname,
+                                short_oid(update.src()),
+                                short_oid(*expected)
+                            ));
+                        }
+                    }
+                }
+                if !stale_leases.is_empty() {
+                    return Err(git2::Error::from_str("stale info"));
+                }
+                if settings.no_verify {
+                    return Ok(());
+                }
+                self.run_pre_push_hook(remote_name, &url, updates)
+                    .map_err(|e| {
+                        hook_error = Some(e.to_string());
+                        git2::Error::from_str("pre-push hook declined the push")
+                    })
+            });
+            callbacks.push_update_reference(|refname, status| {
+                match status {
+                    Some(reason) => rejected.push(format!("{} ({})", refname, reason)),
+                    None => pushed.push(refname.to_string()),
+                }
+                Ok(())
+            });
+
+            let server_options: Vec<&str> =
+                settings.push_options.iter().map(|o| o.as_str()).collect();
+            let mut push_options = git2::PushOptions::new();
+            push_options.remote_callbacks(callbacks);
+            push_options.remote_push_options(&server_options);
+            remote.push(refspecs, Some(&mut push_options))
+        };
+        if !stale_leases.is_empty() {
+            bail!(
+                "Push rejected (stale info): {}. Fetch and review the remote changes first",
+                stale_leases.join(", ")
+            );
+        }
+        if let Some(hook_error) = hook_error {
+            bail!("pre-push hook failed: {}", hook_error);
+        }
+        session.finish(result)?;
+
+        if !rejected.is_empty() {
+            bail!("Push rejected: {}", rejected.join(", "));
+        }
+        let mut output = format!("To {}\n", url);
+        for refname in pushed {
+            output.push_str(&format!("   {}\n", refname));
+        }
+        Ok(output)
+    }
+
+    /// Run `.git/hooks/pre-push` (or core.hooksPath) with git's arguments and
+    /// stdin format. A non-zero exit aborts the push.
+    fn run_pre_push_hook(
+        &self,
+        remote_name: &str,
+        url: &str,
+        updates: &[PushUpdate],
+    ) -> Result<()> {
+        let hooks_dir = match self.repo.config()?.get_path("core.hooksPath") {
+            Ok(dir) if dir.is_relative() => self.path().join(dir),
+            Ok(dir) => dir,
+            Err(_) => self.repo.path().join("hooks"),
+        };
+        let hook = hooks_dir.join("pre-push");
+        if !is_executable(&hook) {
+            return Ok(());
+        }
+
+        // <local ref> SP <local sha> SP <remote ref> SP <remote sha> LF
+        let mut input = String::new();
+        for update in updates {
+            let local_ref = if update.dst().is_zero() {
+                "(delete)"
+            } else {
+                update.src_refname().unwrap_or_default()
+            };
+            input.push_str(&format!(
+                "{} {} {} {}\n",
+                local_ref,
+                update.dst(),
+                update.dst_refname().unwrap_or_default(),
+                update.src()
+            ));
+        }
+
+        // Hooks are shell scripts; Git for Windows runs them through sh as well.
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("sh");
+            cmd.arg(&hook);
+            cmd
+        } else {
+            Command::new(&hook)
+        };
+        cmd.args([remote_name, url])
+            .current_dir(self.path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let mut child = cmd.spawn().context("Failed to run pre-push hook")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // The hook may exit without reading its input; that's not an error.
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let mut message = String::from_utf8_lossy(&output.stderr).to_string();
+            message.push_str(&String::from_utf8_lossy(&output.stdout));
+            bail!(message.trim().to_string());
+        }
+        Ok(())
+    }
+
+    /// Remote-tracking branches of `name` whose source branch no longer
+    /// exists on the remote, i.e. what a pruning fetch would delete.
+    pub fn stale_remote_branches(&self, name: &str) -> Result<Vec<StaleRemoteBranch>> {
+        let mut remote = self.repo.find_remote(name)?;
+
+        // Map each local remote-tracking ref back to the remote ref it mirrors.
+        let mut tracking = Vec::new();
+        for spec in remote.refspecs() {
+            if spec.direction() != Direction::Fetch {
+                continue;
+            }
+            for reference in self.repo.references()? {
+                let reference = reference?;
+                if reference.kind() != Some(git2::ReferenceType::Direct) {
+                    continue;
+                }
+                let Some(ref_name) = reference.name() else {
+                    continue;
+                };
+                if !spec.dst_matches(ref_name) {
+                    continue;
+                }
+                let source = spec.rtransform(ref_name)?;
+                tracking.push((
+                    ref_name.to_string(),
+                    source.as_str().unwrap_or_default().to_string(),
+                    reference
+                        .target()
+                        .map(|o| o.to_string())
+                        .unwrap_or_default(),
+                ));
+            }
+        }
+
+        let session = CredentialSession::new(&self.repo, true);
+        let advertised: HashSet<String> = {
+            let connection = remote.connect_auth(Direction::Fetch, Some(session.callbacks()), None);
+            let connection = session
+                .finish(connection)
+                .with_context(|| format!("Failed to connect to remote '{}'", name))?;
+            connection
+                .list()?
+                .iter()
+                .map(|head| head.name().to_string())
+                .collect()
+        };
+
+        let mut seen = HashSet::new();
+        let mut stale: Vec<StaleRemoteBranch> = tracking
+            .into_iter()
+            .filter(|(ref_name, source, _)| {
+                !advertised.contains(source) && seen.insert(ref_name.clone())
+            })
+            .map(|(ref_name, _, sha)| StaleRemoteBranch {
+                remote: name.to_string(),
+                name: ref_name
+                    .strip_prefix("refs/remotes/")
+                    .unwrap_or(&ref_name)
+                    .to_string(),
+                ref_name,
+                sha,
+            })
+            .collect();
+        stale.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(stale)
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
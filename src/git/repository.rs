@@ -1,13 +1,13 @@
 use anyhow::{bail, Context, Result};
 use chrono::DateTime;
-use git2::{self, BranchType, Cred, Oid, RemoteCallbacks, Repository, Sort};
+use git2::{self, BranchType, Oid, Repository, RepositoryState, Sort};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use crate::models::{BranchInfo, BranchMetadata, Commit, StashEntry};
+use crate::models::{BranchInfo, BranchMetadata, Commit, StashEntry, SubmoduleDiff};
 
 /// Run a git command in the specified directory and return stdout as a String.
 /// This is a standalone utility for handlers that don't need a full GitRepository.
@@ -30,6 +30,48 @@ pub fn run_git(args: &[&str], repo_path: &Path) -> Result<String, String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Build the API commit model from a libgit2 commit, without graph annotations.
+pub(crate) fn commit_from_git2(commit: &git2::Commit, branches: Vec<String>) -> Commit {
+    // Convert git2::Time to an RFC3339 string for the frontend.
+    let seconds = commit.time().seconds();
+    let date_time = DateTime::from_timestamp(seconds, 0)
+        .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
+
+    Commit {
+        sha: commit.id().to_string(),
+        author: commit.author().name().unwrap_or_default().to_string(),
+        email: commit.author().email().unwrap_or_default().to_string(),
+        date: date_time.to_rfc3339(),
+        message: commit.message().unwrap_or_default().to_string(),
+        parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+        is_merge: commit.parent_count() > 1,
+        branches,
+        primary_branch: None,
+        is_head: None,
+        is_main: None,
+        branch_angle: None,
+        branch_info: None,
+        branch_divergence_point: None,
+        branch_base: None,
+        branch_divergence_age_days: None,
+    }
+}
+
+/// The operation a repository is in the middle of, as git names it.
+pub(crate) fn operation_name(state: RepositoryState) -> Option<&'static str> {
+    match state {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::Bisect => Some("bisect"),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some("rebase"),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("am"),
+    }
+}
+
 pub struct GitRepository {
     path: PathBuf,
     pub repo: Repository,
@@ -50,91 +92,56 @@ impl GitRepository {
         &self.path
     }
 
-    pub fn fetch_all(&self) -> Result<()> {
-        let remotes = self.repo.remotes()?;
-        for remote_name in remotes.iter().flatten() {
-            let mut remote = self.repo.find_remote(remote_name)?;
-            
-            // Set up callbacks for SSH authentication
-            let mut callbacks = RemoteCallbacks::new();
-            callbacks.credentials(|_url, username_from_url, _allowed_types| {
-                let username = username_from_url.unwrap_or("git");
-                
-                // Try to use SSH credentials from the system (SSH agent, keys, etc.)
-                Cred::ssh_key_from_agent(username)
-                    .or_else(|_| {
-                        // Fallback: try default SSH key locations
-                        let home = std::env::var("HOME")
-                            .or_else(|_| std::env::var("USERPROFILE"))
-                            .map_err(|_| git2::Error::from_str("Could not find home directory"))?;
-                        let ssh_key_path = std::path::Path::new(&home).join(".ssh").join("id_rsa");
-                        if ssh_key_path.exists() {
-                            Cred::ssh_key(username, None, &ssh_key_path, None)
-                        } else {
-                            Err(git2::Error::from_str("No SSH credentials available"))
-                        }
-                    })
-            });
-            
-            let mut fetch_options = git2::FetchOptions::new();
-            fetch_options.remote_callbacks(callbacks);
-            
-            // Try to fetch, but don't fail if authentication is not available
-            // This allows the app to work with local repos or repos that don't need auth
-            if let Err(e) = remote.fetch(&[] as &[&str], Some(&mut fetch_options), None) {
-                // If it's an authentication error, log it but don't fail completely
-                // The branch list can still be populated from local branches
-                if e.code() == git2::ErrorCode::Auth {
-                    eprintln!("Warning: Failed to fetch remote '{}' due to authentication. Using local branches only.", remote_name);
-                    continue;
-                }
-                return Err(e.into());
-            }
+    pub(crate) fn current_branch_for_remote_op(&self) -> Result<String> {
+        let branch = self.get_current_branch()?;
+        if branch == "HEAD" {
+            bail!("Not on a branch (detached HEAD)");
         }
-        Ok(())
-    }
-
-    /// Pull changes from the remote for the current branch
-    pub fn pull(&self) -> Result<String> {
-        self.run_git(&["pull"])
-    }
-
-    /// Push changes to the remote for the current branch
-    pub fn push(&self) -> Result<String> {
-        self.run_git(&["push"])
-    }
-
-    /// Push with upstream tracking for a new branch
-    pub fn push_set_upstream(&self, branch: &str) -> Result<String> {
-        self.run_git(&["push", "-u", "origin", branch])
+        Ok(branch)
     }
 
-    /// Stash current changes
-    pub fn stash_push(&self, message: Option<&str>) -> Result<String> {
-        match message {
-            Some(msg) => self.run_git(&["stash", "push", "-m", msg]),
-            None => self.run_git(&["stash", "push"]),
+    /// Stash current changes. With `paths`, only matching files are stashed.
+    pub fn stash_push(&self, opts: &StashPushOptions) -> Result<String> {
+        let mut args = vec!["stash", "push"];
+        if let Some(msg) = opts.message.as_deref() {
+            args.extend(["-m", msg]);
         }
+        if opts.keep_index {
+            args.push("--keep-index");
+        }
+        if opts.include_untracked {
+            args.push("--include-untracked");
+        }
+        if !opts.paths.is_empty() {
+            args.push("--");
+            args.extend(opts.paths.iter().map(|p| p.as_str()));
+        }
+        self.journaled("Stash changes", || self.run_git(&args))
     }
 
     /// Pop the most recent stash
     pub fn stash_pop(&self) -> Result<String> {
-        self.run_git(&["stash", "pop"])
+        self.journaled("Pop stash", || self.run_git(&["stash", "pop"]))
     }
 
     /// List all stashes
     pub fn stash_list(&self) -> Result<Vec<StashEntry>> {
-        let output = self.run_git(&["stash", "list", "--format=%gd%x1f%s%x1f%ai"])?;
+        // %gs is the reflog subject, which is what `git stash list` shows and
+        // what stash_rename rewrites. %P lists base, index (and untracked) commits.
+        let output = self.run_git(&["stash", "list", "--format=%gd%x1f%gs%x1f%ai%x1f%H%x1f%P"])?;
         let entries = output
             .lines()
             .filter(|line| !line.is_empty())
             .filter_map(|line| {
                 let parts: Vec<&str> = line.split('\x1f').collect();
-                if parts.len() >= 3 {
+                if parts.len() >= 5 {
                     Some(StashEntry {
                         index: parts[0].to_string(),
                         message: parts[1].to_string(),
                         date: parts[2].to_string(),
+                        sha: parts[3].to_string(),
+                        base_sha: parts[4].split_whitespace().next().unwrap_or_default().to_string(),
+                        branch: stash_branch_from_message(parts[1]),
                     })
                 } else {
                     None
@@ -144,24 +151,147 @@ impl GitRepository {
         Ok(entries)
     }
 
+    /// Show what a stash contains: tracked changes relative to its base commit,
+    /// plus files from the untracked-files commit when the stash has one.
+    pub fn stash_show(&self, stash_ref: &str) -> Result<Vec<StashFileDiff>> {
+        let stash = self.repo.revparse_single(stash_ref)?.peel_to_commit()?;
+        let base_tree = stash.parent(0)?.tree()?;
+        let stash_tree = stash.tree()?;
+
+        let mut trees = vec![(Some(base_tree), stash_tree, false)];
+        if stash.parent_count() > 2 {
+            trees.push((None, stash.parent(2)?.tree()?, true));
+        }
+
+        let mut files = Vec::new();
+        for (old_tree, new_tree, untracked) in &trees {
+            let diff = self
+                .repo
+                .diff_tree_to_tree(old_tree.as_ref(), Some(new_tree), None)?;
+
+            for delta in diff.deltas() {
+                let path = delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if path.is_empty() {
+                    continue;
+                }
+
+                let status = match delta.status() {
+                    git2::Delta::Added | git2::Delta::Untracked => "added",
+                    git2::Delta::Deleted => "removed",
+                    _ => "modified",
+                };
+
+                let file_diff = self.generate_file_diff(old_tree.as_ref(), new_tree, &path)?;
+                files.push(StashFileDiff {
+                    path,
+                    status: status.to_string(),
+                    untracked: *untracked,
+                    diff: file_diff.diff,
+                    hunks: file_diff.hunks,
+                });
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Create and check out a new branch at the stash's base commit, apply the
+    /// stash there and drop it on success (`git stash branch`).
+    pub fn stash_branch(&self, branch_name: &str, stash_ref: &str) -> Result<String> {
+        // Both go to git as positional arguments, so neither may pass as an option.
+        if branch_name.starts_with('-') || !git2::Branch::name_is_valid(branch_name)? {
+            bail!("'{}' is not a valid branch name", branch_name);
+        }
+        if stash_ref.starts_with('-') {
+            bail!("Invalid stash reference '{}'", stash_ref);
+        }
+        self.journaled(&format!("Create branch {} from {}", branch_name, stash_ref), || {
+            self.run_git(&["stash", "branch", branch_name, stash_ref])
+        })
+    }
+
+    /// Change a stash's message in place. Git has no command for this, so the
+    /// stash reflog is rewritten with the same commits, committers and order.
+    pub fn stash_rename(&self, stash_ref: &str, message: &str) -> Result<()> {
+        self.journaled(&format!("Rename {}", stash_ref), || {
+            self.rewrite_stash_message(stash_ref, message)
+        })
+    }
+
+    fn rewrite_stash_message(&self, stash_ref: &str, message: &str) -> Result<()> {
+        let index = stash_ref
+            .strip_prefix("stash@{")
+            .and_then(|s| s.strip_suffix('}'))
+            .and_then(|s| s.parse::<usize>().ok())
+            .with_context(|| format!("Invalid stash reference '{}'", stash_ref))?;
+
+        let mut reflog = self.repo.reflog("refs/stash")?;
+        if index >= reflog.len() {
+            bail!("No stash entry {}", stash_ref);
+        }
+
+        // Index 0 is the newest entry.
+        let entries: Vec<(Oid, git2::Signature<'static>, String)> = reflog
+            .iter()
+            .map(|e| {
+                (
+                    e.id_new(),
+                    e.committer().to_owned(),
+                    e.message().unwrap_or_default().to_string(),
+                )
+            })
+            .collect();
+
+        let new_message = match stash_branch_from_message(&entries[index].2) {
+            Some(branch) => format!("On {}: {}", branch, message),
+            None => message.to_string(),
+        };
+
+        for _ in 0..entries.len() {
+            reflog.remove(0, false)?;
+        }
+        for (i, (oid, committer, msg)) in entries.iter().enumerate().rev() {
+            let msg = if i == index { &new_message } else { msg };
+            reflog.append(*oid, committer, Some(msg))?;
+        }
+        reflog.write()?;
+        Ok(())
+    }
+
     /// Apply a specific stash by index (e.g., "stash@{0}")
     pub fn stash_apply(&self, stash_ref: &str) -> Result<String> {
-        self.run_git(&["stash", "apply", stash_ref])
+        self.journaled(&format!("Apply {}", stash_ref), || {
+            self.run_git(&["stash", "apply", stash_ref])
+        })
     }
 
     /// Checkout a specific commit (detached HEAD mode)
     pub fn checkout_commit(&self, commit_sha: &str) -> Result<String> {
-        self.run_git(&["checkout", commit_sha])
+        self.journaled(&format!("Check out {}", commit_sha), || {
+            self.run_git(&["checkout", commit_sha])
+        })
     }
 
     /// Checkout a branch
     pub fn checkout_branch(&self, branch_name: &str) -> Result<String> {
-        self.run_git(&["checkout", branch_name])
+        if let Some(path) = self.branches_in_other_worktrees()?.get(branch_name) {
+            bail!("'{}' is already checked out in the worktree at {}", branch_name, path);
+        }
+        self.journaled(&format!("Check out {}", branch_name), || {
+            self.run_git(&["checkout", branch_name])
+        })
     }
 
     /// Drop a specific stash by index
     pub fn stash_drop(&self, stash_ref: &str) -> Result<String> {
-        self.run_git(&["stash", "drop", stash_ref])
+        self.journaled(&format!("Drop {}", stash_ref), || {
+            self.run_git(&["stash", "drop", stash_ref])
+        })
     }
 
     /// Get the current branch name
@@ -305,6 +435,25 @@ impl GitRepository {
         Ok(output.stdout)
     }
 
+    /// A user-supplied shell command (`sh -c` or `cmd /C`) to run in the
+    /// working tree.
+    pub(crate) fn shell_command(&self, command: &str) -> Command {
+        #[cfg(target_os = "windows")]
+        let mut cmd = {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", command]).creation_flags(0x08000000); // CREATE_NO_WINDOW
+            cmd
+        };
+        #[cfg(not(target_os = "windows"))]
+        let mut cmd = {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", command]);
+            cmd
+        };
+        cmd.current_dir(&self.path);
+        cmd
+    }
+
     pub fn get_branch_info(&self) -> Result<BranchInfo> {
         // Single-pass collection of all branches (local + remote)
         let mut branches: Vec<String> = Vec::new();
@@ -369,6 +518,7 @@ impl GitRepository {
         // Calculate metadata for each branch in a single pass
         // Also collect OIDs for refs_hash to avoid double revparse_single calls
         let now = chrono::Utc::now();
+        let mut in_other_worktrees = self.branches_in_other_worktrees().unwrap_or_default();
         let mut branch_metadata: HashMap<String, BranchMetadata> = HashMap::with_capacity(branches.len());
         let mut branch_oids: Vec<(&str, Option<Oid>)> = Vec::with_capacity(branches.len());
 
@@ -417,6 +567,7 @@ impl GitRepository {
                     is_stale,
                     is_unborn,
                     last_commit_date,
+                    worktree_path: in_other_worktrees.remove(branch_name),
                 },
             );
         }
@@ -525,31 +676,7 @@ impl GitRepository {
                 .cloned()
                 .unwrap_or_default();
 
-            // Convert git2::Time to an RFC3339 string for the frontend.
-            let time = commit.time();
-            let seconds = time.seconds();
-            let date_time = DateTime::from_timestamp(seconds, 0)
-                .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
-            let date_str = date_time.to_rfc3339();
-
-            commits.push(Commit {
-                sha: commit.id().to_string(),
-                author: commit.author().name().unwrap_or_default().to_string(),
-                email: commit.author().email().unwrap_or_default().to_string(),
-                date: date_str,
-                message: commit.message().unwrap_or_default().to_string(),
-                parents: commit.parent_ids().map(|id| id.to_string()).collect(),
-                is_merge: commit.parent_count() > 1,
-                branches,
-                primary_branch: None,
-                is_head: None,
-                is_main: None,
-                branch_angle: None,
-                branch_info: None,
-                branch_divergence_point: None,
-                branch_base: None,
-                branch_divergence_age_days: None,
-            });
+            commits.push(commit_from_git2(&commit, branches));
         }
 
         Ok(commits)
@@ -580,32 +707,9 @@ impl GitRepository {
             let oid = oid?;
             let commit = self.repo.find_commit(oid)?;
 
-            let time = commit.time();
-            let seconds = time.seconds();
-            let date_time = DateTime::from_timestamp(seconds, 0)
-                .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
-            let date_str = date_time.to_rfc3339();
-
-            commits.push(Commit {
-                sha: commit.id().to_string(),
-                author: commit.author().name().unwrap_or_default().to_string(),
-                email: commit.author().email().unwrap_or_default().to_string(),
-                date: date_str,
-                message: commit.message().unwrap_or_default().to_string(),
-                parents: commit.parent_ids().map(|id| id.to_string()).collect(),
-                is_merge: commit.parent_count() > 1,
-                // In local mode, tag all returned commits with the branch spec
-                // we walked from. The frontend merges these per-branch lists.
-                branches: vec![spec.to_string()],
-                primary_branch: None,
-                is_head: None,
-                is_main: None,
-                branch_angle: None,
-                branch_info: None,
-                branch_divergence_point: None,
-                branch_base: None,
-                branch_divergence_age_days: None,
-            });
+            // In local mode, tag all returned commits with the branch spec
+            // we walked from. The frontend merges these per-branch lists.
+            commits.push(commit_from_git2(&commit, vec![spec.to_string()]));
         }
 
         Ok(commits)
@@ -629,7 +733,8 @@ impl GitRepository {
     /// Count the total number of commits in a repository.
     /// This is used as a heuristic to disable expensive operations on very large repos.
     pub fn count_all_commits(&self) -> Result<usize> {
-        let output = self.run_git(&["rev-list", "--all", "--count"])?;
+        // Undo journal snapshots aren't history.
+        let output = self.run_git(&["rev-list", "--exclude=refs/gitpow/*", "--all", "--count"])?;
         let count = output.trim().parse::<usize>().unwrap_or(0);
         Ok(count)
     }
@@ -784,6 +889,18 @@ impl GitRepository {
             .as_ref()
             .and_then(|t| t.get_path(std::path::Path::new(file_path)).ok());
 
+        // Submodules are recorded as commit OIDs, not blobs
+        let gitlink = |entry: &Option<git2::TreeEntry>| {
+            entry
+                .as_ref()
+                .filter(|e| e.kind() == Some(git2::ObjectType::Commit))
+                .map(|e| e.id())
+        };
+        let (old_link, new_link) = (gitlink(&file_in_parent), gitlink(&file_in_current));
+        if old_link.is_some() || new_link.is_some() {
+            return self.submodule_pointer_diff(file_path, old_link, new_link);
+        }
+
         // Handle different scenarios
         match (file_in_parent, file_in_current) {
             (None, Some(entry)) => {
@@ -813,6 +930,7 @@ impl GitRepository {
                     diff,
                     hunks: vec![hunk],
                     file_path: file_path.to_string(),
+                    submodule: None,
                 })
             }
             (Some(entry), None) => {
@@ -842,6 +960,7 @@ impl GitRepository {
                     diff,
                     hunks: vec![hunk],
                     file_path: file_path.to_string(),
+                    submodule: None,
                 })
             }
             (Some(_), Some(_)) => {
@@ -854,13 +973,14 @@ impl GitRepository {
                     diff: String::new(),
                     hunks: vec![],
                     file_path: file_path.to_string(),
+                    submodule: None,
                 })
             }
         }
     }
 
     /// Generate diff between two trees for a specific file using libgit2
-    fn generate_file_diff(
+    pub(crate) fn generate_file_diff(
         &self,
         old_tree: Option<&git2::Tree>,
         new_tree: &git2::Tree,
@@ -970,11 +1090,16 @@ impl GitRepository {
             diff: diff_text,
             hunks,
             file_path: file_path.to_string(),
+            submodule: None,
         })
     }
 
     /// Get diff for working directory changes (staged or unstaged)
     pub fn get_working_diff(&self, file_path: &str, staged: bool) -> Result<FileDiff> {
+        if self.is_submodule_path(file_path) {
+            return self.submodule_working_diff(file_path, staged);
+        }
+
         let mut diff_opts = git2::DiffOptions::new();
         diff_opts.pathspec(file_path);
         diff_opts.context_lines(3);
@@ -1070,16 +1195,52 @@ impl GitRepository {
             diff: diff_text,
             hunks,
             file_path: file_path.to_string(),
+            submodule: None,
         })
     }
 }
 
+/// Options for `git stash push`
+#[derive(Debug, Default)]
+pub struct StashPushOptions {
+    pub message: Option<String>,
+    pub keep_index: bool,
+    pub include_untracked: bool,
+    pub paths: Vec<String>,
+}
+
+/// One file changed by a stash, with its diff against the stash's base
+#[derive(Debug)]
+pub struct StashFileDiff {
+    pub path: String,
+    pub status: String,
+    pub untracked: bool,
+    pub diff: String,
+    pub hunks: Vec<DiffHunkData>,
+}
+
+/// Extract the branch name from a stash message
+/// ("WIP on main: abc123 subject" or "On main: message").
+fn stash_branch_from_message(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    let branch = rest.split(':').next()?.trim();
+    if branch.is_empty() || branch == "(no branch)" {
+        None
+    } else {
+        Some(branch.to_string())
+    }
+}
+
 /// Diff result for a single file
 #[derive(Debug)]
 pub struct FileDiff {
     pub diff: String,
     pub hunks: Vec<DiffHunkData>,
     pub file_path: String,
+    /// Set when the path is a submodule; `diff` then describes the pointer change.
+    pub submodule: Option<SubmoduleDiff>,
 }
 
 /// Hunk data from libgit2 diff
@@ -0,0 +1,196 @@
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Sort};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::repository::{commit_from_git2, operation_name, GitRepository};
+use super::status::StatusOptions;
+use crate::models::ResetPreview;
+
+/// Unreachable commits listed in a preview; `unreachable_count` has them all.
+const MAX_UNREACHABLE_COMMITS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    Soft,
+    Mixed,
+    Hard,
+    Keep,
+}
+
+impl ResetMode {
+    pub fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "soft" => Ok(Self::Soft),
+            "mixed" => Ok(Self::Mixed),
+            "hard" => Ok(Self::Hard),
+            "keep" => Ok(Self::Keep),
+            other => bail!(
+                "Unknown reset mode '{}' (expected soft, mixed, hard or keep)",
+                other
+            ),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Soft => "soft",
+            Self::Mixed => "mixed",
+            Self::Hard => "hard",
+            Self::Keep => "keep",
+        }
+    }
+}
+
+impl GitRepository {
+    /// Which commits a reset of the current branch (or detached HEAD) to
+    /// `target` leaves unreachable and which local changes it discards.
+    pub fn reset_preview(&self, target: &str, mode: ResetMode) -> Result<ResetPreview> {
+        let head = self
+            .repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .context("HEAD has no commit to reset from")?;
+        let target = self
+            .repo
+            .revparse_single(target)
+            .and_then(|o| o.peel_to_commit())
+            .with_context(|| format!("'{}' is not a commit", target))?;
+        let head_ref = self.repo.head()?.name().map(|n| n.to_string());
+        let branch = head_ref
+            .as_deref()
+            .and_then(|n| n.strip_prefix("refs/heads/"))
+            .map(|b| b.to_string());
+
+        // Everything HEAD reaches that neither the target nor any other ref does.
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(head.id())?;
+        revwalk.hide(target.id())?;
+        for glob in ["refs/heads/*", "refs/tags/*", "refs/remotes/*"] {
+            for reference in self.repo.references_glob(glob)? {
+                let reference = reference?;
+                if reference.name() == head_ref.as_deref() {
+                    continue;
+                }
+                if let Ok(commit) = reference.peel_to_commit() {
+                    revwalk.hide(commit.id())?;
+                }
+            }
+        }
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        let mut unreachable_commits = Vec::new();
+        let mut unreachable_count = 0;
+        for oid in revwalk {
+            let oid = oid?;
+            unreachable_count += 1;
+            if unreachable_commits.len() < MAX_UNREACHABLE_COMMITS {
+                unreachable_commits
+                    .push(commit_from_git2(&self.repo.find_commit(oid)?, Vec::new()));
+            }
+        }
+
+        let mut lost_changes = Vec::new();
+        let mut blocked_by = Vec::new();
+        if matches!(mode, ResetMode::Hard | ResetMode::Keep) {
+            let updated = self.paths_between(head.id(), target.id())?;
+            let target_tree = target.tree()?;
+            let opts = StatusOptions {
+                ignored: false,
+                untracked: Some("all".to_string()),
+            };
+            for file in self.status(&opts)?.files {
+                let touched = updated.contains(&file.path)
+                    || file.old_path.as_ref().is_some_and(|p| updated.contains(p));
+                let untracked = file.r#type == "untracked";
+                // Untracked files are only overwritten where the target has a file.
+                let overwritten =
+                    untracked && touched && target_tree.get_path(Path::new(&file.path)).is_ok();
+                match mode {
+                    ResetMode::Hard if !untracked || overwritten => lost_changes.push(file),
+                    ResetMode::Keep if touched => blocked_by.push(file),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(ResetPreview {
+            mode: mode.as_str().to_string(),
+            branch,
+            old_head: head.id().to_string(),
+            target: target.id().to_string(),
+            unreachable_commits,
+            unreachable_count,
+            lost_changes,
+            blocked_by,
+        })
+    }
+
+    /// Move the current branch (or detached HEAD) to `target`. A hard reset
+    /// only runs once the state it discards is saved in the undo journal.
+    pub fn reset(&self, target: &str, mode: ResetMode) -> Result<String> {
+        if let Some(op) = operation_name(self.repo.state()) {
+            bail!("Finish or abort the {} before resetting", op);
+        }
+        let sha = self
+            .repo
+            .revparse_single(target)
+            .and_then(|o| o.peel_to_commit())
+            .with_context(|| format!("'{}' is not a commit", target))?
+            .id()
+            .to_string();
+        let label = format!("Reset ({}) to {}", mode.as_str(), &sha[..7]);
+        let flag = format!("--{}", mode.as_str());
+
+        if mode == ResetMode::Hard {
+            self.record_journal(&label).context(
+                "Refusing a hard reset: the changes it discards could not be saved for undo",
+            )?;
+            return self.run_git(&["reset", &flag, &sha]);
+        }
+        self.journaled(&label, || self.run_git(&["reset", &flag, &sha]))
+    }
+
+    /// Restore one file in the index and working tree to its version in
+    /// `commit`; a file the commit doesn't have is removed.
+    pub fn restore_file(&self, commit: &str, path: &str) -> Result<String> {
+        let commit = self
+            .repo
+            .revparse_single(commit)
+            .and_then(|o| o.peel_to_commit())
+            .with_context(|| format!("'{}' is not a commit", commit))?;
+        let sha = commit.id().to_string();
+        let in_commit = commit.tree()?.get_path(Path::new(path)).is_ok();
+        let tracked = self.repo.index()?.get_path(Path::new(path), 0).is_some();
+        if !in_commit && !tracked {
+            bail!("'{}' is neither in {} nor tracked", path, &sha[..7]);
+        }
+        self.journaled(&format!("Restore {} from {}", path, &sha[..7]), || {
+            self.run_git(&[
+                "restore",
+                &format!("--source={}", sha),
+                "--staged",
+                "--worktree",
+                "--",
+                path,
+            ])
+        })
+    }
+
+    /// Paths whose content differs between two commits, both sides of renames.
+    fn paths_between(&self, from: Oid, to: Oid) -> Result<HashSet<String>> {
+        let old_tree = self.repo.find_commit(from)?.tree()?;
+        let new_tree = self.repo.find_commit(to)?.tree()?;
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+        let mut paths = HashSet::new();
+        for delta in diff.deltas() {
+            for file in [delta.old_file(), delta.new_file()] {
+                if let Some(path) = file.path() {
+                    paths.insert(path.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+        Ok(paths)
+    }
+}
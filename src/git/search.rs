@@ -0,0 +1,490 @@
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::repository::GitRepository;
+use crate::models::{SearchFileResult, SearchLine, SearchMatchSpan};
+
+/// Cancellation flag of the most recent search per repository.
+/// Starting a new search for a repo flips the previous flag so the older
+/// `git grep` is killed instead of competing for the blocking pool.
+static ACTIVE_SEARCHES: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// How often a running search checks whether it has been superseded.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancellation flag of a registered search. Dropping it unregisters the
+/// search, unless a newer one for the same repository already replaced it.
+pub struct SearchToken {
+    repo_key: String,
+    cancel: Arc<AtomicBool>,
+}
+
+impl std::ops::Deref for SearchToken {
+    type Target = AtomicBool;
+
+    fn deref(&self) -> &AtomicBool {
+        &self.cancel
+    }
+}
+
+impl Drop for SearchToken {
+    fn drop(&mut self) {
+        let mut active = ACTIVE_SEARCHES.lock().unwrap_or_else(|e| e.into_inner());
+        if active
+            .get(&self.repo_key)
+            .is_some_and(|current| Arc::ptr_eq(current, &self.cancel))
+        {
+            active.remove(&self.repo_key);
+        }
+    }
+}
+
+/// Register a new search for `repo_key`, cancelling any search still running
+/// for the same repository. Returns the new search's cancellation flag.
+pub fn supersede_search(repo_key: &str) -> SearchToken {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut active = ACTIVE_SEARCHES.lock().unwrap();
+    if let Some(previous) = active.insert(repo_key.to_string(), cancel.clone()) {
+        previous.store(true, Ordering::SeqCst);
+    }
+    SearchToken {
+        repo_key: repo_key.to_string(),
+        cancel,
+    }
+}
+
+/// A search the caller got wrong: an empty query, a ref that doesn't resolve
+/// to a commit, or a pattern git can't compile.
+#[derive(Debug)]
+pub struct InvalidSearch(pub String);
+
+impl fmt::Display for InvalidSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidSearch {}
+
+/// Options for a content search. `reference: None` searches the worktree.
+pub struct GrepOptions {
+    pub query: String,
+    pub reference: Option<String>,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub max_results: usize,
+    pub context_lines: usize,
+}
+
+pub struct GrepOutcome {
+    pub files: Vec<SearchFileResult>,
+    pub total_matches: usize,
+    pub truncated: bool,
+    pub cancelled: bool,
+}
+
+impl GrepOptions {
+    /// Build the regex used to highlight match spans within returned lines.
+    /// It mirrors the flags passed to `git grep`, but only highlights: which
+    /// lines match is decided by git, since the Rust and POSIX ERE dialects
+    /// differ (`\d`, lookarounds, backreferences).
+    pub fn matcher(&self) -> Result<Regex> {
+        let mut pattern = if self.regex {
+            self.query.clone()
+        } else {
+            regex::escape(&self.query)
+        };
+        if self.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .with_context(|| format!("Invalid search pattern '{}'", self.query))
+    }
+}
+
+/// One line of `git grep --heading --break -n` output.
+enum GrepLine<'a> {
+    /// A file heading, the first line and the one after each blank line
+    Heading(&'a [u8]),
+    /// `<line>:<text>` for matches, `<line>-<text>` for context
+    Line {
+        number: usize,
+        is_match: bool,
+        text: &'a [u8],
+    },
+    /// "--" between context groups, and the blank line between files
+    Separator,
+}
+
+fn parse_grep_line(raw: &[u8], expect_heading: bool) -> GrepLine<'_> {
+    if raw.is_empty() || raw == b"--" {
+        return GrepLine::Separator;
+    }
+    if expect_heading {
+        return GrepLine::Heading(raw);
+    }
+    let digits = raw.iter().take_while(|b| b.is_ascii_digit()).count();
+    match raw.get(digits) {
+        Some(&sep) if digits > 0 && (sep == b':' || sep == b'-') => GrepLine::Line {
+            number: String::from_utf8_lossy(&raw[..digits]).parse().unwrap_or(0),
+            is_match: sep == b':',
+            text: &raw[digits + 1..],
+        },
+        _ => GrepLine::Separator,
+    }
+}
+
+/// Character-based match spans, so the frontend can slice JS strings directly.
+fn match_spans(matcher: &Regex, text: &str) -> Vec<SearchMatchSpan> {
+    matcher
+        .find_iter(text)
+        .filter(|m| !m.as_str().is_empty())
+        .map(|m| SearchMatchSpan {
+            start: text[..m.start()].chars().count(),
+            end: text[..m.end()].chars().count(),
+        })
+        .collect()
+}
+
+impl GitRepository {
+    /// Search file contents with `git grep`, grouping results by file.
+    /// Stops early once `max_results` matching lines were collected, or when
+    /// `cancel` is set by a newer search for the same repository.
+    pub fn grep(&self, opts: &GrepOptions, cancel: &AtomicBool) -> Result<GrepOutcome> {
+        if opts.query.is_empty() {
+            bail!(InvalidSearch("search query is required".to_string()));
+        }
+        // Highlighting is best effort; git still decides what matches.
+        let matcher = opts.matcher().ok();
+
+        // Pass the resolved commit rather than the user's ref, so a ref can
+        // never be taken for an option.
+        let commit = opts
+            .reference
+            .as_deref()
+            .map(|r| -> Result<String> {
+                let commit = (!r.starts_with('-'))
+                    .then(|| {
+                        self.repo
+                            .revparse_single(r)
+                            .and_then(|o| o.peel_to_commit())
+                    })
+                    .and_then(|c| c.ok())
+                    .ok_or_else(|| InvalidSearch(format!("Invalid ref '{}'", r)))?;
+                Ok(commit.id().to_string())
+            })
+            .transpose()?;
+
+        let context = opts.context_lines.to_string();
+        let mut args: Vec<&str> = vec![
+            "-c",
+            "core.quotePath=false",
+            "grep",
+            "-n",
+            "-I",
+            "--heading",
+            "--break",
+            "--no-color",
+        ];
+        args.push(if opts.regex { "-E" } else { "-F" });
+        if !opts.case_sensitive {
+            args.push("-i");
+        }
+        if opts.whole_word {
+            args.push("-w");
+        }
+        if opts.context_lines > 0 {
+            args.extend(["-C", &context]);
+        }
+        args.extend(["-e", &opts.query]);
+        if let Some(commit) = &commit {
+            args.push(commit);
+        }
+        args.push("--");
+        let excludes: Vec<String> = opts
+            .exclude
+            .iter()
+            .map(|p| format!(":(exclude){}", p))
+            .collect();
+        args.extend(opts.include.iter().map(|s| s.as_str()));
+        args.extend(excludes.iter().map(|s| s.as_str()));
+
+        let mut cmd = Command::new("git");
+        cmd.args(&args)
+            .current_dir(self.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to run git with args {:?}", args))?;
+
+        // Drain stderr concurrently so a chatty failure can't fill the pipe
+        // and block git before it exits.
+        let mut stderr = child
+            .stderr
+            .take()
+            .context("Failed to capture git grep errors")?;
+        let stderr_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf);
+            buf
+        });
+
+        // Read output on a helper thread so the cancel flag is still polled
+        // while git grep is busy scanning without producing output.
+        let stdout = child
+            .stdout
+            .take()
+            .context("Failed to capture git grep output")?;
+        let (tx, rx) = mpsc::channel::<Vec<u8>>();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                let mut line = Vec::new();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        // With a tree-ish, git grep prefixes every path with "<commit>:".
+        let ref_prefix = commit.as_ref().map(|c| format!("{}:", c));
+        let mut files: Vec<SearchFileResult> = Vec::new();
+        let mut total_matches = 0;
+        let mut truncated = false;
+        let mut cancelled = false;
+        let mut expect_heading = true;
+
+        loop {
+            if cancel.load(Ordering::SeqCst) {
+                cancelled = true;
+                break;
+            }
+            let raw = match rx.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(raw) => raw,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+            let raw = raw.strip_suffix(b"\n").unwrap_or(&raw);
+            let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+
+            let (line_number, is_match, text) = match parse_grep_line(raw, expect_heading) {
+                GrepLine::Heading(path) => {
+                    let mut path = String::from_utf8_lossy(path).to_string();
+                    if let Some(prefix) = &ref_prefix {
+                        if let Some(stripped) = path.strip_prefix(prefix.as_str()) {
+                            path = stripped.to_string();
+                        }
+                    }
+                    files.push(SearchFileResult {
+                        path,
+                        match_count: 0,
+                        lines: Vec::new(),
+                    });
+                    expect_heading = false;
+                    continue;
+                }
+                GrepLine::Separator => {
+                    // --break puts a blank line before each new file.
+                    expect_heading = raw.is_empty();
+                    continue;
+                }
+                GrepLine::Line {
+                    number,
+                    is_match,
+                    text,
+                } => (number, is_match, String::from_utf8_lossy(text).to_string()),
+            };
+
+            if is_match {
+                if total_matches >= opts.max_results {
+                    truncated = true;
+                    break;
+                }
+                total_matches += 1;
+            }
+            let Some(file) = files.last_mut() else {
+                continue;
+            };
+            let spans = match (&matcher, is_match) {
+                (Some(matcher), true) => match_spans(matcher, &text),
+                _ => Vec::new(),
+            };
+            if is_match {
+                file.match_count += 1;
+            }
+            file.lines.push(SearchLine {
+                line_number,
+                text,
+                is_match,
+                spans,
+            });
+        }
+
+        if cancelled || truncated {
+            let _ = child.kill();
+            let _ = child.wait();
+        } else {
+            let status = child.wait()?;
+            let stderr = stderr_reader.join().unwrap_or_default();
+            // Exit status 1 means "no matches"; anything else is a real failure.
+            if !status.success() && status.code() != Some(1) {
+                let message = String::from_utf8_lossy(&stderr).trim().to_string();
+                // git reports patterns it can't compile as "fatal: -e option, '<pattern>': ..."
+                if message.starts_with("fatal: -e option, ") {
+                    bail!(InvalidSearch(message));
+                }
+                bail!(message);
+            }
+        }
+
+        // When truncated, lines read after the last kept match were leading
+        // context of the dropped match, possibly in a file with no kept matches.
+        if truncated {
+            if let Some(file) = files.last_mut() {
+                while file.lines.last().map(|l| !l.is_match).unwrap_or(false) {
+                    file.lines.pop();
+                }
+            }
+            files.retain(|f| f.match_count > 0);
+        }
+
+        Ok(GrepOutcome {
+            files,
+            total_matches,
+            truncated,
+            cancelled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(raw: &str) -> (usize, bool, &str) {
+        match parse_grep_line(raw.as_bytes(), false) {
+            GrepLine::Line {
+                number,
+                is_match,
+                text,
+            } => (number, is_match, std::str::from_utf8(text).unwrap()),
+            _ => panic!("'{}' was not parsed as a line", raw),
+        }
+    }
+
+    #[test]
+    fn match_and_context_lines() {
+        assert_eq!(line("12:let x = 1;"), (12, true, "let x = 1;"));
+        assert_eq!(line("13-}"), (13, false, "}"));
+        // Only the first separator counts; the rest is text.
+        assert_eq!(line("7:a:b-c"), (7, true, "a:b-c"));
+        assert_eq!(line("8-10:30"), (8, false, "10:30"));
+        assert_eq!(line("9:"), (9, true, ""));
+    }
+
+    #[test]
+    fn headings_and_separators() {
+        assert!(matches!(
+            parse_grep_line(b"src/12:odd-name.rs", true),
+            GrepLine::Heading(b"src/12:odd-name.rs")
+        ));
+        assert!(matches!(parse_grep_line(b"", true), GrepLine::Separator));
+        assert!(matches!(parse_grep_line(b"--", false), GrepLine::Separator));
+        // No line number: not something this parser understands.
+        assert!(matches!(
+            parse_grep_line(b":text", false),
+            GrepLine::Separator
+        ));
+        assert!(matches!(parse_grep_line(b"12", false), GrepLine::Separator));
+    }
+
+    fn options(query: &str, regex: bool, case_sensitive: bool, whole_word: bool) -> GrepOptions {
+        GrepOptions {
+            query: query.to_string(),
+            reference: None,
+            regex,
+            case_sensitive,
+            whole_word,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_results: 10,
+            context_lines: 0,
+        }
+    }
+
+    fn spans(opts: &GrepOptions, text: &str) -> Vec<(usize, usize)> {
+        match_spans(&opts.matcher().unwrap(), text)
+            .iter()
+            .map(|s| (s.start, s.end))
+            .collect()
+    }
+
+    #[test]
+    fn spans_count_characters() {
+        let opts = options("fn", false, true, false);
+        assert_eq!(spans(&opts, "éé fn fn"), vec![(3, 5), (6, 8)]);
+    }
+
+    #[test]
+    fn spans_follow_search_flags() {
+        assert_eq!(
+            spans(&options("a.b", false, true, false), "axb a.b"),
+            vec![(4, 7)]
+        );
+        assert_eq!(
+            spans(&options("a.b", true, true, false), "axb"),
+            vec![(0, 3)]
+        );
+        assert_eq!(
+            spans(&options("foo", false, false, false), "FOO"),
+            vec![(0, 3)]
+        );
+        assert!(spans(&options("foo", false, true, false), "FOO").is_empty());
+        assert_eq!(
+            spans(&options("id", false, true, true), "id valid id_x id"),
+            vec![(0, 2), (14, 16)]
+        );
+    }
+
+    #[test]
+    fn finished_searches_are_unregistered() {
+        let key = "test-repo-finished-searches";
+        let first = supersede_search(key);
+        let second = supersede_search(key);
+        assert!(first.load(Ordering::SeqCst));
+        // The superseded search finishing must not unregister the newer one.
+        drop(first);
+        assert!(ACTIVE_SEARCHES.lock().unwrap().contains_key(key));
+        drop(second);
+        assert!(!ACTIVE_SEARCHES.lock().unwrap().contains_key(key));
+    }
+}
@@ -0,0 +1,645 @@
+use anyhow::{bail, Result};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Months, NaiveDate, NaiveDateTime, TimeDelta, Timelike,
+};
+use git2::{ObjectType, Oid, Sort};
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::repository::GitRepository;
+use crate::models::{
+    ActivityBucket, ActivityResponse, ChangeCoupling, ChurnResponse, ContributorStats,
+    ContributorStatsResponse, PathChurn, WeeklyActivity,
+};
+
+/// Cache key for per-commit diff stats: (repo_path, commit).
+type FileStatsKey = (String, Oid);
+
+/// Per-file diff stats by commit. A SHA pins the diff, so entries never go
+/// stale and repeated analyses only diff commits they haven't seen. Weighed by
+/// path bytes, since one vendoring commit can touch thousands of files.
+static FILE_STATS_CACHE: Lazy<Cache<FileStatsKey, Arc<Vec<FileStat>>>> = Lazy::new(|| {
+    Cache::builder()
+        .weigher(|_, stats: &Arc<Vec<FileStat>>| {
+            let bytes: usize = stats.iter().map(|s| s.path.len() + 16).sum();
+            bytes.saturating_add(64).try_into().unwrap_or(u32::MAX)
+        })
+        .max_capacity(64 * 1024 * 1024)
+        .build()
+});
+
+/// Cache key for activity: (repo_path, tip commit, author filter, path filter).
+type ActivityKey = (String, Oid, Option<String>, Option<String>);
+
+/// Author times of every commit matching an activity query. Keyed by the
+/// resolved tip, so a new commit on the branch misses naturally; date range
+/// and bucket size are applied afterwards, so changing them is free.
+static ACTIVITY_CACHE: Lazy<Cache<ActivityKey, Arc<Vec<DateTime<FixedOffset>>>>> =
+    Lazy::new(|| {
+        Cache::builder()
+            .time_to_live(Duration::from_secs(600))
+            .max_capacity(50)
+            .build()
+    });
+
+/// Period size for activity histograms.
+#[derive(Clone, Copy)]
+pub enum TimeBucket {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl TimeBucket {
+    pub fn parse(value: &str) -> Result<Self> {
+        Ok(match value.to_ascii_lowercase().as_str() {
+            "day" => TimeBucket::Day,
+            "week" => TimeBucket::Week,
+            "month" => TimeBucket::Month,
+            "year" => TimeBucket::Year,
+            _ => bail!(
+                "Unknown bucket '{}': expected day, week, month or year",
+                value
+            ),
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TimeBucket::Day => "day",
+            TimeBucket::Week => "week",
+            TimeBucket::Month => "month",
+            TimeBucket::Year => "year",
+        }
+    }
+
+    /// First day of the period `date` falls in.
+    fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            TimeBucket::Day => date,
+            TimeBucket::Week => week_start(date),
+            TimeBucket::Month => date.with_day(1).unwrap(),
+            TimeBucket::Year => date.with_ordinal(1).unwrap(),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            TimeBucket::Day => start + TimeDelta::days(1),
+            TimeBucket::Week => start + TimeDelta::days(7),
+            TimeBucket::Month => start + Months::new(1),
+            TimeBucket::Year => start + Months::new(12),
+        }
+    }
+
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            TimeBucket::Day | TimeBucket::Week => start.format("%Y-%m-%d").to_string(),
+            TimeBucket::Month => start.format("%Y-%m").to_string(),
+            TimeBucket::Year => start.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Lines added and removed in one file by one commit.
+pub(crate) struct FileStat {
+    pub path: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// What one commit contributes to its author's statistics.
+struct CommitSample {
+    name: String,
+    email: String,
+    /// Author time, in the author's time zone
+    time: DateTime<FixedOffset>,
+    files: Arc<Vec<FileStat>>,
+}
+
+/// Options for a churn analysis.
+pub struct ChurnOptions {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Files, directories and coupled pairs reported at most
+    pub limit: usize,
+    /// Commits touching more files than this (mass renames, reformatting)
+    /// are left out of change coupling.
+    pub max_coupling_files: usize,
+    /// Pairs must share at least this many commits to be reported.
+    pub min_shared_commits: usize,
+}
+
+#[derive(Default)]
+struct ChurnTotals {
+    commits: usize,
+    lines_added: usize,
+    lines_removed: usize,
+    lines: usize,
+}
+
+#[derive(Default)]
+struct AuthorTotals {
+    name: String,
+    email: String,
+    commits: usize,
+    lines_added: usize,
+    lines_removed: usize,
+    files: HashSet<String>,
+    first: Option<DateTime<FixedOffset>>,
+    last: Option<DateTime<FixedOffset>>,
+    days: HashSet<NaiveDate>,
+    weeks: BTreeMap<NaiveDate, WeeklyActivity>,
+}
+
+/// Parse a `since`/`until` bound given as YYYY-MM-DD or RFC3339 into a Unix
+/// timestamp. Bare dates are taken in UTC; an `until` date includes the whole day.
+pub fn parse_date_bound(value: &str, end_of_day: bool) -> Result<i64> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date_time.timestamp());
+    }
+    let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") else {
+        bail!("Invalid date '{}': expected YYYY-MM-DD or RFC3339", value);
+    };
+    let time: NaiveDateTime = if end_of_day {
+        date.and_hms_opt(23, 59, 59).unwrap()
+    } else {
+        date.and_hms_opt(0, 0, 0).unwrap()
+    };
+    Ok(time.and_utc().timestamp())
+}
+
+/// Author time of a commit in the author's own time zone.
+pub(crate) fn author_time(signature: &git2::Signature) -> DateTime<FixedOffset> {
+    let when = signature.when();
+    let offset = FixedOffset::east_opt(when.offset_minutes() * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    DateTime::from_timestamp(when.seconds(), 0)
+        .unwrap_or_default()
+        .with_timezone(&offset)
+}
+
+/// Rank churned paths by hotspot score: normalized churn (commit count and
+/// lines changed, equally weighted) times normalized current size.
+fn rank_hotspots(totals: HashMap<String, ChurnTotals>, limit: usize) -> Vec<PathChurn> {
+    let max_commits = totals.values().map(|t| t.commits).max().unwrap_or(0).max(1) as f64;
+    let max_changed = totals
+        .values()
+        .map(|t| t.lines_added + t.lines_removed)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let max_lines = totals.values().map(|t| t.lines).max().unwrap_or(0).max(1) as f64;
+
+    let mut ranked: Vec<PathChurn> = totals
+        .into_iter()
+        .map(|(path, t)| {
+            let churn = (t.commits as f64 / max_commits
+                + (t.lines_added + t.lines_removed) as f64 / max_changed)
+                / 2.0;
+            PathChurn {
+                path,
+                commits: t.commits,
+                lines_added: t.lines_added,
+                lines_removed: t.lines_removed,
+                lines: t.lines,
+                hotspot_score: churn * t.lines as f64 / max_lines,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.hotspot_score
+            .total_cmp(&a.hotspot_score)
+            .then_with(|| b.commits.cmp(&a.commits))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Directories containing `path`, innermost last, with "" for the root.
+fn parent_dirs(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::once("").chain(path.match_indices('/').map(move |(i, _)| &path[..i]))
+}
+
+/// The Monday starting the week `date` falls in.
+pub(crate) fn week_start(date: NaiveDate) -> NaiveDate {
+    date - TimeDelta::days(date.weekday().num_days_from_monday() as i64)
+}
+
+impl GitRepository {
+    /// Commits reachable from `reference` whose author time lies within
+    /// `since..=until` (Unix seconds), newest first.
+    pub(crate) fn walk_range(
+        &self,
+        reference: &str,
+        since: Option<i64>,
+        until: Option<i64>,
+    ) -> Result<Vec<Oid>> {
+        let target = self.repo.revparse_single(reference)?.peel_to_commit()?;
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(target.id())?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        let mut oids = Vec::new();
+        for oid in revwalk {
+            let oid = oid?;
+            let commit = self.repo.find_commit(oid)?;
+            let seconds = commit.author().when().seconds();
+            if since.is_some_and(|s| seconds < s) || until.is_some_and(|u| seconds > u) {
+                continue;
+            }
+            oids.push(oid);
+        }
+        Ok(oids)
+    }
+
+    /// Per-file line counts of a commit against its first parent. Merge
+    /// commits report no files, so their changes aren't counted twice.
+    pub(crate) fn commit_file_stats(&self, commit: &git2::Commit) -> Result<Vec<FileStat>> {
+        if commit.parent_count() > 1 {
+            return Ok(Vec::new());
+        }
+        let tree = commit.tree()?;
+        let parent_tree = if commit.parent_count() == 1 {
+            Some(commit.parent(0)?.tree()?)
+        } else {
+            None
+        };
+        let diff = self
+            .repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        let mut files = Vec::with_capacity(diff.deltas().len());
+        for (index, delta) in diff.deltas().enumerate() {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if path.is_empty() {
+                continue;
+            }
+            // Binary files have no patch; they still count as touched.
+            let (added, removed) = match git2::Patch::from_diff(&diff, index)? {
+                Some(patch) => {
+                    let (_, added, removed) = patch.line_stats()?;
+                    (added, removed)
+                }
+                None => (0, 0),
+            };
+            files.push(FileStat {
+                path,
+                added,
+                removed,
+            });
+        }
+        Ok(files)
+    }
+
+    /// `commit_file_stats`, served from the per-SHA cache when possible.
+    pub(crate) fn cached_file_stats(&self, commit: &git2::Commit) -> Result<Arc<Vec<FileStat>>> {
+        let key = (self.path().to_string_lossy().to_string(), commit.id());
+        if let Some(files) = FILE_STATS_CACHE.get(&key) {
+            return Ok(files);
+        }
+        let files = Arc::new(self.commit_file_stats(commit)?);
+        FILE_STATS_CACHE.insert(key, files.clone());
+        Ok(files)
+    }
+
+    /// Per-author statistics for the commits reachable from `reference` in
+    /// the given date range, with identities merged through `.mailmap`.
+    pub fn contributor_stats(
+        &self,
+        reference: &str,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<ContributorStatsResponse> {
+        let since_ts = since.map(|s| parse_date_bound(s, false)).transpose()?;
+        let until_ts = until.map(|u| parse_date_bound(u, true)).transpose()?;
+        let oids = self.walk_range(reference, since_ts, until_ts)?;
+
+        // Diffing dominates, so commits are sampled in parallel; each rayon
+        // thread opens its own repo connection (libgit2 pattern).
+        let path = self.path().to_path_buf();
+        let samples = oids
+            .par_iter()
+            .map_init(
+                || {
+                    let repo = GitRepository::open(&path)?;
+                    let mailmap = repo.repo.mailmap()?;
+                    Ok::<_, anyhow::Error>((repo, mailmap))
+                },
+                |state, oid| {
+                    let (repo, mailmap) = match state {
+                        Ok(state) => state,
+                        Err(e) => bail!("Failed to open repository: {}", e),
+                    };
+                    let commit = repo.repo.find_commit(*oid)?;
+                    let author = commit.author_with_mailmap(mailmap)?;
+                    Ok(CommitSample {
+                        name: author.name().unwrap_or_default().to_string(),
+                        email: author.email().unwrap_or_default().to_string(),
+                        time: author_time(&author),
+                        files: repo.cached_file_stats(&commit)?,
+                    })
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+
+        let total_commits = samples.len();
+        let mut authors: HashMap<String, AuthorTotals> = HashMap::new();
+        for sample in samples {
+            let key = sample.email.to_lowercase();
+            let totals = authors.entry(key).or_default();
+            if totals.name.is_empty() {
+                totals.name = sample.name;
+                totals.email = sample.email;
+            }
+            let added: usize = sample.files.iter().map(|f| f.added).sum();
+            let removed: usize = sample.files.iter().map(|f| f.removed).sum();
+            totals.commits += 1;
+            totals.lines_added += added;
+            totals.lines_removed += removed;
+            totals
+                .files
+                .extend(sample.files.iter().map(|f| f.path.clone()));
+            totals.first = Some(totals.first.map_or(sample.time, |t| t.min(sample.time)));
+            totals.last = Some(totals.last.map_or(sample.time, |t| t.max(sample.time)));
+
+            let day = sample.time.date_naive();
+            totals.days.insert(day);
+            let week = totals.weeks.entry(week_start(day)).or_default();
+            week.commits += 1;
+            week.lines_added += added;
+            week.lines_removed += removed;
+        }
+
+        let mut contributors: Vec<ContributorStats> = authors
+            .into_values()
+            .map(|totals| ContributorStats {
+                name: totals.name,
+                email: totals.email,
+                commits: totals.commits,
+                lines_added: totals.lines_added,
+                lines_removed: totals.lines_removed,
+                files_touched: totals.files.len(),
+                first_commit: totals.first.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                last_commit: totals.last.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                active_days: totals.days.len(),
+                weeks: totals
+                    .weeks
+                    .into_iter()
+                    .map(|(start, mut week)| {
+                        week.week = start.format("%Y-%m-%d").to_string();
+                        week
+                    })
+                    .collect(),
+            })
+            .collect();
+        contributors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(ContributorStatsResponse {
+            branch: reference.to_string(),
+            since: since.map(str::to_string),
+            until: until.map(str::to_string),
+            total_commits,
+            contributors,
+        })
+    }
+
+    /// Current size in lines of `path` in `tree`; None if it doesn't exist
+    /// there. Binary files count as zero lines.
+    fn lines_at(&self, tree: &git2::Tree, path: &str) -> Option<usize> {
+        let entry = tree.get_path(Path::new(path)).ok()?;
+        if entry.kind() != Some(ObjectType::Blob) {
+            return None;
+        }
+        let blob = self.repo.find_blob(entry.id()).ok()?;
+        if blob.is_binary() {
+            return Some(0);
+        }
+        let content = blob.content();
+        let newlines = content.iter().filter(|&&b| b == b'\n').count();
+        Some(newlines + usize::from(content.last().is_some_and(|&b| b != b'\n')))
+    }
+
+    /// Files and directories ranked by churn and size, plus files that tend
+    /// to change in the same commits, for the commits reachable from
+    /// `reference` in the given date range.
+    pub fn churn_stats(&self, reference: &str, opts: &ChurnOptions) -> Result<ChurnResponse> {
+        let since_ts = opts
+            .since
+            .as_deref()
+            .map(|s| parse_date_bound(s, false))
+            .transpose()?;
+        let until_ts = opts
+            .until
+            .as_deref()
+            .map(|u| parse_date_bound(u, true))
+            .transpose()?;
+        let oids = self.walk_range(reference, since_ts, until_ts)?;
+
+        let path = self.path().to_path_buf();
+        let commits = oids
+            .par_iter()
+            .map_init(
+                || GitRepository::open(&path),
+                |repo, oid| {
+                    let repo = match repo {
+                        Ok(repo) => repo,
+                        Err(e) => bail!("Failed to open repository: {}", e),
+                    };
+                    repo.cached_file_stats(&repo.repo.find_commit(*oid)?)
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut files: HashMap<String, ChurnTotals> = HashMap::new();
+        let mut pairs: HashMap<(String, String), usize> = HashMap::new();
+        for stats in &commits {
+            for file in stats.iter() {
+                let totals = files.entry(file.path.clone()).or_default();
+                totals.commits += 1;
+                totals.lines_added += file.added;
+                totals.lines_removed += file.removed;
+            }
+            if stats.len() < 2 || stats.len() > opts.max_coupling_files {
+                continue;
+            }
+            let mut paths: Vec<&str> = stats.iter().map(|f| f.path.as_str()).collect();
+            paths.sort_unstable();
+            for (i, a) in paths.iter().enumerate() {
+                for b in &paths[i + 1..] {
+                    *pairs.entry((a.to_string(), b.to_string())).or_default() += 1;
+                }
+            }
+        }
+
+        // Deleted files aren't hotspots; keep what still exists at the ref.
+        let tree = self.repo.revparse_single(reference)?.peel_to_tree()?;
+        files.retain(|path, totals| match self.lines_at(&tree, path) {
+            Some(lines) => {
+                totals.lines = lines;
+                true
+            }
+            None => false,
+        });
+
+        // A directory counts each commit once, however many of its files changed.
+        let mut directories: HashMap<String, ChurnTotals> = HashMap::new();
+        for (path, totals) in &files {
+            for dir in parent_dirs(path) {
+                let dir_totals = directories.entry(dir.to_string()).or_default();
+                dir_totals.lines_added += totals.lines_added;
+                dir_totals.lines_removed += totals.lines_removed;
+                dir_totals.lines += totals.lines;
+            }
+        }
+        for stats in &commits {
+            let touched: HashSet<&str> = stats
+                .iter()
+                .filter(|f| files.contains_key(&f.path))
+                .flat_map(|f| parent_dirs(&f.path))
+                .collect();
+            for dir in touched {
+                if let Some(dir_totals) = directories.get_mut(dir) {
+                    dir_totals.commits += 1;
+                }
+            }
+        }
+
+        let mut coupling: Vec<ChangeCoupling> = pairs
+            .into_iter()
+            .filter(|(_, shared)| *shared >= opts.min_shared_commits)
+            .filter_map(|((file_a, file_b), shared_commits)| {
+                let a = files.get(&file_a)?.commits;
+                let b = files.get(&file_b)?.commits;
+                Some(ChangeCoupling {
+                    degree: shared_commits as f64 * 2.0 / (a + b) as f64,
+                    file_a,
+                    file_b,
+                    shared_commits,
+                })
+            })
+            .collect();
+        coupling.sort_by(|x, y| {
+            y.shared_commits
+                .cmp(&x.shared_commits)
+                .then_with(|| y.degree.total_cmp(&x.degree))
+                .then_with(|| x.file_a.cmp(&y.file_a))
+                .then_with(|| x.file_b.cmp(&y.file_b))
+        });
+        coupling.truncate(opts.limit);
+
+        Ok(ChurnResponse {
+            branch: reference.to_string(),
+            since: opts.since.clone(),
+            until: opts.until.clone(),
+            commits_analyzed: commits.len(),
+            files: rank_hotspots(files, opts.limit),
+            directories: rank_hotspots(directories, opts.limit),
+            coupling,
+        })
+    }
+
+    /// Author times of the commits reachable from `tip`, optionally limited
+    /// to an author (matched as `git log --author` does, after .mailmap) and
+    /// a path. `git log` does the walk, since its path limiting can use the
+    /// commit-graph's Bloom filters on large repositories.
+    fn activity_times(
+        &self,
+        tip: Oid,
+        author: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<Arc<Vec<DateTime<FixedOffset>>>> {
+        let key = (
+            self.path().to_string_lossy().to_string(),
+            tip,
+            author.map(str::to_string),
+            path.map(str::to_string),
+        );
+        if let Some(times) = ACTIVITY_CACHE.get(&key) {
+            return Ok(times);
+        }
+
+        let tip = tip.to_string();
+        let author_arg = author.map(|a| format!("--author={}", a));
+        let mut args = vec!["log", "--use-mailmap", "--format=%aI", tip.as_str()];
+        if let Some(author_arg) = &author_arg {
+            args.push(author_arg);
+        }
+        if let Some(path) = path {
+            args.extend(["--", path]);
+        }
+        let output = self.run_git(&args)?;
+        let times: Vec<DateTime<FixedOffset>> = output
+            .lines()
+            .filter_map(|line| DateTime::parse_from_rfc3339(line.trim()).ok())
+            .collect();
+
+        let times = Arc::new(times);
+        ACTIVITY_CACHE.insert(key, times.clone());
+        Ok(times)
+    }
+
+    /// Commit counts per period and per weekday and hour over the full
+    /// history of `reference`, filtered by author, path and date range.
+    pub fn commit_activity(
+        &self,
+        reference: &str,
+        bucket: TimeBucket,
+        author: Option<&str>,
+        path: Option<&str>,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<ActivityResponse> {
+        let since_ts = since.map(|s| parse_date_bound(s, false)).transpose()?;
+        let until_ts = until.map(|u| parse_date_bound(u, true)).transpose()?;
+        let tip = self.repo.revparse_single(reference)?.peel_to_commit()?.id();
+        let times = self.activity_times(tip, author, path)?;
+
+        let mut periods: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        let mut punchcard = vec![vec![0usize; 24]; 7];
+        let mut total_commits = 0;
+        for time in times.iter() {
+            let seconds = time.timestamp();
+            if since_ts.is_some_and(|s| seconds < s) || until_ts.is_some_and(|u| seconds > u) {
+                continue;
+            }
+            total_commits += 1;
+            *periods.entry(bucket.start(time.date_naive())).or_default() += 1;
+            punchcard[time.weekday().num_days_from_monday() as usize][time.hour() as usize] += 1;
+        }
+
+        let mut buckets = Vec::new();
+        if let (Some(&first), Some(&last)) = (periods.keys().next(), periods.keys().next_back()) {
+            let mut start = first;
+            while start <= last {
+                buckets.push(ActivityBucket {
+                    period: bucket.label(start),
+                    commits: periods.get(&start).copied().unwrap_or(0),
+                });
+                start = bucket.next(start);
+            }
+        }
+
+        Ok(ActivityResponse {
+            branch: reference.to_string(),
+            bucket: bucket.name().to_string(),
+            since: since.map(str::to_string),
+            until: until.map(str::to_string),
+            total_commits,
+            buckets,
+            punchcard,
+        })
+    }
+}
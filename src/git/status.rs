@@ -0,0 +1,398 @@
+use anyhow::{bail, Result};
+
+use super::repository::GitRepository;
+use crate::models::{
+    ConflictInfo, ConflictStage, StatusBranch, StatusFile, StatusResponse, SubmoduleState,
+};
+
+/// Options for reading the working tree status
+#[derive(Debug, Default)]
+pub struct StatusOptions {
+    /// Also list ignored files.
+    pub ignored: bool,
+    /// "normal" (untracked folders as one entry), "all" or "no"; defaults to "normal".
+    pub untracked: Option<String>,
+}
+
+impl GitRepository {
+    /// Working tree status from `git status --porcelain=v2 -z`, which keeps
+    /// paths unquoted and reports rename scores, conflict stages, submodule
+    /// state and the branch headers that v1 leaves out.
+    pub fn status(&self, opts: &StatusOptions) -> Result<StatusResponse> {
+        let untracked = match opts.untracked.as_deref().unwrap_or("normal") {
+            mode @ ("normal" | "all" | "no") => format!("--untracked-files={}", mode),
+            other => bail!(
+                "Unknown untracked mode '{}' (expected normal, all or no)",
+                other
+            ),
+        };
+        let mut args = vec![
+            "status",
+            "--porcelain=v2",
+            "-z",
+            "--branch",
+            untracked.as_str(),
+        ];
+        if opts.ignored {
+            args.push("--ignored=matching");
+        }
+        let output = self.run_git(&args)?;
+        Ok(parse_porcelain_v2(&output))
+    }
+}
+
+fn parse_porcelain_v2(output: &str) -> StatusResponse {
+    let mut branch = StatusBranch::default();
+    let mut files = Vec::new();
+    let mut records = output.split('\0');
+
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            parse_header(&mut branch, header);
+            continue;
+        }
+        let Some((kind, rest)) = record.split_once(' ') else {
+            continue;
+        };
+        match kind {
+            // 1 XY sub mH mI mW hH hI path
+            "1" => {
+                let fields: Vec<&str> = rest.splitn(8, ' ').collect();
+                if let [xy, sub, _, _, _, _, _, path] = fields[..] {
+                    files.push(changed_file(xy, sub, path, None, None));
+                }
+            }
+            // 2 XY sub mH mI mW hH hI Xscore path, then the source path as its own record
+            "2" => {
+                let fields: Vec<&str> = rest.splitn(9, ' ').collect();
+                if let [xy, sub, _, _, _, _, _, score, path] = fields[..] {
+                    let old_path = records.next().map(|s| s.to_string());
+                    let similarity = score.get(1..).and_then(|s| s.parse().ok());
+                    files.push(changed_file(xy, sub, path, old_path, similarity));
+                }
+            }
+            // u XY sub m1 m2 m3 mW h1 h2 h3 path
+            "u" => {
+                let fields: Vec<&str> = rest.splitn(10, ' ').collect();
+                if let [xy, sub, m1, m2, m3, _, h1, h2, h3, path] = fields[..] {
+                    let mut file = changed_file(xy, sub, path, None, None);
+                    file.r#type = "conflicted".to_string();
+                    file.conflict = Some(ConflictInfo {
+                        kind: conflict_kind(xy).to_string(),
+                        base: stage(m1, h1),
+                        ours: stage(m2, h2),
+                        theirs: stage(m3, h3),
+                        renamed_from: None,
+                    });
+                    files.push(file);
+                }
+            }
+            "?" => files.push(plain_file(rest, "??", "untracked")),
+            "!" => files.push(plain_file(rest, "!!", "ignored")),
+            _ => {}
+        }
+    }
+
+    mark_rename_conflicts(&mut files);
+    StatusResponse {
+        files,
+        branch: Some(branch),
+    }
+}
+
+/// A rename/rename conflict shows up as the source path with only a base
+/// stage (DD) and each new name with only our (AU) or their (UA) stage.
+/// Pair them up by blob, or by elimination when there is a single source.
+fn mark_rename_conflicts(files: &mut [StatusFile]) {
+    let sources: Vec<(String, String)> = files
+        .iter()
+        .filter_map(|f| {
+            let conflict = f.conflict.as_ref()?;
+            let base = conflict.base.as_ref()?;
+            (conflict.kind == "both-deleted").then(|| (f.path.clone(), base.sha.clone()))
+        })
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+
+    let mut renamed = Vec::new();
+    for file in files.iter_mut() {
+        let Some(conflict) = file.conflict.as_mut() else {
+            continue;
+        };
+        let side = match conflict.kind.as_str() {
+            "added-by-us" => conflict.ours.as_ref(),
+            "added-by-them" => conflict.theirs.as_ref(),
+            _ => continue,
+        };
+        let Some(side) = side else {
+            continue;
+        };
+        let source = sources
+            .iter()
+            .find(|(_, sha)| *sha == side.sha)
+            .or_else(|| (sources.len() == 1).then(|| &sources[0]));
+        if let Some((path, _)) = source {
+            conflict.kind = "rename-rename".to_string();
+            conflict.renamed_from = Some(path.clone());
+            renamed.push(path.clone());
+        }
+    }
+
+    for file in files.iter_mut() {
+        if let Some(conflict) = file.conflict.as_mut() {
+            if conflict.kind == "both-deleted" && renamed.contains(&file.path) {
+                conflict.kind = "rename-rename".to_string();
+            }
+        }
+    }
+}
+
+fn parse_header(branch: &mut StatusBranch, header: &str) {
+    let Some((key, value)) = header.split_once(' ') else {
+        return;
+    };
+    match key {
+        "branch.oid" => branch.oid = (value != "(initial)").then(|| value.to_string()),
+        "branch.head" => {
+            branch.detached = value == "(detached)";
+            branch.head = (!branch.detached).then(|| value.to_string());
+        }
+        "branch.upstream" => branch.upstream = Some(value.to_string()),
+        "branch.ab" => {
+            // "+1 -2"
+            for part in value.split(' ') {
+                if let Some(n) = part.strip_prefix('+') {
+                    branch.ahead = n.parse().ok();
+                } else if let Some(n) = part.strip_prefix('-') {
+                    branch.behind = n.parse().ok();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn changed_file(
+    xy: &str,
+    sub: &str,
+    path: &str,
+    old_path: Option<String>,
+    similarity: Option<u8>,
+) -> StatusFile {
+    let mut chars = xy.chars();
+    let x = chars.next().unwrap_or('.');
+    let y = chars.next().unwrap_or('.');
+    let file_type = if x == 'R' || y == 'R' {
+        "renamed"
+    } else if x == 'C' || y == 'C' {
+        "copied"
+    } else if x == 'A' {
+        "added"
+    } else if x == 'D' || y == 'D' {
+        "deleted"
+    } else if x == 'T' || y == 'T' {
+        "type-changed"
+    } else {
+        "modified"
+    };
+    StatusFile {
+        path: path.to_string(),
+        old_path,
+        // v1-style code, e.g. "M " or " M"
+        status: xy.replace('.', " "),
+        staged: x != '.',
+        unstaged: y != '.',
+        r#type: file_type.to_string(),
+        submodule: submodule_state(sub),
+        index_state: state_name(x).to_string(),
+        worktree_state: state_name(y).to_string(),
+        similarity,
+        conflict: None,
+    }
+}
+
+fn plain_file(path: &str, status: &str, file_type: &str) -> StatusFile {
+    StatusFile {
+        path: path.to_string(),
+        old_path: None,
+        status: status.to_string(),
+        staged: false,
+        unstaged: false,
+        r#type: file_type.to_string(),
+        submodule: None,
+        index_state: "unmodified".to_string(),
+        worktree_state: file_type.to_string(),
+        similarity: None,
+        conflict: None,
+    }
+}
+
+fn state_name(code: char) -> &'static str {
+    match code {
+        'M' => "modified",
+        'T' => "type-changed",
+        'A' => "added",
+        'D' => "deleted",
+        'R' => "renamed",
+        'C' => "copied",
+        'U' => "unmerged",
+        _ => "unmodified",
+    }
+}
+
+/// "N..." for regular files, "S<c><m><u>" for submodules.
+fn submodule_state(sub: &str) -> Option<SubmoduleState> {
+    let flags = sub.strip_prefix('S')?.as_bytes();
+    Some(SubmoduleState {
+        commit_changed: flags.first() == Some(&b'C'),
+        modified_content: flags.get(1) == Some(&b'M'),
+        untracked_content: flags.get(2) == Some(&b'U'),
+    })
+}
+
+fn conflict_kind(xy: &str) -> &'static str {
+    match xy {
+        "DD" => "both-deleted",
+        "AU" => "added-by-us",
+        "UD" => "deleted-by-them",
+        "UA" => "added-by-them",
+        "DU" => "deleted-by-us",
+        "AA" => "both-added",
+        _ => "both-modified",
+    }
+}
+
+/// A conflict stage; mode 000000 means that side has no version.
+fn stage(mode: &str, sha: &str) -> Option<ConflictStage> {
+    (mode != "000000").then(|| ConflictStage {
+        mode: mode.to_string(),
+        sha: sha.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZERO: &str = "0000000000000000000000000000000000000000";
+
+    fn parse(records: &[&str]) -> StatusResponse {
+        parse_porcelain_v2(&format!("{}\0", records.join("\0")))
+    }
+
+    #[test]
+    fn branch_headers() {
+        let status = parse(&[
+            "# branch.oid 1111111111111111111111111111111111111111",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +1 -2",
+        ]);
+        let branch = status.branch.unwrap();
+        assert_eq!(branch.head.as_deref(), Some("main"));
+        assert_eq!(branch.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((branch.ahead, branch.behind), (Some(1), Some(2)));
+        assert!(!branch.detached);
+
+        let unborn = parse(&["# branch.oid (initial)", "# branch.head (detached)"])
+            .branch
+            .unwrap();
+        assert_eq!(unborn.oid, None);
+        assert!(unborn.detached);
+        assert_eq!(unborn.head, None);
+    }
+
+    #[test]
+    fn ordinary_change_keeps_spaces_and_newlines_in_path() {
+        let status =
+            parse(&["1 .M N... 100644 100644 100644 aaaa aaaa dir/a file\nwith newline.txt"]);
+        let file = &status.files[0];
+        assert_eq!(file.path, "dir/a file\nwith newline.txt");
+        assert_eq!(file.status, " M");
+        assert!(!file.staged && file.unstaged);
+        assert_eq!(file.r#type, "modified");
+        assert_eq!(file.index_state, "unmodified");
+        assert_eq!(file.worktree_state, "modified");
+        assert!(file.submodule.is_none());
+    }
+
+    #[test]
+    fn rename_reads_source_from_next_record() {
+        let status = parse(&[
+            "2 R. N... 100644 100644 100644 aaaa bbbb R87 new name.txt",
+            "old name.txt",
+            "? after.txt",
+        ]);
+        assert_eq!(status.files.len(), 2);
+        let file = &status.files[0];
+        assert_eq!(file.path, "new name.txt");
+        assert_eq!(file.old_path.as_deref(), Some("old name.txt"));
+        assert_eq!(file.similarity, Some(87));
+        assert_eq!(file.r#type, "renamed");
+        assert!(file.staged && !file.unstaged);
+        assert_eq!(status.files[1].path, "after.txt");
+    }
+
+    #[test]
+    fn unmerged_entry_has_stages() {
+        let status = parse(&[
+            "u UU N... 100644 100644 100644 100644 base ours theirs both.txt",
+            &format!(
+                "u DU N... 100644 000000 100644 100644 base {} theirs gone.txt",
+                ZERO
+            ),
+        ]);
+        let both = &status.files[0];
+        assert_eq!(both.r#type, "conflicted");
+        let conflict = both.conflict.as_ref().unwrap();
+        assert_eq!(conflict.kind, "both-modified");
+        assert_eq!(conflict.base.as_ref().unwrap().sha, "base");
+        assert_eq!(conflict.ours.as_ref().unwrap().sha, "ours");
+        assert_eq!(conflict.theirs.as_ref().unwrap().sha, "theirs");
+
+        let gone = status.files[1].conflict.as_ref().unwrap();
+        assert_eq!(gone.kind, "deleted-by-us");
+        assert!(gone.ours.is_none());
+    }
+
+    #[test]
+    fn rename_rename_conflict_is_paired() {
+        let status = parse(&[
+            &format!(
+                "u DD N... 100644 000000 000000 000000 blob {0} {0} src.txt",
+                ZERO
+            ),
+            &format!(
+                "u AU N... 000000 100644 000000 100644 {0} left {0} left.txt",
+                ZERO
+            ),
+            &format!(
+                "u UA N... 000000 000000 100644 100644 {0} {0} right right.txt",
+                ZERO
+            ),
+        ]);
+        for file in &status.files {
+            assert_eq!(file.conflict.as_ref().unwrap().kind, "rename-rename");
+        }
+        let left = status.files[1].conflict.as_ref().unwrap();
+        assert_eq!(left.renamed_from.as_deref(), Some("src.txt"));
+    }
+
+    #[test]
+    fn untracked_ignored_and_submodule_entries() {
+        let status = parse(&[
+            "? new dir/",
+            "! target/",
+            "1 .M SCMU 160000 160000 160000 aaaa aaaa vendor/lib",
+        ]);
+        assert_eq!(status.files[0].r#type, "untracked");
+        assert_eq!(status.files[0].path, "new dir/");
+        assert_eq!(status.files[0].status, "??");
+        assert_eq!(status.files[1].r#type, "ignored");
+        assert_eq!(status.files[1].worktree_state, "ignored");
+        let sub = status.files[2].submodule.as_ref().unwrap();
+        assert!(sub.commit_changed && sub.modified_content && sub.untracked_content);
+    }
+}
@@ -0,0 +1,348 @@
+use anyhow::{bail, Context, Result};
+use git2::{
+    ConfigLevel, FetchOptions, Oid, Repository, Submodule, SubmoduleIgnore, SubmoduleStatus,
+    SubmoduleUpdateOptions,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::credentials::CredentialSession;
+use super::jobs;
+use super::repository::{FileDiff, GitRepository};
+use crate::models::{SubmoduleCommit, SubmoduleDiff, SubmoduleInfo, SubmoduleState};
+
+/// Commits listed on each side of a submodule pointer change.
+const MAX_POINTER_COMMITS: usize = 100;
+
+impl GitRepository {
+    /// Submodules with the commit recorded in HEAD, in the index and checked
+    /// out in the submodule's working tree.
+    pub fn list_submodules(&self) -> Result<Vec<SubmoduleInfo>> {
+        let mut submodules = Vec::new();
+        for submodule in self.repo.submodules()? {
+            let name = submodule.name().unwrap_or_default().to_string();
+            let path = submodule.path().to_string_lossy().replace('\\', "/");
+            let status = self.repo.submodule_status(&name, SubmoduleIgnore::None)?;
+            let head_id = submodule.head_id();
+            let workdir_id = submodule.workdir_id();
+            submodules.push(SubmoduleInfo {
+                url: submodule.url().map(|s| s.to_string()),
+                branch: submodule.branch().map(|s| s.to_string()),
+                configured_sha: head_id.map(|oid| oid.to_string()),
+                index_sha: submodule.index_id().map(|oid| oid.to_string()),
+                checked_out_sha: workdir_id.map(|oid| oid.to_string()),
+                initialized: self.is_submodule_initialized(&name),
+                checked_out: workdir_id.is_some(),
+                out_of_date: workdir_id.is_some() && workdir_id != submodule.index_id(),
+                dirty: status.intersects(
+                    SubmoduleStatus::WD_INDEX_MODIFIED
+                        | SubmoduleStatus::WD_WD_MODIFIED
+                        | SubmoduleStatus::WD_UNTRACKED,
+                ),
+                name,
+                path,
+            });
+        }
+        Ok(submodules)
+    }
+
+    /// Working tree state of every submodule, by path, for status.
+    pub fn submodule_states(&self) -> Result<HashMap<String, SubmoduleState>> {
+        let mut states = HashMap::new();
+        for submodule in self.repo.submodules()? {
+            let Some(name) = submodule.name() else {
+                continue;
+            };
+            let status = self.repo.submodule_status(name, SubmoduleIgnore::None)?;
+            states.insert(
+                submodule.path().to_string_lossy().replace('\\', "/"),
+                SubmoduleState {
+                    commit_changed: status.contains(SubmoduleStatus::WD_MODIFIED),
+                    modified_content: status.intersects(
+                        SubmoduleStatus::WD_INDEX_MODIFIED | SubmoduleStatus::WD_WD_MODIFIED,
+                    ),
+                    untracked_content: status.contains(SubmoduleStatus::WD_UNTRACKED),
+                },
+            );
+        }
+        Ok(states)
+    }
+
+    /// Copy submodule URLs from .gitmodules into .git/config, for the
+    /// submodules in `paths` or all of them.
+    pub fn init_submodules(&self, paths: &[String]) -> Result<Vec<String>> {
+        let mut initialized = Vec::new();
+        for mut submodule in self.selected_submodules(paths)? {
+            submodule.init(false)?;
+            initialized.push(submodule.path().to_string_lossy().to_string());
+        }
+        Ok(initialized)
+    }
+
+    /// Re-copy URLs after they changed in .gitmodules, into .git/config and
+    /// each checked-out submodule's remote.
+    pub fn sync_submodules(&self, paths: &[String], recursive: bool) -> Result<Vec<String>> {
+        let mut synced = Vec::new();
+        for mut submodule in self.selected_submodules(paths)? {
+            submodule.sync()?;
+            let path = submodule.path().to_string_lossy().to_string();
+            if recursive {
+                if let Ok(repo) = submodule.open() {
+                    let inner = GitRepository::open(repo.workdir().unwrap_or(repo.path()))?;
+                    for nested in inner.sync_submodules(&[], true)? {
+                        synced.push(format!("{}/{}", path, nested));
+                    }
+                }
+            }
+            synced.push(path);
+        }
+        Ok(synced)
+    }
+
+    /// Clone missing submodules and check out the commit recorded in the
+    /// index. With `init`, uninitialized submodules are initialized first
+    /// (`git submodule update --init`).
+    pub fn update_submodules(
+        &self,
+        paths: &[String],
+        init: bool,
+        recursive: bool,
+    ) -> Result<Vec<String>> {
+        let mut updated = Vec::new();
+        for submodule in self.selected_submodules(paths)? {
+            let path = submodule.path().to_string_lossy().to_string();
+            if !init && !self.is_submodule_initialized(submodule.name().unwrap_or_default()) {
+                continue;
+            }
+            jobs::set_stage("submodules");
+            let repo = update_one(&self.repo, submodule, init)
+                .with_context(|| format!("Failed to update submodule '{}'", path))?;
+            if recursive {
+                let inner = GitRepository::open(repo.workdir().unwrap_or(repo.path()))?;
+                for nested in inner.update_submodules(&[], init, true)? {
+                    updated.push(format!("{}/{}", path, nested));
+                }
+            }
+            updated.push(path);
+        }
+        Ok(updated)
+    }
+
+    /// Working tree folder of a checked-out submodule, to open it as a repository.
+    pub fn submodule_workdir(&self, path: &str) -> Result<PathBuf> {
+        let submodule = self.find_submodule_by_path(path)?;
+        let repo = submodule
+            .open()
+            .with_context(|| format!("Submodule '{}' is not checked out; update it first", path))?;
+        let workdir = repo.workdir().context("Submodule has no working tree")?;
+        Ok(workdir
+            .canonicalize()
+            .unwrap_or_else(|_| workdir.to_path_buf()))
+    }
+
+    /// A submodule pointer change from `old` to `new`, like
+    /// `git diff --submodule=log`: "Submodule lib a1b2c3d..c3d4e5f:" followed
+    /// by the commits in between, when the submodule's objects are available.
+    pub fn submodule_pointer_diff(
+        &self,
+        path: &str,
+        old: Option<Oid>,
+        new: Option<Oid>,
+    ) -> Result<FileDiff> {
+        let short = |oid: Option<Oid>| {
+            oid.map(|o| o.to_string()[..7].to_string())
+                .unwrap_or_else(|| "0000000".to_string())
+        };
+        let range = format!("{}..{}", short(old), short(new));
+        let mut diff = SubmoduleDiff {
+            path: path.to_string(),
+            old_sha: old.map(|o| o.to_string()),
+            new_sha: new.map(|o| o.to_string()),
+            range: range.clone(),
+            commits: Vec::new(),
+            rewind: false,
+            commits_available: false,
+        };
+
+        let mut text = match (old, new) {
+            (None, Some(_)) => format!("Submodule {} {} (new submodule)\n", path, range),
+            (Some(_), None) => format!("Submodule {} {} (submodule deleted)\n", path, range),
+            _ => String::new(),
+        };
+        if let (Some(old), Some(new)) = (old, new) {
+            if let Some(repo) = self.submodule_repo(path) {
+                if repo.find_commit(old).is_ok() && repo.find_commit(new).is_ok() {
+                    diff.commits_available = true;
+                    diff.commits.extend(walk_range(&repo, new, old, true)?);
+                    diff.commits.extend(walk_range(&repo, old, new, false)?);
+                    diff.rewind = !repo.graph_descendant_of(new, old)? && old != new;
+                }
+            }
+            text.push_str(&format!(
+                "Submodule {} {}{}:\n",
+                path,
+                range,
+                if diff.rewind { " (rewind)" } else { "" }
+            ));
+            if !diff.commits_available {
+                text.push_str("  (commits not present)\n");
+            }
+            for commit in &diff.commits {
+                text.push_str(&format!(
+                    "  {} {}\n",
+                    if commit.added { '>' } else { '<' },
+                    commit.summary
+                ));
+            }
+        }
+
+        Ok(FileDiff {
+            diff: text,
+            hunks: Vec::new(),
+            file_path: path.to_string(),
+            submodule: Some(diff),
+        })
+    }
+
+    /// Staged (HEAD to index) or unstaged (index to checkout) pointer change
+    /// of a submodule. Unstaged, changes inside the submodule are noted too.
+    pub fn submodule_working_diff(&self, path: &str, staged: bool) -> Result<FileDiff> {
+        let index_id = self
+            .repo
+            .index()?
+            .get_path(Path::new(path), 0)
+            .map(|e| e.id);
+        let (old, new) = if staged {
+            let head_id = self
+                .repo
+                .head()
+                .and_then(|h| h.peel_to_tree())
+                .and_then(|t| t.get_path(Path::new(path)))
+                .ok()
+                .map(|e| e.id());
+            (head_id, index_id)
+        } else {
+            let workdir_id = self
+                .find_submodule_by_path(path)
+                .ok()
+                .and_then(|s| s.workdir_id());
+            (index_id, workdir_id.or(index_id))
+        };
+
+        let mut diff = if old == new {
+            FileDiff {
+                diff: String::new(),
+                hunks: Vec::new(),
+                file_path: path.to_string(),
+                submodule: None,
+            }
+        } else {
+            self.submodule_pointer_diff(path, old, new)?
+        };
+        if !staged {
+            if let Some(state) = self.submodule_states()?.get(path) {
+                if state.modified_content {
+                    diff.diff
+                        .push_str(&format!("Submodule {} contains modified content\n", path));
+                }
+                if state.untracked_content {
+                    diff.diff
+                        .push_str(&format!("Submodule {} contains untracked content\n", path));
+                }
+            }
+        }
+        Ok(diff)
+    }
+
+    /// Registered in .git/config by `git submodule init`. libgit2's IN_CONFIG
+    /// status only means it is listed in .gitmodules.
+    fn is_submodule_initialized(&self, name: &str) -> bool {
+        self.repo
+            .config()
+            .and_then(|c| c.open_level(ConfigLevel::Local))
+            .and_then(|mut c| c.snapshot())
+            .and_then(|c| c.get_string(&format!("submodule.{}.url", name)))
+            .is_ok()
+    }
+
+    /// Whether `path` is a submodule in the index or HEAD.
+    pub fn is_submodule_path(&self, path: &str) -> bool {
+        self.repo.find_submodule(path).is_ok() || self.find_submodule_by_path(path).is_ok()
+    }
+
+    fn find_submodule_by_path(&self, path: &str) -> Result<Submodule<'_>> {
+        let wanted = Path::new(path.trim_end_matches('/'));
+        self.repo
+            .submodules()?
+            .into_iter()
+            .find(|s| s.path() == wanted)
+            .with_context(|| format!("'{}' is not a submodule", path))
+    }
+
+    fn selected_submodules(&self, paths: &[String]) -> Result<Vec<Submodule<'_>>> {
+        let all = self.repo.submodules()?;
+        let wanted: Vec<&Path> = paths
+            .iter()
+            .map(|p| Path::new(p.trim_end_matches('/')))
+            .collect();
+        if let Some(missing) = wanted.iter().find(|w| !all.iter().any(|s| s.path() == **w)) {
+            bail!("'{}' is not a submodule", missing.display());
+        }
+        Ok(all
+            .into_iter()
+            .filter(|s| wanted.is_empty() || wanted.contains(&s.path()))
+            .collect())
+    }
+
+    /// The submodule's repository: its checkout, or the clone kept in
+    /// .git/modules when it isn't checked out.
+    fn submodule_repo(&self, path: &str) -> Option<Repository> {
+        let submodule = self.find_submodule_by_path(path).ok()?;
+        if let Ok(repo) = submodule.open() {
+            return Some(repo);
+        }
+        let name = submodule.name()?;
+        Repository::open(self.repo.commondir().join("modules").join(name)).ok()
+    }
+}
+
+/// Commits reachable from `from` but not `hidden`, newest first.
+fn walk_range(
+    repo: &Repository,
+    from: Oid,
+    hidden: Oid,
+    added: bool,
+) -> Result<Vec<SubmoduleCommit>> {
+    let mut walk = repo.revwalk()?;
+    walk.push(from)?;
+    walk.hide(hidden)?;
+    let mut commits = Vec::new();
+    for oid in walk.take(MAX_POINTER_COMMITS) {
+        let commit = repo.find_commit(oid?)?;
+        commits.push(SubmoduleCommit {
+            sha: commit.id().to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            added,
+        });
+    }
+    Ok(commits)
+}
+
+/// `git submodule update [--init]` for one submodule through libgit2, with
+/// the credential providers used for every other fetch.
+fn update_one<'r>(
+    parent: &'r Repository,
+    mut submodule: Submodule<'r>,
+    init: bool,
+) -> Result<Repository> {
+    let session = CredentialSession::new(parent, true);
+    let result = {
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(session.callbacks());
+        let mut update = SubmoduleUpdateOptions::new();
+        update.fetch(fetch_options);
+        submodule.update(init, Some(&mut update))
+    };
+    session.finish(result)?;
+    Ok(submodule.open()?)
+}
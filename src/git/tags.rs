@@ -0,0 +1,370 @@
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use std::cmp::Ordering;
+
+use super::repository::GitRepository;
+use crate::models::Tag;
+
+/// Markers git appends to the message of signed tags (GPG, X.509 and SSH).
+const SIGNATURE_MARKERS: [&str; 3] = [
+    "-----BEGIN PGP SIGNATURE-----",
+    "-----BEGIN SIGNED MESSAGE-----",
+    "-----BEGIN SSH SIGNATURE-----",
+];
+
+/// Parsed `MAJOR.MINOR[.PATCH][-PRERELEASE][+BUILD]` with an optional `v` prefix.
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Option<String>,
+}
+
+impl Version {
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.rsplit('/').next().unwrap_or(name);
+        let name = name.strip_prefix(['v', 'V']).unwrap_or(name);
+        let name = name.split('+').next().unwrap_or(name);
+        let (core, pre) = match name.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (name, None),
+        };
+
+        let mut parts = core.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = match parts.next() {
+            Some(p) => p.parse().ok()?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                // A release sorts after any of its pre-releases.
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Semver pre-release precedence: dot-separated identifiers, numeric ones
+/// compared numerically and ranked below alphanumeric ones.
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+        }
+    }
+}
+
+/// Order tags for display: version tags first, highest version first,
+/// then all other tags newest first.
+fn sort_tags(tags: &mut [Tag]) {
+    tags.sort_by(
+        |a, b| match (Version::parse(&a.name), Version::parse(&b.name)) {
+            (Some(va), Some(vb)) => vb.cmp(&va).then_with(|| a.name.cmp(&b.name)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => b.date.cmp(&a.date).then_with(|| a.name.cmp(&b.name)),
+        },
+    );
+}
+
+/// Tag names reach git as arguments and refspecs, so they must be valid
+/// ref names that can't pass as an option.
+fn check_tag_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("tag name is required");
+    }
+    if name.starts_with('-') || !git2::Reference::is_valid_name(&format!("refs/tags/{}", name)) {
+        bail!("'{}' is not a valid tag name", name);
+    }
+    Ok(())
+}
+
+fn format_time(time: git2::Time) -> String {
+    DateTime::from_timestamp(time.seconds(), 0)
+        .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap())
+        .to_rfc3339()
+}
+
+impl GitRepository {
+    /// List all tags with the commit they point at. Annotated tags also carry
+    /// tagger, message and whether they are signed; with `verify`, signed tags
+    /// are checked with `git verify-tag`.
+    pub fn list_tags(&self, verify: bool) -> Result<Vec<Tag>> {
+        let mut tags = Vec::new();
+
+        for reference in self.repo.references_glob("refs/tags/*")? {
+            let reference = reference?;
+            let Some(name) = reference.shorthand().map(|s| s.to_string()) else {
+                continue;
+            };
+            let Some(object_id) = reference.target() else {
+                continue;
+            };
+
+            // Peel through (possibly nested) tag objects to the tagged commit.
+            // Tags of trees and blobs are still listed, without one.
+            let commit = reference.peel_to_commit().ok();
+
+            let mut tag = Tag {
+                name,
+                sha: commit
+                    .as_ref()
+                    .map(|c| c.id().to_string())
+                    .unwrap_or_default(),
+                object_sha: object_id.to_string(),
+                date: commit
+                    .as_ref()
+                    .map(|c| format_time(c.time()))
+                    .unwrap_or_default(),
+                annotated: false,
+                tagger: None,
+                tagger_email: None,
+                message: None,
+                signature_status: "unsigned".to_string(),
+            };
+
+            if let Ok(tag_obj) = self.repo.find_tag(object_id) {
+                let raw_message = tag_obj.message().unwrap_or_default();
+                let signature_start = SIGNATURE_MARKERS
+                    .iter()
+                    .filter_map(|m| raw_message.find(m))
+                    .min();
+
+                tag.annotated = true;
+                if let Some(tagger) = tag_obj.tagger() {
+                    tag.date = format_time(tagger.when());
+                    tag.tagger = tagger.name().map(|s| s.to_string());
+                    tag.tagger_email = tagger.email().map(|s| s.to_string());
+                }
+                tag.message = Some(
+                    raw_message[..signature_start.unwrap_or(raw_message.len())]
+                        .trim_end()
+                        .to_string(),
+                );
+                if signature_start.is_some() {
+                    tag.signature_status = if verify {
+                        self.verify_tag(&tag.name)
+                    } else {
+                        "signed".to_string()
+                    };
+                }
+            }
+
+            tags.push(tag);
+        }
+
+        sort_tags(&mut tags);
+        Ok(tags)
+    }
+
+    /// Check a signed tag's signature. Returns "good", "bad", "unknown-key" or "error".
+    pub fn verify_tag(&self, name: &str) -> String {
+        match self.run_git(&["verify-tag", "--raw", name]) {
+            Ok(_) => "good".to_string(),
+            // --raw prints gpg status lines to stderr, which run_git returns as the error.
+            Err(e) => {
+                let status = e.to_string();
+                if status.contains("BADSIG") {
+                    "bad".to_string()
+                } else if status.contains("NO_PUBKEY") || status.contains("ERRSIG") {
+                    "unknown-key".to_string()
+                } else {
+                    "error".to_string()
+                }
+            }
+        }
+    }
+
+    /// Create a tag at `target`. A message makes it annotated; `sign` creates a
+    /// signed annotated tag using the user's configured signing key.
+    pub fn create_tag(
+        &self,
+        name: &str,
+        target: &str,
+        message: Option<&str>,
+        sign: bool,
+        force: bool,
+    ) -> Result<String> {
+        check_tag_name(name)?;
+
+        let mut args = vec!["tag"];
+        if sign {
+            args.push("-s");
+        } else if message.is_some() {
+            args.push("-a");
+        }
+        if sign || message.is_some() {
+            // Signed tags are always annotated; default the message to the name.
+            args.extend(["-m", message.unwrap_or(name)]);
+        }
+        if force {
+            args.push("-f");
+        }
+        args.extend(["--", name, target]);
+        self.journaled(&format!("Create tag {}", name), || self.run_git(&args))
+    }
+
+    /// Delete a tag locally and/or from a remote.
+    pub fn delete_tag(&self, name: &str, local: bool, remote: Option<&str>) -> Result<String> {
+        check_tag_name(name)?;
+        let mut output = String::new();
+        if let Some(remote) = remote {
+            output.push_str(&self.push_refspecs(remote, &[format!(":refs/tags/{}", name)])?);
+        }
+        if local {
+            let deleted = self.journaled(&format!("Delete tag {}", name), || {
+                self.run_git(&["tag", "-d", name])
+            })?;
+            output.push_str(&deleted);
+        }
+        Ok(output)
+    }
+
+    /// Push the given tags to `remote`, or every tag when `names` is empty.
+    pub fn push_tags(&self, remote: &str, names: &[String]) -> Result<String> {
+        let names = if names.is_empty() {
+            let all = self.repo.tag_names(None)?;
+            all.iter().flatten().map(|s| s.to_string()).collect()
+        } else {
+            for name in names {
+                check_tag_name(name)?;
+            }
+            names.to_vec()
+        };
+        if names.is_empty() {
+            bail!("No tags to push");
+        }
+        let refspecs: Vec<String> = names
+            .iter()
+            .map(|n| format!("refs/tags/{0}:refs/tags/{0}", n))
+            .collect();
+        self.push_refspecs(remote, &refspecs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str, date: &str) -> Tag {
+        Tag {
+            name: name.to_string(),
+            sha: String::new(),
+            object_sha: String::new(),
+            date: date.to_string(),
+            annotated: false,
+            tagger: None,
+            tagger_email: None,
+            message: None,
+            signature_status: "unsigned".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_version_forms() {
+        let v = Version::parse("v1.2.3-rc.1+build.5").unwrap();
+        assert_eq!((v.major, v.minor, v.patch), (1, 2, 3));
+        assert_eq!(v.pre.as_deref(), Some("rc.1"));
+        assert_eq!(Version::parse("release/2.0"), Version::parse("2.0.0"));
+        assert!(Version::parse("1").is_none());
+        assert!(Version::parse("1.2.3.4").is_none());
+        assert!(Version::parse("latest").is_none());
+    }
+
+    #[test]
+    fn prerelease_precedence_follows_semver() {
+        // The example from the semver specification, in ascending order.
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            let (a, b) = (
+                Version::parse(pair[0]).unwrap(),
+                Version::parse(pair[1]).unwrap(),
+            );
+            assert!(a < b, "{} should sort before {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn numeric_parts_compare_numerically() {
+        assert!(Version::parse("v1.10.0") > Version::parse("v1.9.9"));
+        assert!(Version::parse("2.0.0") > Version::parse("1.99.99"));
+    }
+
+    #[test]
+    fn versions_first_then_other_tags_newest_first() {
+        let mut tags = vec![
+            tag("nightly", "2024-03-01T00:00:00+00:00"),
+            tag("v1.9.0", "2024-01-01T00:00:00+00:00"),
+            tag("v1.10.0-rc.1", "2024-02-01T00:00:00+00:00"),
+            tag("deploy", "2024-04-01T00:00:00+00:00"),
+            tag("v1.10.0", "2024-02-15T00:00:00+00:00"),
+        ];
+        sort_tags(&mut tags);
+        let names: Vec<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["v1.10.0", "v1.10.0-rc.1", "v1.9.0", "deploy", "nightly"]
+        );
+    }
+
+    #[test]
+    fn tag_names_are_checked() {
+        assert!(check_tag_name("v1.0.0").is_ok());
+        assert!(check_tag_name("release/2024").is_ok());
+        assert!(check_tag_name("").is_err());
+        assert!(check_tag_name("--delete").is_err());
+        assert!(check_tag_name("bad..name").is_err());
+        assert!(check_tag_name("with space").is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use git2::{ObjectType, Oid, Pathspec, PathspecFlags, Sort, TreeWalkMode, TreeWalkResult};
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use super::repository::GitRepository;
+use crate::models::{LastCommitInfo, TreeItem};
+
+/// Upper bound on commits inspected when attributing last-change commits.
+/// Entries still unresolved after this many commits are returned without one.
+const LAST_COMMIT_WALK_LIMIT: usize = 100_000;
+
+/// Cache key for last-change lookups: (repo_path, dir_path, commit, tree_oid).
+type LastCommitKey = (String, String, Oid, Oid);
+
+/// Last-change commits per directory listing. The commit pins the history
+/// searched (the same tree reached from another ref may have a different
+/// one) and the tree OID the contents, so entries never go stale; the TTL
+/// only bounds memory.
+static LAST_COMMIT_CACHE: Lazy<Cache<LastCommitKey, HashMap<String, LastCommitInfo>>> =
+    Lazy::new(|| {
+        Cache::builder()
+            .time_to_live(Duration::from_secs(600))
+            .max_capacity(1_000)
+            .build()
+    });
+
+/// Result of listing a tree at a given ref.
+pub struct TreeListing {
+    pub commit: Oid,
+    pub tree_oid: Oid,
+    pub entries: Vec<TreeItem>,
+}
+
+/// Map a git filemode to the entry kind reported to the frontend.
+fn kind_for_mode(mode: i32) -> &'static str {
+    match mode & 0o170000 {
+        0o040000 => "tree",
+        0o160000 => "submodule",
+        0o120000 => "symlink",
+        _ => "blob",
+    }
+}
+
+impl GitRepository {
+    /// List the entries of the directory `dir` at `reference`.
+    /// With `recursive`, all descendants are listed (directories included)
+    /// and `pattern` filters them by git pathspec glob (e.g. `*.rs`, `src/**/mod.rs`).
+    pub fn list_tree(
+        &self,
+        reference: &str,
+        dir: &str,
+        recursive: bool,
+        pattern: Option<&str>,
+    ) -> Result<TreeListing> {
+        let commit = self.repo.revparse_single(reference)?.peel_to_commit()?;
+        let dir = dir.trim_matches('/');
+
+        let tree = if dir.is_empty() {
+            commit.tree()?
+        } else {
+            let entry = commit.tree()?.get_path(Path::new(dir))?;
+            if entry.kind() != Some(ObjectType::Tree) {
+                bail!("'{}' is not a directory", dir);
+            }
+            self.repo.find_tree(entry.id())?
+        };
+
+        let pathspec = match pattern.filter(|p| !p.is_empty()) {
+            Some(p) => Some(Pathspec::new([p])?),
+            None => None,
+        };
+        let odb = self.repo.odb()?;
+
+        let mut entries = Vec::new();
+        let mut push_entry = |parent: &str, entry: &git2::TreeEntry| {
+            let name = entry.name().unwrap_or_default().to_string();
+            let rel = format!("{}{}", parent, name);
+            let path = if dir.is_empty() {
+                rel
+            } else {
+                format!("{}/{}", dir, rel)
+            };
+
+            if let Some(spec) = &pathspec {
+                if !spec.matches_path(Path::new(&path), PathspecFlags::DEFAULT) {
+                    return;
+                }
+            }
+
+            let mode = entry.filemode();
+            let kind = kind_for_mode(mode);
+            let size = if kind == "blob" || kind == "symlink" {
+                odb.read_header(entry.id())
+                    .ok()
+                    .map(|(size, _)| size as u64)
+            } else {
+                None
+            };
+
+            entries.push(TreeItem {
+                name,
+                path,
+                kind: kind.to_string(),
+                mode: format!("{:06o}", mode),
+                oid: entry.id().to_string(),
+                size,
+                last_commit: None,
+            });
+        };
+
+        if recursive {
+            tree.walk(TreeWalkMode::PreOrder, |parent, entry| {
+                push_entry(parent, entry);
+                TreeWalkResult::Ok
+            })?;
+        } else {
+            for entry in tree.iter() {
+                push_entry("", &entry);
+            }
+            // Directories first, then files, like most tree views.
+            entries.sort_by(|a, b| {
+                (a.kind != "tree")
+                    .cmp(&(b.kind != "tree"))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+        }
+
+        Ok(TreeListing {
+            commit: commit.id(),
+            tree_oid: tree.id(),
+            entries,
+        })
+    }
+
+    /// Attach the last commit that changed each entry of a single-directory listing.
+    /// Results are cached by the listing's commit and the directory's tree OID.
+    pub fn annotate_last_commits(&self, listing: &mut TreeListing, dir: &str) -> Result<()> {
+        let dir = dir.trim_matches('/').to_string();
+        let cache_key = (
+            self.path().to_string_lossy().to_string(),
+            dir.clone(),
+            listing.commit,
+            listing.tree_oid,
+        );
+
+        let found = match LAST_COMMIT_CACHE.get(&cache_key) {
+            Some(found) => found,
+            None => {
+                let targets: HashMap<String, Oid> = listing
+                    .entries
+                    .iter()
+                    .filter_map(|e| Oid::from_str(&e.oid).ok().map(|oid| (e.name.clone(), oid)))
+                    .collect();
+                let found = self.find_last_commits(listing.commit, &dir, targets)?;
+                LAST_COMMIT_CACHE.insert(cache_key, found.clone());
+                found
+            }
+        };
+
+        for entry in listing.entries.iter_mut() {
+            entry.last_commit = found.get(&entry.name).cloned();
+        }
+        Ok(())
+    }
+
+    /// Single history pass attributing each directory entry to the commit that
+    /// introduced its current OID. Commits whose copy of `dir` is identical to
+    /// a parent's are skipped without loading the directory tree.
+    fn find_last_commits(
+        &self,
+        start: Oid,
+        dir: &str,
+        targets: HashMap<String, Oid>,
+    ) -> Result<HashMap<String, LastCommitInfo>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(start)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        // Memoize commit -> dir tree OID, since every commit is looked up
+        // once as itself and once per child as a parent.
+        let mut dir_oids: HashMap<Oid, Option<Oid>> = HashMap::new();
+        let mut dir_oid_of = |commit: &git2::Commit| -> Option<Oid> {
+            *dir_oids.entry(commit.id()).or_insert_with(|| {
+                if dir.is_empty() {
+                    Some(commit.tree_id())
+                } else {
+                    commit
+                        .tree()
+                        .ok()
+                        .and_then(|t| t.get_path(Path::new(dir)).ok())
+                        .filter(|e| e.kind() == Some(ObjectType::Tree))
+                        .map(|e| e.id())
+                }
+            })
+        };
+
+        let mut pending = targets;
+        let mut found = HashMap::new();
+
+        for oid in revwalk.take(LAST_COMMIT_WALK_LIMIT) {
+            if pending.is_empty() {
+                break;
+            }
+            let commit = self.repo.find_commit(oid?)?;
+            let Some(dir_oid) = dir_oid_of(&commit) else {
+                continue;
+            };
+
+            let parents: Vec<git2::Commit> = commit.parents().collect();
+            let parent_dir_oids: Vec<Option<Oid>> = parents.iter().map(&mut dir_oid_of).collect();
+            if parent_dir_oids.contains(&Some(dir_oid)) {
+                continue;
+            }
+
+            let dir_tree = self.repo.find_tree(dir_oid)?;
+            let parent_trees: Vec<git2::Tree> = parent_dir_oids
+                .iter()
+                .flatten()
+                .filter_map(|oid| self.repo.find_tree(*oid).ok())
+                .collect();
+
+            let introduced: Vec<String> = pending
+                .iter()
+                .filter(|(name, target)| {
+                    dir_tree.get_name(name).map(|e| e.id()) == Some(**target)
+                        && parent_trees
+                            .iter()
+                            .all(|t| t.get_name(name).map(|e| e.id()) != Some(**target))
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+
+            if introduced.is_empty() {
+                continue;
+            }
+
+            let date = DateTime::from_timestamp(commit.time().seconds(), 0)
+                .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap())
+                .to_rfc3339();
+            let info = LastCommitInfo {
+                sha: commit.id().to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                date,
+                message: commit.summary().unwrap_or_default().to_string(),
+            };
+            for name in introduced {
+                pending.remove(&name);
+                found.insert(name, info.clone());
+            }
+        }
+
+        Ok(found)
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{bail, Context, Result};
+use git2::{Repository, WorktreeLockStatus};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::repository::GitRepository;
+use crate::models::WorktreeInfo;
+use crate::utils::repo_id;
+
+/// Options for adding a linked worktree
+#[derive(Debug, Default)]
+pub struct AddWorktreeOptions {
+    /// Where to create it; defaults to `<repo>-<branch>` next to the main worktree.
+    pub path: Option<PathBuf>,
+    /// Existing branch to check out.
+    pub branch: Option<String>,
+    /// Create this branch instead, at `start_point` (HEAD when omitted).
+    pub new_branch: Option<String>,
+    pub start_point: Option<String>,
+}
+
+impl GitRepository {
+    /// The main worktree followed by every linked worktree, including ones
+    /// whose folder has been deleted (those are `prunable`).
+    pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        let current = self
+            .path()
+            .canonicalize()
+            .unwrap_or_else(|_| self.path().to_path_buf());
+        let mut worktrees = Vec::new();
+
+        // Opened from a linked worktree, the main one is found via the common dir.
+        let main = if self.repo.is_worktree() {
+            Repository::open(self.repo.commondir())?
+        } else {
+            Repository::open(self.repo.path())?
+        };
+        if let Some(workdir) = main.workdir() {
+            let mut info = describe_worktree(&main, workdir, &current);
+            info.is_main = true;
+            worktrees.push(info);
+        }
+
+        for name in self.repo.worktrees()?.iter().flatten() {
+            let worktree = self.repo.find_worktree(name)?;
+            let path = worktree.path();
+            let mut info = match Repository::open_from_worktree(&worktree) {
+                Ok(repo) if path.exists() => describe_worktree(&repo, path, &current),
+                _ => WorktreeInfo {
+                    path: repo_id(path),
+                    ..Default::default()
+                },
+            };
+            info.name = Some(name.to_string());
+            if let Ok(WorktreeLockStatus::Locked(reason)) = worktree.is_locked() {
+                info.locked = true;
+                info.lock_reason = reason
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty());
+            }
+            info.prunable = worktree.validate().is_err() && !info.locked;
+            worktrees.push(info);
+        }
+        Ok(worktrees)
+    }
+
+    /// Branches checked out in a worktree other than this one, with that
+    /// worktree's path. git refuses to check them out here.
+    pub fn branches_in_other_worktrees(&self) -> Result<HashMap<String, String>> {
+        Ok(self
+            .list_worktrees()?
+            .into_iter()
+            .filter(|w| !w.is_current && !w.prunable)
+            .filter_map(|w| Some((w.branch?, w.path)))
+            .collect())
+    }
+
+    /// Add a linked worktree and return its path.
+    pub fn add_worktree(&self, opts: &AddWorktreeOptions) -> Result<PathBuf> {
+        let new_branch = opts
+            .new_branch
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty());
+        let branch = opts
+            .branch
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty());
+        let Some(name) = new_branch.or(branch) else {
+            bail!("Choose a branch to check out or a new branch to create");
+        };
+        if name.starts_with('-') || !git2::Reference::is_valid_name(&format!("refs/heads/{}", name))
+        {
+            bail!("'{}' is not a valid branch name", name);
+        }
+        let start_point = opts.start_point.as_deref().filter(|s| !s.is_empty());
+        if let Some(start) = start_point.filter(|s| s.starts_with('-')) {
+            bail!("Invalid start point '{}'", start);
+        }
+        if new_branch.is_none() {
+            let worktrees = self.list_worktrees()?;
+            if let Some(w) = worktrees
+                .iter()
+                .find(|w| !w.prunable && w.branch.as_deref() == Some(name))
+            {
+                bail!("'{}' is already checked out in {}", name, w.path);
+            }
+        }
+
+        let path = match &opts.path {
+            Some(path) => path.clone(),
+            None => self.default_worktree_path(name)?,
+        };
+        if path.exists() && path.read_dir().map_or(true, |mut d| d.next().is_some()) {
+            bail!("{} already exists", path.display());
+        }
+        let path_arg = path.to_string_lossy().to_string();
+
+        let mut args = vec!["worktree", "add"];
+        if let Some(new_branch) = new_branch {
+            args.extend(["-b", new_branch, "--", &path_arg]);
+            if let Some(start) = start_point {
+                args.push(start);
+            }
+        } else {
+            args.extend(["--", path_arg.as_str(), name]);
+        }
+        self.run_git(&args)?;
+        Ok(path.canonicalize().unwrap_or(path))
+    }
+
+    /// Remove a linked worktree's folder and administrative files. Without
+    /// `force`, git refuses when it has uncommitted changes or is locked.
+    pub fn remove_worktree(&self, path: &str, force: bool) -> Result<String> {
+        let worktree = self.find_linked_worktree(path)?;
+        let path = worktree.path.clone();
+        let mut args = vec!["worktree", "remove"];
+        if force {
+            // Twice also removes locked worktrees.
+            args.extend(["--force", "--force"]);
+        }
+        args.push(&path);
+        self.run_git(&args)
+    }
+
+    /// Forget worktrees whose folder no longer exists. Returns their names.
+    pub fn prune_worktrees(&self) -> Result<Vec<String>> {
+        let prunable: Vec<String> = self
+            .list_worktrees()?
+            .into_iter()
+            .filter(|w| w.prunable)
+            .filter_map(|w| w.name)
+            .collect();
+        self.run_git(&["worktree", "prune"])?;
+        Ok(prunable)
+    }
+
+    /// A linked worktree of this repository, by its path.
+    pub fn find_linked_worktree(&self, path: &str) -> Result<WorktreeInfo> {
+        let wanted = Path::new(path);
+        let wanted = wanted
+            .canonicalize()
+            .unwrap_or_else(|_| wanted.to_path_buf());
+        self.list_worktrees()?
+            .into_iter()
+            .find(|w| {
+                !w.is_main && {
+                    let p = Path::new(&w.path);
+                    p.canonicalize().unwrap_or_else(|_| p.to_path_buf()) == wanted
+                }
+            })
+            .with_context(|| format!("{} is not a linked worktree of this repository", path))
+    }
+
+    /// `<parent>/<repo>-<branch>` next to the main worktree, with '/' in the
+    /// branch name replaced.
+    fn default_worktree_path(&self, branch: &str) -> Result<PathBuf> {
+        let main = self
+            .list_worktrees()?
+            .into_iter()
+            .find(|w| w.is_main)
+            .map(|w| PathBuf::from(w.path))
+            .context("A bare repository needs an explicit worktree path")?;
+        let main = main.canonicalize().unwrap_or(main);
+        let folder = main
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let parent = main
+            .parent()
+            .context("The repository has no parent folder")?;
+        Ok(parent.join(format!("{}-{}", folder, branch.replace(['/', '\\'], "-"))))
+    }
+}
+
+fn describe_worktree(repo: &Repository, path: &Path, current: &Path) -> WorktreeInfo {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut info = WorktreeInfo {
+        path: repo_id(&path),
+        is_current: path == current,
+        ..Default::default()
+    };
+    match repo.head() {
+        Ok(head) => {
+            info.head = head.target().map(|oid| oid.to_string());
+            if head.is_branch() {
+                info.branch = head.shorthand().map(|s| s.to_string());
+            } else {
+                info.detached = true;
+            }
+        }
+        Err(_) => {
+            // Unborn branch
+            info.branch = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|h| h.symbolic_target().map(|s| s.to_string()))
+                .and_then(|t| t.strip_prefix("refs/heads/").map(|s| s.to_string()));
+        }
+    }
+    info
+}
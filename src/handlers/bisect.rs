@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::models::{BisectState, ErrorResponse, GitOperationResponse};
+use crate::utils::open_repo;
+
+#[derive(Deserialize)]
+pub struct BisectStartRequest {
+    bad: String,
+    #[serde(default)]
+    good: Vec<String>,
+    /// Only test commits that touch these paths
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BisectMarkQuery {
+    /// good, bad or skip
+    term: String,
+    /// Defaults to the commit checked out
+    commit: Option<String>,
+}
+
+fn bisect_error(e: anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    )
+}
+
+/// The bisect in progress (null if none)
+pub async fn get_bisect(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Option<BisectState>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    let state = git_repo.bisect_state().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to read bisect state: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(state))
+}
+
+/// Start bisecting between a bad commit and known good ones
+pub async fn start_bisect(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Json(req): Json<BisectStartRequest>,
+) -> Result<Json<BisectState>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    git_repo
+        .bisect_start(&req.bad, &req.good, &req.paths)
+        .map(Json)
+        .map_err(bisect_error)
+}
+
+/// Mark a commit good, bad or skip and move to the next one to test
+pub async fn mark_bisect(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<BisectMarkQuery>,
+) -> Result<Json<BisectState>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    git_repo
+        .bisect_mark(&params.term, params.commit.as_deref())
+        .map(Json)
+        .map_err(bisect_error)
+}
+
+/// End the bisect and return to the original branch
+pub async fn reset_bisect(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    match git_repo.bisect_reset() {
+        Ok(output) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some("Bisect ended".to_string()),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
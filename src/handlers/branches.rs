@@ -94,7 +94,7 @@ pub async fn get_branches(
             tracing::debug!("Background fetch started for {}", repo_path_clone.display());
             match GitRepository::open(&repo_path_clone) {
                 Ok(git_repo) => {
-                    if let Err(e) = git_repo.fetch_all() {
+                    if let Err(e) = git_repo.fetch_all(false) {
                         tracing::warn!(
                             "Background fetch for repo '{}' failed: {}",
                             repo_path_clone.display(),
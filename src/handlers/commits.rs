@@ -10,7 +10,7 @@ use std::sync::Mutex;
 
 use crate::config::Config;
 use crate::git::repository::GitRepository;
-use crate::models::{Commit, CommitMetric, CommitsBetweenResponse, ErrorResponse, Tag};
+use crate::models::{Commit, CommitMetric, CommitsBetweenResponse, ErrorResponse};
 use crate::utils::{get_repo_path, normalize_sha};
 use anyhow;
 
@@ -341,40 +341,3 @@ pub async fn get_commit_metrics(
 
     Ok(Json(metrics))
 }
-
-pub async fn get_tags(
-    State(config): State<Config>,
-    Path(repo): Path<String>,
-) -> Result<Json<Vec<Tag>>, (StatusCode, Json<ErrorResponse>)> {
-    let repo_path = get_repo_path(&repo, &config.repos_root);
-    let git_repo = GitRepository::open(&repo_path).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to open repository: {}", e),
-            }),
-        )
-    })?;
-
-    let tags_out = git_repo.run_git(&[
-        "for-each-ref",
-        "refs/tags",
-        "--format=%(refname:short)%00%(objectname)%00%(creatordate:iso-strict)",
-    ]);
-
-    let mut tags = Vec::new();
-    if let Ok(output) = tags_out {
-        for line in output.lines() {
-            let parts: Vec<&str> = line.split('\0').collect();
-            if parts.len() >= 3 {
-                tags.push(Tag {
-                    name: parts[0].to_string(),
-                    sha: parts[1].to_string(),
-                    date: parts[2].to_string(),
-                });
-            }
-        }
-    }
-
-    Ok(Json(tags))
-}
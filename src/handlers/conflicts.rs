@@ -7,25 +7,34 @@ use serde::Deserialize;
 use std::fs;
 
 use crate::config::Config;
+use crate::git::autoresolve;
 use crate::git::repository::run_git;
 use crate::models::{
-    ConflictFile, ConflictFileResponse, ConflictsResponse, ErrorResponse, ResolveConflictRequest,
+    AutoResolveResponse, ConflictFileResponse, ConflictStrategyRule, ConflictsResponse,
+    ErrorResponse, ResolveConflictRequest, ResolveRegionsRequest, ResolveRegionsResponse,
     SuccessResponse,
 };
-use crate::utils::get_repo_path;
+use crate::utils::{get_repo_path, open_repo};
 
 #[derive(Deserialize)]
 pub struct ConflictFileQuery {
     path: String,
 }
 
+#[derive(Deserialize)]
+pub struct RerereQuery {
+    enabled: bool,
+}
+
+/// Conflicted files with their conflict kind, and files auto-resolved
+/// during the current merge/rebase
 pub async fn get_conflicts(
     State(config): State<Config>,
     Path(repo): Path<String>,
 ) -> Result<Json<ConflictsResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let git_repo = open_repo(&config, &repo)?;
 
-    let status_out = run_git(&["status", "--porcelain"], &repo_path).map_err(|e| {
+    let conflicts = git_repo.conflicts().map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -33,86 +42,30 @@ pub async fn get_conflicts(
             }),
         )
     })?;
-
-    let lines: Vec<&str> = status_out.split('\n').collect();
-    let mut conflicted_files = Vec::new();
-
-    for line in lines {
-        let line = line.trim();
-        if line.len() < 3 {
-            continue;
-        }
-
-        let status1 = line.chars().next().unwrap_or(' ');
-        let status2 = line.chars().nth(1).unwrap_or(' ');
-
-        let is_conflict = (status1 == 'A' && status2 == 'A')
-            || status1 == 'U'
-            || status2 == 'U'
-            || (status1 == 'D' && status2 == 'D')
-            || (status1 == 'A' && status2 == 'U')
-            || (status1 == 'U' && status2 == 'A')
-            || (status1 == 'D' && status2 == 'U')
-            || (status1 == 'U' && status2 == 'D');
-
-        if is_conflict {
-            let file_path = &line[3..];
-            if file_path.contains(" -> ") {
-                let parts: Vec<&str> = file_path.split(" -> ").collect();
-                if parts.len() == 2 {
-                    conflicted_files.push(ConflictFile {
-                        path: parts[1].to_string(),
-                        r#type: "both-modified".to_string(),
-                    });
-                }
-            } else {
-                conflicted_files.push(ConflictFile {
-                    path: file_path.to_string(),
-                    r#type: "both-modified".to_string(),
-                });
-            }
-        }
-    }
-
-    Ok(Json(ConflictsResponse {
-        files: conflicted_files.clone(),
-        has_conflicts: !conflicted_files.is_empty(),
-    }))
+    Ok(Json(conflicts))
 }
 
+/// Base/mine/theirs/result of a conflicted file and its conflict regions
 pub async fn get_conflict_file(
     State(config): State<Config>,
     Path(repo): Path<String>,
     Query(params): Query<ConflictFileQuery>,
 ) -> Result<Json<ConflictFileResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let git_repo = open_repo(&config, &repo)?;
 
-    // Get Base (common ancestor), Mine (current/ours), and Theirs (incoming)
-    // :1: = base, :2: = ours, :3: = theirs
-    let base = run_git(&["show", &format!(":1:{}", params.path)], &repo_path).unwrap_or_default();
-
-    let mine =
-        run_git(&["show", &format!(":2:{}", params.path)], &repo_path).unwrap_or_else(|_| {
-            // Fallback to working tree
-            let full_path = repo_path.join(&params.path);
-            fs::read_to_string(&full_path).unwrap_or_default()
-        });
-
-    let theirs = run_git(&["show", &format!(":3:{}", params.path)], &repo_path).unwrap_or_default();
-
-    // Get current conflicted content (working tree)
-    let full_path = repo_path.join(&params.path);
-    let result = fs::read_to_string(&full_path).unwrap_or_default();
-
-    Ok(Json(ConflictFileResponse {
-        base,
-        mine,
-        theirs,
-        result,
-        file_path: params.path,
-    }))
+    let file = git_repo.conflict_file(&params.path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to read conflict: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(file))
 }
 
+/// Resolve a file with the given content, or with a whole side via
+/// `resolution`
 pub async fn resolve_conflict(
     State(config): State<Config>,
     Path(repo): Path<String>,
@@ -120,6 +73,21 @@ pub async fn resolve_conflict(
 ) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
     let repo_path = get_repo_path(&repo, &config.repos_root);
 
+    if let Some(resolution) = req.resolution.as_deref().filter(|r| !r.is_empty()) {
+        let git_repo = open_repo(&config, &repo)?;
+        git_repo
+            .resolve_conflict_side(&req.path, resolution)
+            .map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Failed to resolve conflict: {}", e),
+                    }),
+                )
+            })?;
+        return Ok(Json(SuccessResponse { success: true }));
+    }
+
     if req.path.is_empty() || req.content.is_empty() {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -163,3 +131,101 @@ pub async fn resolve_conflict(
 
     Ok(Json(SuccessResponse { success: true }))
 }
+
+/// Resolve individual conflict regions; the file is staged once none remain
+pub async fn resolve_conflict_regions(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Json(req): Json<ResolveRegionsRequest>,
+) -> Result<Json<ResolveRegionsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo)?;
+
+    let result = git_repo
+        .resolve_conflict_regions(&req.path, &req.resolutions)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Failed to resolve conflict: {}", e),
+                }),
+            )
+        })?;
+    Ok(Json(result))
+}
+
+/// Apply rerere and the configured strategies to the current conflicts
+pub async fn auto_resolve_conflicts(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+) -> Result<Json<AutoResolveResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo)?;
+
+    let result = git_repo.auto_resolve_conflicts().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to auto-resolve conflicts: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(result))
+}
+
+/// Turn rerere on or off for the repository
+pub async fn set_rerere(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<RerereQuery>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo)?;
+
+    git_repo.set_rerere_enabled(params.enabled).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to update rerere setting: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// Conflict strategy rules from the GitPow config
+pub async fn get_conflict_strategies() -> Json<Vec<ConflictStrategyRule>> {
+    Json(autoresolve::conflict_strategies())
+}
+
+/// Replace the conflict strategy rules. Shell commands run on auto-resolve
+/// and this server has no auth, so "command" rules can only be kept as they
+/// are here; adding or changing one is left to the desktop app.
+pub async fn save_conflict_strategies(
+    Json(rules): Json<Vec<ConflictStrategyRule>>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let stored = autoresolve::conflict_strategies();
+    let new_command = rules.iter().find(|rule| {
+        rule.strategy == "command"
+            && !stored.iter().any(|s| {
+                s.strategy == "command" && s.pattern == rule.pattern && s.command == rule.command
+            })
+    });
+    if let Some(rule) = new_command {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: format!(
+                    "The command rule for '{}' can only be set from the desktop app",
+                    rule.pattern
+                ),
+            }),
+        ));
+    }
+    autoresolve::save_conflict_strategies(&rules).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    Ok(Json(SuccessResponse { success: true }))
+}
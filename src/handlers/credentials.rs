@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::git::credentials;
+use crate::models::{
+    CredentialAnswer, CredentialInfo, CredentialPrompt, ErrorResponse, SaveCredentialRequest,
+    SuccessResponse,
+};
+
+#[derive(Deserialize)]
+pub struct DeleteCredentialQuery {
+    host: Option<String>,
+    key_path: Option<String>,
+    username: Option<String>,
+}
+
+/// List saved tokens, passwords and key passphrases (without secrets)
+pub async fn list_credentials() -> Json<Vec<CredentialInfo>> {
+    Json(credentials::list_credentials())
+}
+
+/// Save a token (or password / key passphrase) for use by fetch, pull and push
+pub async fn save_credential(
+    Json(request): Json<SaveCredentialRequest>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    credentials::save_credential(request).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// Forget saved credentials for a host or key
+pub async fn delete_credential(
+    Query(params): Query<DeleteCredentialQuery>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if params.host.is_none() && params.key_path.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "host or key_path query parameter is required".to_string(),
+            }),
+        ));
+    }
+
+    let removed = credentials::remove_credential(
+        params.host.as_deref(),
+        params.key_path.as_deref(),
+        params.username.as_deref(),
+    )
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to update credential store: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(SuccessResponse { success: removed }))
+}
+
+/// Credentials prompts raised by running fetch/pull/push operations.
+/// The UI polls this while a network operation is in flight.
+pub async fn get_credential_prompts() -> Json<Vec<CredentialPrompt>> {
+    Json(credentials::pending_prompts())
+}
+
+/// Answer a credentials prompt; the waiting operation retries with it
+pub async fn answer_credential_prompt(
+    Path(id): Path<String>,
+    Json(answer): Json<CredentialAnswer>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if credentials::answer_prompt(&id, Some(answer)) {
+        Ok(Json(SuccessResponse { success: true }))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Prompt not found (already answered or timed out)".to_string(),
+            }),
+        ))
+    }
+}
+
+/// Decline a credentials prompt; the waiting operation fails with "credentials required"
+pub async fn cancel_credential_prompt(Path(id): Path<String>) -> Json<SuccessResponse> {
+    Json(SuccessResponse {
+        success: credentials::answer_prompt(&id, None),
+    })
+}
@@ -56,6 +56,7 @@ pub async fn get_diff(
                 diff: file_diff.diff,
                 hunks,
                 file_path: file_diff.file_path,
+                submodule: file_diff.submodule,
             });
         }
 
@@ -82,6 +83,7 @@ pub async fn get_diff(
             diff: file_diff.diff,
             hunks,
             file_path: file_diff.file_path,
+            submodule: file_diff.submodule,
         })
     })
     .await
@@ -1,3 +1,4 @@
+use crate::git::credentials::CredentialsRequired;
 use crate::git::repository::GitRepository;
 use crate::config::Config;
 use axum::{
@@ -17,19 +18,31 @@ pub async fn fetch_repo(
         return (StatusCode::NOT_FOUND, "Repository not found").into_response();
     }
 
-    match GitRepository::open(&repo_path) {
-        Ok(repo) => match repo.fetch_all() {
-            Ok(_) => (StatusCode::OK, "Fetch successful").into_response(),
+    // Fetching may wait on a credentials prompt, so keep it off the async runtime
+    let result = tokio::task::spawn_blocking(move || match GitRepository::open(&repo_path) {
+        Ok(repo) => match repo.fetch_all(true) {
+            Ok(_) => (StatusCode::OK, "Fetch successful".to_string()),
+            Err(e) if e.downcast_ref::<CredentialsRequired>().is_some() => {
+                (StatusCode::UNAUTHORIZED, format!("Failed to fetch: {}", e))
+            }
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Failed to fetch: {}", e),
-            )
-                .into_response(),
+            ),
         },
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to open repository: {}", e),
+        ),
+    })
+    .await;
+
+    match result {
+        Ok(response) => response.into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
         )
             .into_response(),
     }
-}
\ No newline at end of file
+}
@@ -13,7 +13,7 @@ use std::time::Duration;
 
 use crate::config::Config;
 use crate::git::repository::GitRepository;
-use crate::models::{ErrorResponse, FileChange, FileCreationInfo, ImageResponse};
+use crate::models::{ErrorResponse, FileChange, FileCreationInfo, ImageResponse, TreeResponse};
 use crate::utils::{get_repo_path, normalize_sha};
 
 #[derive(serde::Deserialize)]
@@ -23,6 +23,16 @@ pub struct FileQuery {
     path: Option<String>,
 }
 
+#[derive(serde::Deserialize)]
+pub struct TreeQuery {
+    #[serde(rename = "ref")]
+    ref_: Option<String>,
+    path: Option<String>,
+    recursive: Option<bool>,
+    pattern: Option<String>,
+    last_commit: Option<bool>,
+}
+
 #[derive(serde::Deserialize)]
 pub struct FileCreationQuery {
     path: Option<String>,
@@ -162,6 +172,72 @@ pub async fn get_files(
     Ok(Json(files))
 }
 
+/// List a directory at any ref with per-entry kind, mode, size and OID.
+/// `last_commit=true` adds the commit that last touched each entry
+/// (single-directory listings only); `recursive=true` lists all descendants,
+/// optionally filtered by a pathspec glob in `pattern`.
+pub async fn get_tree(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<TreeQuery>,
+) -> Result<Json<TreeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let reference = params.ref_.unwrap_or_else(|| "HEAD".to_string());
+    let dir = params.path.unwrap_or_default();
+    let recursive = params.recursive.unwrap_or(false);
+    let last_commit = params.last_commit.unwrap_or(false);
+
+    if recursive && last_commit {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "last_commit is only supported for non-recursive listings".to_string(),
+            }),
+        ));
+    }
+
+    // Move blocking git operations to a thread pool to avoid blocking the async runtime
+    let response = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+        let mut listing = git_repo
+            .list_tree(&reference, &dir, recursive, params.pattern.as_deref())
+            .map_err(|e| format!("Failed to list tree: {}", e))?;
+
+        if last_commit {
+            git_repo
+                .annotate_last_commits(&mut listing, &dir)
+                .map_err(|e| format!("Failed to find last commits: {}", e))?;
+        }
+
+        Ok::<_, String>(TreeResponse {
+            reference,
+            commit: listing.commit.to_string(),
+            path: dir.trim_matches('/').to_string(),
+            tree_oid: listing.tree_oid.to_string(),
+            entries: listing.entries,
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(response))
+}
+
 pub async fn get_commit_files(
     State(config): State<Config>,
     Path(repo): Path<String>,
@@ -1,7 +1,11 @@
 use crate::config::Config;
-use crate::git::repository::GitRepository;
+use crate::git::pull::{pull_summary, PullMode, PullOptions};
+use crate::git::credentials::CredentialsRequired;
+use crate::git::push::PushOptions;
+use crate::git::repository::{GitRepository, StashPushOptions};
 use crate::models::{
-    BranchStatusResponse, ErrorResponse, GitOperationResponse, StashListResponse,
+    BranchStatusResponse, DiffHunk, ErrorResponse, GitOperationResponse, PullResponse,
+    PushPreview, StashFileChange, StashListResponse, StashShowResponse,
 };
 use axum::{
     extract::{Path, Query, State},
@@ -11,9 +15,70 @@ use axum::{
 use serde::Deserialize;
 use std::path::PathBuf;
 
+#[derive(Deserialize)]
+pub struct PullQuery {
+    /// "ff-only", "merge", "rebase" or "rebase-merges"; defaults to the
+    /// repository's pull.rebase / pull.ff configuration.
+    mode: Option<String>,
+    /// Stash local changes first and reapply them afterwards.
+    autostash: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct PushQuery {
+    /// Remote to push to; defaults to the upstream's remote (or the default push remote).
+    remote: Option<String>,
+    /// Local branch to push; defaults to the current branch.
+    branch: Option<String>,
+    /// Branch name (or full ref) on the remote, if different from the upstream/local name.
+    remote_branch: Option<String>,
+    force_with_lease: Option<bool>,
+    /// SHA the remote branch must still be at; defaults to our remote-tracking ref.
+    expected_sha: Option<String>,
+    /// Also push all tags.
+    tags: Option<bool>,
+    no_verify: Option<bool>,
+    /// JSON array of server-side push options
+    push_options: Option<String>,
+}
+
+impl PushQuery {
+    pub(crate) fn into_options(self) -> Result<PushOptions, (StatusCode, Json<ErrorResponse>)> {
+        let push_options = match self.push_options.as_deref().filter(|p| !p.is_empty()) {
+            Some(raw) => serde_json::from_str(raw).map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!(
+                            "invalid push_options parameter (expected JSON array of strings): {}",
+                            e
+                        ),
+                    }),
+                )
+            })?,
+            None => Vec::new(),
+        };
+        Ok(PushOptions {
+            remote: self.remote.filter(|r| !r.is_empty()),
+            branch: self.branch.filter(|b| !b.is_empty()),
+            remote_branch: self.remote_branch.filter(|b| !b.is_empty()),
+            force_with_lease: self.force_with_lease.unwrap_or(false),
+            expected_sha: self.expected_sha.filter(|s| !s.is_empty()),
+            tags: self.tags.unwrap_or(false),
+            no_verify: self.no_verify.unwrap_or(false),
+            push_options,
+        })
+    }
+}
+
 #[derive(Deserialize)]
 pub struct StashPushQuery {
     message: Option<String>,
+    keep_index: Option<bool>,
+    include_untracked: Option<bool>,
+    // JSON-encoded array of pathspecs for a partial stash.
+    // Example: ?paths=["src/a.rs","docs"]
+    paths: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -22,6 +87,20 @@ pub struct StashRefQuery {
     stash_ref: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct StashBranchQuery {
+    #[serde(rename = "ref")]
+    stash_ref: Option<String>,
+    branch: String,
+}
+
+#[derive(Deserialize)]
+pub struct StashRenameQuery {
+    #[serde(rename = "ref")]
+    stash_ref: Option<String>,
+    message: String,
+}
+
 /// Get the current branch status including ahead/behind counts and stash info
 pub async fn get_branch_status(
     State(config): State<Config>,
@@ -56,6 +135,7 @@ pub async fn get_branch_status(
     };
     let has_uncommitted = git_repo.has_uncommitted_changes().unwrap_or(false);
     let stash_count = git_repo.stash_list().map(|l| l.len()).unwrap_or(0);
+    let operation = git_repo.operation_state().unwrap_or(None);
 
     Ok(Json(BranchStatusResponse {
         branch,
@@ -64,14 +144,16 @@ pub async fn get_branch_status(
         behind,
         has_uncommitted,
         stash_count,
+        operation,
     }))
 }
 
-/// Pull changes from remote
+/// Pull changes from remote with the given (or configured) strategy
 pub async fn pull_repo(
     State(config): State<Config>,
     Path(repo_name): Path<String>,
-) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Query(params): Query<PullQuery>,
+) -> Result<Json<PullResponse>, (StatusCode, Json<ErrorResponse>)> {
     let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
 
     if !repo_path.exists() || !repo_path.is_dir() {
@@ -83,6 +165,22 @@ pub async fn pull_repo(
         ));
     }
 
+    let mode = match params.mode.as_deref().filter(|m| !m.is_empty()) {
+        Some(mode) => Some(PullMode::parse(mode).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })?),
+        None => None,
+    };
+    let opts = PullOptions {
+        mode,
+        autostash: params.autostash,
+    };
+
     let git_repo = GitRepository::open(&repo_path).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -94,26 +192,46 @@ pub async fn pull_repo(
 
     // Check if there's an upstream configured
     if !git_repo.has_upstream().unwrap_or(false) {
-        return Ok(Json(GitOperationResponse {
+        return Ok(Json(PullResponse {
             success: false,
             message: Some("No upstream branch configured".to_string()),
-            output: None,
             error: Some("No upstream branch configured. Push first or set upstream manually.".to_string()),
+            result: None,
         }));
     }
 
-    match git_repo.pull() {
-        Ok(output) => Ok(Json(GitOperationResponse {
+    // Pulling may wait on a credentials prompt, so keep it off the async runtime
+    let result = tokio::task::spawn_blocking(move || git_repo.pull(&opts))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Task join error: {}", e),
+                }),
+            )
+        })?;
+
+    match result {
+        // A conflicted pull isn't a success, but the result tells the UI
+        // which files to hand to the conflict resolver.
+        Ok(result) if result.outcome == "conflicted" => Ok(Json(PullResponse {
+            success: false,
+            message: None,
+            error: Some(pull_summary(&result)),
+            result: Some(result),
+        })),
+        Ok(result) => Ok(Json(PullResponse {
             success: true,
-            message: Some("Pull successful".to_string()),
-            output: Some(output),
+            message: Some(pull_summary(&result)),
             error: None,
+            result: Some(result),
         })),
-        Err(e) => Ok(Json(GitOperationResponse {
+        Err(e) => Ok(Json(PullResponse {
             success: false,
             message: None,
-            output: None,
             error: Some(e.to_string()),
+            result: None,
         })),
     }
 }
@@ -122,7 +240,9 @@ pub async fn pull_repo(
 pub async fn push_repo(
     State(config): State<Config>,
     Path(repo_name): Path<String>,
+    Query(params): Query<PushQuery>,
 ) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let opts = params.into_options()?;
     let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
 
     if !repo_path.exists() || !repo_path.is_dir() {
@@ -143,41 +263,33 @@ pub async fn push_repo(
         )
     })?;
 
-    // Check if there's an upstream configured
-    let has_upstream = git_repo.has_upstream().unwrap_or(false);
+    // Pushing may wait on a credentials prompt, so keep it off the async runtime
+    let result = tokio::task::spawn_blocking(move || {
+        git_repo.push_branch(&opts)
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?;
 
-    if has_upstream {
-        match git_repo.push() {
-            Ok(output) => Ok(Json(GitOperationResponse {
-                success: true,
-                message: Some("Push successful".to_string()),
-                output: Some(output),
-                error: None,
-            })),
-            Err(e) => Ok(Json(GitOperationResponse {
-                success: false,
-                message: None,
-                output: None,
-                error: Some(e.to_string()),
-            })),
-        }
-    } else {
-        // Try to push with upstream tracking
-        let branch = git_repo.get_current_branch().unwrap_or_else(|_| "HEAD".to_string());
-        match git_repo.push_set_upstream(&branch) {
-            Ok(output) => Ok(Json(GitOperationResponse {
-                success: true,
-                message: Some(format!("Pushed and set upstream for branch '{}'", branch)),
-                output: Some(output),
-                error: None,
-            })),
-            Err(e) => Ok(Json(GitOperationResponse {
-                success: false,
-                message: None,
-                output: None,
-                error: Some(e.to_string()),
-            })),
-        }
+    match result {
+        Ok((output, message)) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(message),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
     }
 }
 
@@ -211,6 +323,100 @@ pub async fn stash_list(
     Ok(Json(StashListResponse { entries }))
 }
 
+/// Delete a branch on the remote (`remote_branch`), optionally with a lease
+pub async fn delete_remote_branch(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<PushQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let opts = params.into_options()?;
+    let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Repository not found".to_string(),
+            }),
+        ));
+    }
+
+    let result = tokio::task::spawn_blocking(move || {
+        GitRepository::open(&repo_path)?.delete_remote_ref(&opts)
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?;
+
+    match result {
+        Ok(output) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some("Remote branch deleted".to_string()),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
+
+/// Check a push against the remote before doing it: whether it needs
+/// force, whether a lease would hold, and which commits it would discard.
+pub async fn push_preview(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<PushQuery>,
+) -> Result<Json<PushPreview>, (StatusCode, Json<ErrorResponse>)> {
+    let opts = params.into_options()?;
+    let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Repository not found".to_string(),
+            }),
+        ));
+    }
+
+    let result = tokio::task::spawn_blocking(move || {
+        GitRepository::open(&repo_path)?.push_preview(&opts)
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?;
+
+    result.map(Json).map_err(|e| {
+        let status = if e.downcast_ref::<CredentialsRequired>().is_some() {
+            StatusCode::UNAUTHORIZED
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })
+}
+
 /// Push changes to stash
 pub async fn stash_push(
     State(config): State<Config>,
@@ -237,6 +443,18 @@ pub async fn stash_push(
         )
     })?;
 
+    let paths: Vec<String> = match params.paths.as_deref().filter(|p| !p.is_empty()) {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("invalid paths parameter (expected JSON array of strings): {}", e),
+                }),
+            )
+        })?,
+        None => Vec::new(),
+    };
+
     // Check if there are changes to stash
     if !git_repo.has_uncommitted_changes().unwrap_or(false) {
         return Ok(Json(GitOperationResponse {
@@ -247,7 +465,14 @@ pub async fn stash_push(
         }));
     }
 
-    match git_repo.stash_push(params.message.as_deref()) {
+    let opts = StashPushOptions {
+        message: params.message,
+        keep_index: params.keep_index.unwrap_or(false),
+        include_untracked: params.include_untracked.unwrap_or(false),
+        paths,
+    };
+
+    match git_repo.stash_push(&opts) {
         Ok(output) => Ok(Json(GitOperationResponse {
             success: true,
             message: Some("Changes stashed".to_string()),
@@ -402,3 +627,193 @@ pub async fn stash_drop(
         })),
     }
 }
+
+/// Show the files and per-file diffs of a stash, including untracked files
+pub async fn stash_show(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<StashRefQuery>,
+) -> Result<Json<StashShowResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Repository not found".to_string(),
+            }),
+        ));
+    }
+
+    let stash_ref = params.stash_ref.unwrap_or_else(|| "stash@{0}".to_string());
+
+    // Move blocking git operations to a thread pool to avoid blocking the async runtime
+    let result = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to open repository: {}", e),
+            )
+        })?;
+
+        let entry = git_repo
+            .stash_list()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|e| e.index == stash_ref || e.sha == stash_ref)
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Stash {} not found", stash_ref)))?;
+
+        let files = git_repo.stash_show(&entry.sha).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to show stash: {}", e),
+            )
+        })?;
+
+        let files = files
+            .into_iter()
+            .map(|f| StashFileChange {
+                path: f.path,
+                status: f.status,
+                untracked: f.untracked,
+                diff: f.diff,
+                hunks: f
+                    .hunks
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, h)| DiffHunk {
+                        old_start: h.old_start,
+                        old_count: h.old_count,
+                        new_start: h.new_start,
+                        new_count: h.new_count,
+                        lines: h.lines,
+                        line_start: i as i32,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(StashShowResponse { entry, files })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?;
+
+    result
+        .map(Json)
+        .map_err(|(status, error)| (status, Json(ErrorResponse { error })))
+}
+
+/// Create a branch at the stash's base commit and apply the stash onto it
+pub async fn stash_branch(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<StashBranchQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Repository not found".to_string(),
+            }),
+        ));
+    }
+
+    if params.branch.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "branch query parameter is required".to_string(),
+            }),
+        ));
+    }
+
+    let stash_ref = params.stash_ref.unwrap_or_else(|| "stash@{0}".to_string());
+    let branch = params.branch.trim().to_string();
+
+    let git_repo = GitRepository::open(&repo_path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to open repository: {}", e),
+            }),
+        )
+    })?;
+
+    match git_repo.stash_branch(&branch, &stash_ref) {
+        Ok(output) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(format!("Created branch '{}' from {}", branch, stash_ref)),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
+
+/// Change the message of a stash without changing its position
+pub async fn stash_rename(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<StashRenameQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = PathBuf::from(&config.repos_root).join(&repo_name);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Repository not found".to_string(),
+            }),
+        ));
+    }
+
+    let message = params.message.trim().to_string();
+    if message.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "message query parameter is required".to_string(),
+            }),
+        ));
+    }
+
+    let stash_ref = params.stash_ref.unwrap_or_else(|| "stash@{0}".to_string());
+
+    let git_repo = GitRepository::open(&repo_path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to open repository: {}", e),
+            }),
+        )
+    })?;
+
+    match git_repo.stash_rename(&stash_ref, &message) {
+        Ok(()) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(format!("Stash {} renamed", stash_ref)),
+            output: None,
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
@@ -0,0 +1,261 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Json,
+    },
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::config::Config;
+use crate::git::clone::CloneOptions;
+use crate::git::jobs::{self, JobConflict};
+use crate::git::pull::{PullMode, PullOptions};
+use crate::handlers::git_ops::PushQuery;
+use crate::handlers::repos::discovery_scope;
+use crate::handlers::submodules::parse_paths;
+use crate::models::{ErrorResponse, JobInfo, SuccessResponse};
+use crate::utils::repo_path_for;
+
+#[derive(Deserialize)]
+pub struct JobsQuery {
+    /// Only jobs of this repository.
+    repo: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct FetchJobQuery {
+    /// Remote to fetch; all remotes when omitted.
+    remote: Option<String>,
+    prune: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct FetchAllJobQuery {
+    repos_root: Option<String>,
+    max_depth: Option<usize>,
+    prune: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct SubmoduleUpdateJobQuery {
+    // JSON-encoded array of submodule paths; all submodules when omitted.
+    paths: Option<String>,
+    /// Initialize submodules that aren't yet (`--init`).
+    init: Option<bool>,
+    recursive: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct PullJobQuery {
+    /// "ff-only", "merge", "rebase" or "rebase-merges"; defaults to configuration.
+    mode: Option<String>,
+    autostash: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct CloneJobQuery {
+    url: String,
+    /// Folder name under the repos root; derived from the URL when omitted.
+    name: Option<String>,
+    branch: Option<String>,
+    /// Shallow clone depth
+    depth: Option<u32>,
+    /// Partial clone filter, e.g. "blob:none"
+    filter: Option<String>,
+    recurse_submodules: Option<bool>,
+}
+
+fn job_response(
+    result: anyhow::Result<JobInfo>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    result.map(Json).map_err(|e| {
+        let status = if e.downcast_ref::<JobConflict>().is_some() {
+            StatusCode::CONFLICT
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })
+}
+
+/// List running and recently finished jobs, newest first
+pub async fn list_jobs(Query(params): Query<JobsQuery>) -> Json<Vec<JobInfo>> {
+    Json(jobs::list_jobs(params.repo.as_deref()))
+}
+
+pub async fn get_job(
+    Path(id): Path<String>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    jobs::get_job(&id).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Job not found".to_string(),
+            }),
+        )
+    })
+}
+
+/// Cancel a running job; `success` is false if it had already finished
+pub async fn cancel_job(
+    Path(id): Path<String>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if jobs::get_job(&id).is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Job not found".to_string(),
+            }),
+        ));
+    }
+    Ok(Json(SuccessResponse {
+        success: jobs::cancel_job(&id),
+    }))
+}
+
+/// Server-sent events with a `job` event (JobInfo JSON) for every progress
+/// update and state change of any job.
+pub async fn job_events() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = stream::unfold(jobs::subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(info) => {
+                    let event = Event::default()
+                        .event("job")
+                        .json_data(&info)
+                        .unwrap_or_else(|_| Event::default().comment("unserializable job"));
+                    return Some((Ok(event), receiver));
+                }
+                // A slow client missed some progress updates; later ones carry full state.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Start fetching in the background
+pub async fn start_fetch_job(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<FetchJobQuery>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = repo_path_for(&config, &repo_name)?;
+    let remote = params.remote.filter(|r| !r.is_empty());
+    job_response(jobs::start_fetch(
+        &repo_name,
+        repo_path,
+        remote,
+        params.prune.unwrap_or(false),
+    ))
+}
+
+/// Start fetching every repository under the repos root (or `repos_root`)
+/// in the background. The finished job carries a RepoFetchSummary per
+/// repository in `result`.
+pub async fn start_fetch_all_job(
+    State(config): State<Config>,
+    Query(params): Query<FetchAllJobQuery>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let (repos_root, options) =
+        discovery_scope(&config, params.repos_root.as_deref(), params.max_depth);
+    job_response(jobs::start_fetch_all_repos(
+        repos_root,
+        options,
+        params.prune.unwrap_or(false),
+    ))
+}
+
+/// Start pulling the current branch in the background. The finished job
+/// carries a PullResult in `result`.
+pub async fn start_pull_job(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<PullJobQuery>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = repo_path_for(&config, &repo_name)?;
+    let mode = match params.mode.as_deref().filter(|m| !m.is_empty()) {
+        Some(mode) => Some(PullMode::parse(mode).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })?),
+        None => None,
+    };
+    let opts = PullOptions {
+        mode,
+        autostash: params.autostash,
+    };
+    job_response(jobs::start_pull(&repo_name, repo_path, opts))
+}
+
+/// Start a push in the background; takes the same options as a plain push
+pub async fn start_push_job(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<PushQuery>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let opts = params.into_options()?;
+    let repo_path = repo_path_for(&config, &repo_name)?;
+    job_response(jobs::start_push(&repo_name, repo_path, opts))
+}
+
+/// Start updating submodules in the background (`git submodule update`),
+/// cloning missing ones and checking out the recorded commits
+pub async fn start_submodule_update_job(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<SubmoduleUpdateJobQuery>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    let paths = parse_paths(params.paths.as_deref())?;
+    let repo_path = repo_path_for(&config, &repo_name)?;
+    job_response(jobs::start_submodule_update(
+        &repo_name,
+        repo_path,
+        paths,
+        params.init.unwrap_or(false),
+        params.recursive.unwrap_or(false),
+    ))
+}
+
+/// Start cloning a repository into the repos root in the background. The
+/// finished job carries the new Repo in `result`.
+pub async fn start_clone_job(
+    State(config): State<Config>,
+    Query(params): Query<CloneJobQuery>,
+) -> Result<Json<JobInfo>, (StatusCode, Json<ErrorResponse>)> {
+    if params.url.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "url query parameter is required".to_string(),
+            }),
+        ));
+    }
+    let opts = CloneOptions {
+        branch: params.branch.filter(|b| !b.is_empty()),
+        depth: params.depth.filter(|d| *d > 0),
+        filter: params.filter.filter(|f| !f.is_empty()),
+        recurse_submodules: params.recurse_submodules.unwrap_or(false),
+    };
+    job_response(jobs::start_clone(
+        &config.repos_root,
+        &params.url,
+        params.name.as_deref(),
+        opts,
+    ))
+}
@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::models::{ErrorResponse, JournalEntry};
+use crate::utils::open_repo;
+
+#[derive(Deserialize)]
+pub struct UndoQuery {
+    /// Journal entry to undo; the latest one when omitted
+    id: Option<String>,
+}
+
+/// Operations GitPow recorded in this repository, newest first
+pub async fn get_journal(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Vec<JournalEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let entries = git_repo.journal_history().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to read the journal: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(entries))
+}
+
+/// Undo the latest recorded operation, or the one given by id. Returns the
+/// journal entry of the undo itself, which can be undone in turn.
+pub async fn undo(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<UndoQuery>,
+) -> Result<Json<JournalEntry>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let result = match params.id.as_deref() {
+        Some(id) => git_repo.undo(id),
+        None => git_repo.undo_last(),
+    };
+    let entry = result.map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Failed to undo: {:#}", e),
+            }),
+        )
+    })?;
+    Ok(Json(entry))
+}
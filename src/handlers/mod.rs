@@ -1,12 +1,25 @@
 pub mod branches;
 pub mod commits;
 pub mod conflicts;
+pub mod credentials;
 pub mod diff;
 pub mod explorer;
 pub mod browse;
 pub mod files;
 pub mod git_ops;
+pub mod jobs;
 pub mod rebase;
+pub mod remotes;
 pub mod repos;
+pub mod search;
 pub mod staging;
+pub mod tags;
 pub mod fetch;
+pub mod worktrees;
+pub mod submodules;
+pub mod operations;
+pub mod journal;
+pub mod reflog;
+pub mod reset;
+pub mod bisect;
+pub mod stats;
@@ -0,0 +1,91 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+
+use crate::config::Config;
+use crate::git::repository::GitRepository;
+use crate::models::{ErrorResponse, GitOperationResponse, OperationState};
+use crate::utils::open_repo;
+
+/// Report the result of continue/skip/abort, and where the operation stands
+/// afterwards (a rebase may stop again at the next conflict).
+fn operation_response(
+    git_repo: &GitRepository,
+    result: anyhow::Result<String>,
+    verb: &str,
+) -> Json<GitOperationResponse> {
+    match result {
+        Ok(output) => {
+            let message = match git_repo.operation_state().ok().flatten() {
+                Some(op) if op.conflicted_files > 0 => format!(
+                    "{} the {}; it stopped again with {} conflicted file(s)",
+                    verb, op.kind, op.conflicted_files
+                ),
+                Some(op) => format!("{} the {}; it is still in progress", verb, op.kind),
+                None => format!("{} the operation", verb),
+            };
+            Json(GitOperationResponse {
+                success: true,
+                message: Some(message),
+                output: Some(output),
+                error: None,
+            })
+        }
+        Err(e) => Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// The merge, rebase, cherry-pick, revert, bisect or am in progress (null if none)
+pub async fn get_operation(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Option<OperationState>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let state = git_repo.operation_state().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to read repository state: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(state))
+}
+
+/// Continue the operation in progress once its conflicts are resolved
+pub async fn continue_operation(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    let result = git_repo.continue_operation();
+    Ok(operation_response(&git_repo, result, "Continued"))
+}
+
+/// Skip the commit the operation stopped at
+pub async fn skip_operation(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    let result = git_repo.skip_operation();
+    Ok(operation_response(&git_repo, result, "Skipped a step of"))
+}
+
+/// Abort the operation in progress
+pub async fn abort_operation(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    let result = git_repo.abort_operation();
+    Ok(operation_response(&git_repo, result, "Aborted"))
+}
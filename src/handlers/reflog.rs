@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::models::{ErrorResponse, GitOperationResponse, LostCommit, ReflogEntry};
+use crate::utils::open_repo;
+
+#[derive(Deserialize)]
+pub struct ReflogQuery {
+    /// Branch name or full ref; HEAD when omitted
+    branch: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct RestoreQuery {
+    branch: Option<String>,
+    index: usize,
+}
+
+#[derive(Deserialize)]
+pub struct RecoverQuery {
+    sha: String,
+    /// Create this branch at the commit; without it a dropped stash is re-stashed
+    branch: Option<String>,
+}
+
+fn operation_response(
+    result: anyhow::Result<String>,
+    message: String,
+) -> Json<GitOperationResponse> {
+    match result {
+        Ok(output) => Json(GitOperationResponse {
+            success: true,
+            message: Some(message),
+            output: Some(output),
+            error: None,
+        }),
+        Err(e) => Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Reflog of HEAD or a branch, newest first
+pub async fn get_reflog(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<ReflogQuery>,
+) -> Result<Json<Vec<ReflogEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let entries = git_repo
+        .reflog(params.branch.as_deref(), params.limit)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Failed to read reflog: {}", e),
+                }),
+            )
+        })?;
+    Ok(Json(entries))
+}
+
+/// Move HEAD or a branch back to a reflog entry
+pub async fn restore_reflog_entry(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<RestoreQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    let name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+    let result = git_repo.restore_reflog_entry(Some(&name), params.index);
+    Ok(operation_response(
+        result,
+        format!("Restored {} to {}@{{{}}}", name, name, params.index),
+    ))
+}
+
+/// Commits no branch, tag or stash reaches, e.g. after a reset or a dropped stash
+pub async fn get_lost_commits(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Vec<LostCommit>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let commits = git_repo.lost_commits().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to find lost commits: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(commits))
+}
+
+/// Make a lost commit reachable again as a branch or stash entry
+pub async fn recover_commit(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<RecoverQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+    let result = git_repo.recover_commit(&params.sha, params.branch.as_deref());
+    let message = match params.branch.as_deref() {
+        Some(branch) => format!("Recovered {} as branch '{}'", params.sha, branch),
+        None => format!("Recovered {} as a stash", params.sha),
+    };
+    Ok(operation_response(result, message))
+}
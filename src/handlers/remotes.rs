@@ -0,0 +1,306 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::git::credentials::CredentialsRequired;
+use crate::git::repository::GitRepository;
+use crate::models::{
+    ErrorResponse, GitOperationResponse, RemoteFetchResult, RemoteInfo, StaleRemoteBranch,
+};
+use crate::utils::{open_repo, repo_path_for};
+
+#[derive(Deserialize)]
+pub struct AddRemoteQuery {
+    name: String,
+    url: String,
+    /// Custom fetch refspec instead of `+refs/heads/*:refs/remotes/<name>/*`.
+    fetch: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RemoteNameQuery {
+    name: String,
+}
+
+#[derive(Deserialize)]
+pub struct RenameRemoteQuery {
+    name: String,
+    new_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct SetRemoteUrlQuery {
+    name: String,
+    url: String,
+    /// Set the push URL instead of the fetch URL; an empty URL clears it.
+    push: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct SetRefspecsQuery {
+    name: String,
+    // JSON-encoded arrays of refspecs; push is left unchanged when omitted.
+    // Example: ?fetch=["+refs/heads/main:refs/remotes/origin/main"]
+    fetch: String,
+    push: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct FetchRemoteQuery {
+    name: String,
+    prune: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct StaleBranchesQuery {
+    /// Only check this remote; all remotes when omitted.
+    name: Option<String>,
+}
+
+fn operation_response(result: anyhow::Result<()>, message: String) -> Json<GitOperationResponse> {
+    match result {
+        Ok(()) => Json(GitOperationResponse {
+            success: true,
+            message: Some(message),
+            output: None,
+            error: None,
+        }),
+        Err(e) => Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+fn parse_refspecs(raw: &str, name: &str) -> Result<Vec<String>, (StatusCode, Json<ErrorResponse>)> {
+    serde_json::from_str(raw).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "invalid {} parameter (expected JSON array of strings): {}",
+                    name, e
+                ),
+            }),
+        )
+    })
+}
+
+/// List remotes with their URLs and refspecs
+pub async fn list_remotes(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Vec<RemoteInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let remotes = git_repo.list_remotes().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to list remotes: {}", e),
+            }),
+        )
+    })?;
+
+    Ok(Json(remotes))
+}
+
+/// Add a new remote
+pub async fn add_remote(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<AddRemoteQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let fetch = params.fetch.filter(|f| !f.is_empty());
+    let result = git_repo.add_remote(params.name.trim(), params.url.trim(), fetch.as_deref());
+    Ok(operation_response(
+        result,
+        format!("Added remote '{}'", params.name.trim()),
+    ))
+}
+
+/// Remove a remote and its remote-tracking branches
+pub async fn remove_remote(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<RemoteNameQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let result = git_repo.remove_remote(&params.name);
+    Ok(operation_response(
+        result,
+        format!("Removed remote '{}'", params.name),
+    ))
+}
+
+/// Rename a remote
+pub async fn rename_remote(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<RenameRemoteQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let new_name = params.new_name.trim();
+    match git_repo.rename_remote(&params.name, new_name) {
+        Ok(problems) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(format!(
+                "Renamed remote '{}' to '{}'",
+                params.name, new_name
+            )),
+            // Custom refspecs aren't rewritten by git; surface them so the user can fix them
+            output: (!problems.is_empty()).then(|| {
+                format!(
+                    "These refspecs were not updated and may need editing:\n{}",
+                    problems.join("\n")
+                )
+            }),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
+
+/// Change a remote's fetch or push URL
+pub async fn set_remote_url(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<SetRemoteUrlQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let push = params.push.unwrap_or(false);
+    let result = git_repo.set_remote_url(&params.name, params.url.trim(), push);
+    let kind = if push { "push URL" } else { "URL" };
+    Ok(operation_response(
+        result,
+        format!("Updated {} of remote '{}'", kind, params.name),
+    ))
+}
+
+/// Replace a remote's fetch (and optionally push) refspecs
+pub async fn set_remote_refspecs(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<SetRefspecsQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let fetch = parse_refspecs(&params.fetch, "fetch")?;
+    let push = match params.push.as_deref().filter(|s| !s.is_empty()) {
+        Some(raw) => Some(parse_refspecs(raw, "push")?),
+        None => None,
+    };
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let result = git_repo.set_remote_refspecs(&params.name, &fetch, push.as_deref());
+    Ok(operation_response(
+        result,
+        format!("Updated refspecs of remote '{}'", params.name),
+    ))
+}
+
+/// Fetch a single remote, optionally pruning deleted branches
+pub async fn fetch_remote(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<FetchRemoteQuery>,
+) -> Result<Json<RemoteFetchResult>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = repo_path_for(&config, &repo_name)?;
+    let prune = params.prune.unwrap_or(false);
+
+    // Network I/O; keep it off the async runtime
+    let result = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to open repository: {}", e),
+            )
+        })?;
+        git_repo.fetch_remote(&params.name, prune).map_err(|e| {
+            // Let the client tell a missing credential apart from a real failure
+            let status = if e.downcast_ref::<CredentialsRequired>().is_some() {
+                StatusCode::UNAUTHORIZED
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, format!("Failed to fetch '{}': {}", params.name, e))
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|(status, e)| (status, Json(ErrorResponse { error: e })))?;
+
+    Ok(Json(result))
+}
+
+/// Report remote-tracking branches whose branch was deleted on the remote
+pub async fn get_stale_branches(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<StaleBranchesQuery>,
+) -> Result<Json<Vec<StaleRemoteBranch>>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = repo_path_for(&config, &repo_name)?;
+
+    let stale = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+        let names = match params.name.filter(|n| !n.is_empty()) {
+            Some(name) => vec![name],
+            None => git_repo
+                .list_remotes()
+                .map_err(|e| format!("Failed to list remotes: {}", e))?
+                .into_iter()
+                .map(|r| r.name)
+                .collect(),
+        };
+
+        let mut stale = Vec::new();
+        for name in names {
+            stale.extend(
+                git_repo
+                    .stale_remote_branches(&name)
+                    .map_err(|e| format!("Failed to check remote '{}': {:#}", name, e))?,
+            );
+        }
+        Ok::<_, String>(stale)
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(stale))
+}
@@ -1,109 +1,141 @@
 use axum::{extract::{Query, State}, http::StatusCode, response::Json};
 use serde::Deserialize;
-use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::config::Config;
-use crate::models::{ConfigResponse, ErrorResponse, Repo};
+use crate::git::clone::{new_repo_dir, InitOptions};
+use crate::git::dashboard;
+use crate::git::discovery::{self, DiscoveryOptions};
+use crate::git::repository::GitRepository;
+use crate::models::{ConfigResponse, ErrorResponse, Repo, RepoSummary};
 
 #[derive(Deserialize)]
 pub struct ReposQuery {
     repos_root: Option<String>,
+    /// Folder levels to search below the root; defaults to REPO_SCAN_DEPTH (3).
+    max_depth: Option<usize>,
 }
 
+#[derive(Deserialize)]
+pub struct InitRepoQuery {
+    /// Folder name under the repos root.
+    name: String,
+    default_branch: Option<String>,
+    initial_commit: Option<bool>,
+    /// .gitignore template name, e.g. "rust" or "node".
+    gitignore: Option<String>,
+    readme: Option<bool>,
+}
+
+/// Repositories under the repos root (or `repos_root`), from the cached
+/// discovery scan
 pub async fn get_repos(
     State(config): State<Config>,
     Query(query): Query<ReposQuery>,
 ) -> Result<Json<Vec<Repo>>, (StatusCode, Json<ErrorResponse>)> {
-    // Use custom repos_root from query if provided, otherwise use config default
-    let repos_root = if let Some(custom_root) = &query.repos_root {
-        let path = PathBuf::from(custom_root);
-        path.canonicalize().unwrap_or_else(|_| path)
-    } else {
-        config.repos_root.clone()
-    };
+    discover(&config, query, discovery::list_repos).await
+}
 
-    // Helper to create a user-facing ID string from a path.
-    // On Windows, strip any extended-length prefix (\\?\C:\...) for readability.
-    fn make_repo_id(path: &Path) -> String {
-        let raw = path.to_string_lossy().to_string();
-        if cfg!(windows) && raw.starts_with(r"\\?\") {
-            raw[4..].to_string()
-        } else {
-            raw
-        }
-    }
+/// Walk the repos root again, e.g. after repositories were added outside GitPow
+pub async fn rescan_repos(
+    State(config): State<Config>,
+    Query(query): Query<ReposQuery>,
+) -> Result<Json<Vec<Repo>>, (StatusCode, Json<ErrorResponse>)> {
+    discover(&config, query, discovery::rescan).await
+}
+
+/// Summary of every repository under the repos root (or `repos_root`):
+/// branch, ahead/behind, change counts, stashes, in-progress operation and
+/// last fetch. Repositories are read in parallel, a few at a time.
+pub async fn get_dashboard(
+    State(config): State<Config>,
+    Query(query): Query<ReposQuery>,
+) -> Result<Json<Vec<RepoSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    discover(&config, query, dashboard::summarize_all).await
+}
 
-    let mut repos = Vec::new();
+/// Where to look for repositories: `repos_root` or the configured root, and
+/// `max_depth` or REPO_SCAN_DEPTH.
+pub(crate) fn discovery_scope(
+    config: &Config,
+    repos_root: Option<&str>,
+    max_depth: Option<usize>,
+) -> (PathBuf, DiscoveryOptions) {
+    let repos_root = match repos_root {
+        Some(custom_root) => {
+            let path = PathBuf::from(custom_root);
+            path.canonicalize().unwrap_or(path)
+        }
+        None => config.repos_root.clone(),
+    };
+    let options = DiscoveryOptions::with_depth(max_depth.unwrap_or(config.repo_scan_depth));
+    (repos_root, options)
+}
 
-    // Case 1: the selected folder itself is a git repository.
-    let self_git_dir = repos_root.join(".git");
-    let self_is_repo = self_git_dir.is_dir();
-    if self_is_repo {
-        let name = repos_root
-            .file_name()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| repos_root.to_string_lossy().to_string());
-        repos.push(Repo {
-            id: make_repo_id(&repos_root),
-            name,
-        });
-    }
+async fn discover<T: Send + 'static>(
+    config: &Config,
+    query: ReposQuery,
+    find: fn(&Path, &DiscoveryOptions) -> anyhow::Result<Vec<T>>,
+) -> Result<Json<Vec<T>>, (StatusCode, Json<ErrorResponse>)> {
+    let (repos_root, options) = discovery_scope(config, query.repos_root.as_deref(), query.max_depth);
 
-    // Case 2: the folder may contain multiple git repositories as children.
-    // If we can't read the directory at all and it's not itself a repo, surface an error.
-    let entries_result = fs::read_dir(&repos_root);
-    let entries = match entries_result {
-        Ok(entries) => entries,
-        Err(e) => {
-            if self_is_repo {
-                // Folder itself is a repo; treat missing read_dir as "no additional repos".
-                return Ok(Json(repos));
-            }
-            return Err((
+    tokio::task::spawn_blocking(move || find(&repos_root, &options))
+        .await
+        .map_err(|e| {
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: format!("Failed to read repos directory: {}", e),
+                    error: format!("Task join error: {}", e),
                 }),
-            ));
-        }
-    };
-
-    for entry in entries {
-        let entry = entry.map_err(|e| {
+            )
+        })?
+        .map(Json)
+        .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: format!("Failed to read directory entry: {}", e),
+                    error: e.to_string(),
                 }),
             )
-        })?;
+        })
+}
 
-        if entry
-            .file_type()
-            .map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to get file type: {}", e),
-                    }),
-                )
-            })?
-            .is_dir()
-        {
-            let full_path = repos_root.join(entry.file_name());
-            let git_dir = full_path.join(".git");
-            if git_dir.exists() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                repos.push(Repo {
-                    id: make_repo_id(&full_path),
-                    name,
-                });
-            }
-        }
-    }
+/// Create a new repository under the repos root
+pub async fn init_repo(
+    State(config): State<Config>,
+    Query(params): Query<InitRepoQuery>,
+) -> Result<Json<Repo>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |e: anyhow::Error| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    };
+    let dest = new_repo_dir(&config.repos_root, &params.name).map_err(bad_request)?;
+    let opts = InitOptions {
+        default_branch: params.default_branch,
+        initial_commit: params.initial_commit.unwrap_or(false),
+        gitignore: params.gitignore,
+        readme: params.readme.unwrap_or(false),
+    };
+    // The initial commit runs git add and git commit
+    let repo = tokio::task::spawn_blocking(move || {
+        GitRepository::init_at(&dest, &opts).map(|_| discovery::register(&dest))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(bad_request)?;
 
-    Ok(Json(repos))
+    Ok(Json(repo))
 }
 
 pub async fn get_config(State(config): State<Config>) -> Json<ConfigResponse> {
@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::git::reset::ResetMode;
+use crate::models::{ErrorResponse, GitOperationResponse, ResetPreview};
+use crate::utils::open_repo;
+
+#[derive(Deserialize)]
+pub struct ResetQuery {
+    /// Commit, branch or tag to move to
+    target: String,
+    /// soft, mixed (default), hard or keep
+    mode: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RestoreFileQuery {
+    commit: String,
+    path: String,
+}
+
+fn parse_mode(mode: Option<&str>) -> Result<ResetMode, (StatusCode, Json<ErrorResponse>)> {
+    ResetMode::parse(mode.unwrap_or("mixed")).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })
+}
+
+/// Commits a reset would leave unreachable and local changes it would discard
+pub async fn reset_preview(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<ResetQuery>,
+) -> Result<Json<ResetPreview>, (StatusCode, Json<ErrorResponse>)> {
+    let mode = parse_mode(params.mode.as_deref())?;
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let preview = git_repo.reset_preview(&params.target, mode).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Failed to preview reset: {:#}", e),
+            }),
+        )
+    })?;
+    Ok(Json(preview))
+}
+
+/// Reset the current branch to a commit
+pub async fn reset(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<ResetQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let mode = parse_mode(params.mode.as_deref())?;
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    match git_repo.reset(&params.target, mode) {
+        Ok(output) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(format!("Reset ({}) to {}", mode.as_str(), params.target)),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(format!("{:#}", e)),
+        })),
+    }
+}
+
+/// Restore one file from a commit into the index and working tree
+pub async fn restore_file(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<RestoreFileQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    match git_repo.restore_file(&params.commit, &params.path) {
+        Ok(output) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(format!("Restored {} from {}", params.path, params.commit)),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
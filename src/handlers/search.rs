@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::git::repository::GitRepository;
+use crate::git::search::{supersede_search, GrepOptions, InvalidSearch};
+use crate::models::{ErrorResponse, SearchResponse};
+use crate::utils::get_repo_path;
+
+/// Default and hard upper bound for the number of matching lines returned.
+const DEFAULT_MAX_RESULTS: usize = 1_000;
+const MAX_RESULTS_LIMIT: usize = 10_000;
+const MAX_CONTEXT_LINES: usize = 10;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: String,
+    /// Commit-ish to search; omitted (or "WORKTREE") searches the working tree.
+    #[serde(rename = "ref")]
+    ref_: Option<String>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    whole_word: Option<bool>,
+    // JSON-encoded arrays of pathspec globs.
+    // Example: ?include=["src/**"]&exclude=["*.lock"]
+    include: Option<String>,
+    exclude: Option<String>,
+    max_results: Option<usize>,
+    context: Option<usize>,
+}
+
+fn parse_globs(
+    raw: Option<&str>,
+    name: &str,
+) -> Result<Vec<String>, (StatusCode, Json<ErrorResponse>)> {
+    match raw.filter(|s| !s.is_empty()) {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!(
+                        "invalid {} parameter (expected JSON array of strings): {}",
+                        name, e
+                    ),
+                }),
+            )
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Full-text search across a ref or the worktree using `git grep`.
+/// A newer search for the same repository cancels this one, in which case
+/// the response comes back with `cancelled: true` and partial results.
+pub async fn search_code(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if params.q.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "q query parameter is required".to_string(),
+            }),
+        ));
+    }
+
+    let include = parse_globs(params.include.as_deref(), "include")?;
+    let exclude = parse_globs(params.exclude.as_deref(), "exclude")?;
+
+    let reference = params
+        .ref_
+        .filter(|r| !r.is_empty() && !r.eq_ignore_ascii_case("WORKTREE"));
+    let opts = GrepOptions {
+        query: params.q,
+        reference,
+        regex: params.regex.unwrap_or(false),
+        case_sensitive: params.case_sensitive.unwrap_or(false),
+        whole_word: params.whole_word.unwrap_or(false),
+        include,
+        exclude,
+        max_results: params
+            .max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT),
+        context_lines: params.context.unwrap_or(0).min(MAX_CONTEXT_LINES),
+    };
+
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let cancel = supersede_search(&repo_path.to_string_lossy());
+
+    // Move blocking git operations to a thread pool to avoid blocking the async runtime
+    let response = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to open repository: {}", e),
+            )
+        })?;
+
+        let outcome = git_repo.grep(&opts, &cancel).map_err(|e| {
+            // A bad ref or pattern is the caller's mistake, not a server failure
+            let status = if e.downcast_ref::<InvalidSearch>().is_some() {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, format!("Search failed: {}", e))
+        })?;
+
+        Ok(SearchResponse {
+            query: opts.query,
+            reference: opts.reference,
+            files: outcome.files,
+            total_matches: outcome.total_matches,
+            truncated: outcome.truncated,
+            cancelled: outcome.cancelled,
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|(status, e)| (status, Json(ErrorResponse { error: e })))?;
+
+    Ok(Json(response))
+}
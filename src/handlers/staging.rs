@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
 };
@@ -7,10 +7,19 @@ use serde::Deserialize;
 use std::fs;
 
 use crate::config::Config;
-use crate::git::repository::run_git;
-use crate::models::{ErrorResponse, StatusFile, StatusResponse, SuccessResponse};
+use crate::git::repository::{run_git, GitRepository};
+use crate::git::status::StatusOptions;
+use crate::models::{ErrorResponse, StatusResponse, SuccessResponse};
 use crate::utils::get_repo_path;
 
+#[derive(Deserialize)]
+pub struct StatusQuery {
+    /// Also list ignored files.
+    ignored: Option<bool>,
+    /// normal, all or no
+    untracked: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct StageRequest {
     path: String,
@@ -27,83 +36,31 @@ pub struct CommitRequest {
     message: String,
 }
 
+/// Working tree status with per-side states, rename similarity, conflict
+/// stages and the branch/upstream headers
 pub async fn get_status(
     State(config): State<Config>,
     Path(repo): Path<String>,
+    Query(params): Query<StatusQuery>,
 ) -> Result<Json<StatusResponse>, (StatusCode, Json<ErrorResponse>)> {
     let repo_path = get_repo_path(&repo, &config.repos_root);
 
-    let status_out = run_git(&["status", "--porcelain"], &repo_path).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to get status: {}", e),
-            }),
-        )
-    })?;
-
-    let lines: Vec<&str> = status_out.split('\n').collect();
-    let mut files = Vec::new();
-
-    for line in lines {
-        // Don't trim leading spaces - they're significant in git status --porcelain format
-        // Format: "XY filename" where X=staged status, Y=unstaged status, then space, then filename
-        let line = line.trim_end(); // Only trim trailing whitespace
-        if line.is_empty() || line.len() < 4 {
-            continue;
-        }
-
-        let staged = line.chars().next().unwrap() != ' ' && line.chars().next().unwrap() != '?';
-        let unstaged = line.chars().nth(1).unwrap() != ' ' && line.chars().nth(1).unwrap() != '?';
-        let status = &line[..2];
-        // Git status format is always: 2 status chars, then space, then filename
-        // Find the space after the 2-char status and get everything after it
-        let file_path = if line.len() >= 3 && line.chars().nth(2) == Some(' ') {
-            // Standard format: "XY filename" - filename starts at index 3
-            &line[3..]
-        } else if line.len() > 2 {
-            // Fallback: skip first 3 chars (should be "XY " but handle edge cases)
-            &line[3..]
-        } else {
-            continue;
-        };
-
-        if file_path.contains(" -> ") {
-            // Renamed file
-            let parts: Vec<&str> = file_path.split(" -> ").collect();
-            if parts.len() == 2 {
-                files.push(StatusFile {
-                    path: parts[1].to_string(),
-                    old_path: Some(parts[0].to_string()),
-                    status: status.to_string(),
-                    staged,
-                    unstaged,
-                    r#type: "renamed".to_string(),
-                });
-            }
-        } else {
-            let file_type = if status.contains('A') {
-                "added"
-            } else if status.contains('D') {
-                "deleted"
-            } else if status.contains('?') {
-                "untracked"
-            } else {
-                "modified"
-            };
-
-            files.push(StatusFile {
-                path: file_path.to_string(),
-                old_path: None,
-                status: status.to_string(),
-                staged,
-                unstaged,
-                r#type: file_type.to_string(),
-            });
-        }
-    }
+    let opts = StatusOptions {
+        ignored: params.ignored.unwrap_or(false),
+        untracked: params.untracked,
+    };
+    let status = GitRepository::open(&repo_path)
+        .and_then(|repo| repo.status(&opts))
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to get status: {}", e),
+                }),
+            )
+        })?;
 
-    Ok(Json(StatusResponse { files }))
+    Ok(Json(status))
 }
 
 pub async fn stage(
@@ -216,14 +173,18 @@ pub async fn commit(
         ));
     }
 
-    run_git(&["commit", "-m", message], &repo_path).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to create commit: {}", e),
-            }),
-        )
-    })?;
+    GitRepository::open(&repo_path)
+        .and_then(|git_repo| {
+            git_repo.journaled("Commit", || git_repo.run_git(&["commit", "-m", message]))
+        })
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to create commit: {}", e),
+                }),
+            )
+        })?;
 
     Ok(Json(SuccessResponse { success: true }))
 }
@@ -0,0 +1,199 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::git::repository::GitRepository;
+use crate::git::stats::{parse_date_bound, ChurnOptions, TimeBucket};
+use crate::models::{ActivityResponse, ChurnResponse, ContributorStatsResponse, ErrorResponse};
+use crate::utils::get_repo_path;
+
+/// Reject malformed `since`/`until` up front so they surface as 400, not as a
+/// failed analysis.
+fn check_date_bounds(
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    for (value, end_of_day) in [(since, false), (until, true)] {
+        if let Some(value) = value {
+            parse_date_bound(value, end_of_day).map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: e.to_string(),
+                    }),
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct ContributorStatsQuery {
+    branch: Option<String>,
+    /// YYYY-MM-DD or RFC3339
+    since: Option<String>,
+    until: Option<String>,
+}
+
+/// Per-author commits, line counts, files and weekly activity
+pub async fn get_contributor_stats(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<ContributorStatsQuery>,
+) -> Result<Json<ContributorStatsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let branch_name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+    check_date_bounds(params.since.as_deref(), params.until.as_deref())?;
+
+    let stats = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .contributor_stats(
+                &branch_name,
+                params.since.as_deref(),
+                params.until.as_deref(),
+            )
+            .map_err(|e| format!("Failed to compute contributor stats: {}", e))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(stats))
+}
+
+#[derive(Deserialize)]
+pub struct ChurnQuery {
+    branch: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<usize>,
+    /// Skip commits touching more files than this when pairing files
+    max_coupling_files: Option<usize>,
+    min_shared_commits: Option<usize>,
+}
+
+/// Files and directories ranked by churn and size, with change coupling
+pub async fn get_churn_stats(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<ChurnQuery>,
+) -> Result<Json<ChurnResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let branch_name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+    check_date_bounds(params.since.as_deref(), params.until.as_deref())?;
+    let opts = ChurnOptions {
+        since: params.since,
+        until: params.until,
+        limit: params.limit.unwrap_or(50).clamp(1, 1_000),
+        max_coupling_files: params.max_coupling_files.unwrap_or(50),
+        min_shared_commits: params.min_shared_commits.unwrap_or(3).max(1),
+    };
+
+    let stats = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .churn_stats(&branch_name, &opts)
+            .map_err(|e| format!("Failed to compute churn: {}", e))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(stats))
+}
+
+#[derive(Deserialize)]
+pub struct ActivityQuery {
+    branch: Option<String>,
+    /// day, week (default), month or year
+    bucket: Option<String>,
+    author: Option<String>,
+    path: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+}
+
+/// Commit counts per period and a weekday x hour punchcard over full history
+pub async fn get_commit_activity(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<ActivityQuery>,
+) -> Result<Json<ActivityResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let branch_name = params.branch.unwrap_or_else(|| "HEAD".to_string());
+    check_date_bounds(params.since.as_deref(), params.until.as_deref())?;
+    let bucket = TimeBucket::parse(params.bucket.as_deref().unwrap_or("week")).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    let author = params.author.filter(|a| !a.is_empty());
+    let path = params.path.filter(|p| !p.is_empty());
+
+    let activity = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .commit_activity(
+                &branch_name,
+                bucket,
+                author.as_deref(),
+                path.as_deref(),
+                params.since.as_deref(),
+                params.until.as_deref(),
+            )
+            .map_err(|e| format!("Failed to compute commit activity: {}", e))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(activity))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::git::discovery;
+use crate::models::{ErrorResponse, GitOperationResponse, Repo, SubmoduleInfo};
+use crate::utils::open_repo;
+
+#[derive(Deserialize)]
+pub struct SubmodulesQuery {
+    // JSON-encoded array of submodule paths; all submodules when omitted.
+    paths: Option<String>,
+    recursive: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct SubmodulePathQuery {
+    path: String,
+}
+
+/// Parse the `paths` parameter shared by the submodule endpoints.
+pub(crate) fn parse_paths(
+    raw: Option<&str>,
+) -> Result<Vec<String>, (StatusCode, Json<ErrorResponse>)> {
+    match raw.filter(|p| !p.is_empty()) {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!(
+                        "invalid paths parameter (expected JSON array of strings): {}",
+                        e
+                    ),
+                }),
+            )
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn paths_response(result: anyhow::Result<Vec<String>>, verb: &str) -> Json<GitOperationResponse> {
+    match result {
+        Ok(paths) => Json(GitOperationResponse {
+            success: true,
+            message: Some(format!("{} {} submodule(s)", verb, paths.len())),
+            output: (!paths.is_empty()).then(|| paths.join("\n")),
+            error: None,
+        }),
+        Err(e) => Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(format!("{:#}", e)),
+        }),
+    }
+}
+
+/// Submodules with configured and checked-out commits and dirty state
+pub async fn list_submodules(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Vec<SubmoduleInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let submodules = git_repo.list_submodules().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to list submodules: {}", e),
+            }),
+        )
+    })?;
+    Ok(Json(submodules))
+}
+
+/// `git submodule init`: register submodules from .gitmodules in .git/config
+pub async fn init_submodules(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<SubmodulesQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let paths = parse_paths(params.paths.as_deref())?;
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    Ok(paths_response(
+        git_repo.init_submodules(&paths),
+        "Initialized",
+    ))
+}
+
+/// `git submodule sync`: apply URL changes from .gitmodules
+pub async fn sync_submodules(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<SubmodulesQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let paths = parse_paths(params.paths.as_deref())?;
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let result = git_repo.sync_submodules(&paths, params.recursive.unwrap_or(false));
+    Ok(paths_response(result, "Synced"))
+}
+
+/// A checked-out submodule as a Repo, to open it like any other repository
+pub async fn open_submodule(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<SubmodulePathQuery>,
+) -> Result<Json<Repo>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let workdir = git_repo.submodule_workdir(&params.path).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    Ok(Json(discovery::register(&workdir)))
+}
@@ -0,0 +1,223 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::git::repository::GitRepository;
+use crate::models::{ErrorResponse, GitOperationResponse, Tag};
+use crate::utils::{get_repo_path, open_repo};
+
+#[derive(Deserialize)]
+pub struct TagsQuery {
+    /// Run `git verify-tag` on signed tags instead of only detecting the signature.
+    verify: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateTagQuery {
+    name: String,
+    /// Commit-ish to tag; defaults to HEAD.
+    target: Option<String>,
+    /// Creates an annotated tag when present.
+    message: Option<String>,
+    sign: Option<bool>,
+    force: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteTagQuery {
+    name: String,
+    /// Also delete the tag from this remote.
+    remote: Option<String>,
+    /// Delete the local tag (default true); false only removes it from `remote`.
+    local: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct PushTagsQuery {
+    remote: Option<String>,
+    // JSON-encoded array of tag names; omitted or empty pushes all tags.
+    // Example: ?names=["v1.0.0","v1.0.1"]
+    names: Option<String>,
+}
+
+fn operation_response(
+    result: anyhow::Result<String>,
+    message: String,
+) -> Json<GitOperationResponse> {
+    match result {
+        Ok(output) => Json(GitOperationResponse {
+            success: true,
+            message: Some(message),
+            output: Some(output),
+            error: None,
+        }),
+        Err(e) => Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// List tags, version tags first in descending semver order.
+pub async fn get_tags(
+    State(config): State<Config>,
+    Path(repo): Path<String>,
+    Query(params): Query<TagsQuery>,
+) -> Result<Json<Vec<Tag>>, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = get_repo_path(&repo, &config.repos_root);
+    let verify = params.verify.unwrap_or(false);
+
+    // Verifying signatures spawns gpg per signed tag, so keep it off the runtime
+    let tags = tokio::task::spawn_blocking(move || {
+        let git_repo = GitRepository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        git_repo
+            .list_tags(verify)
+            .map_err(|e| format!("Failed to list tags: {}", e))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(tags))
+}
+
+/// Create a lightweight, annotated or signed tag at any commit
+pub async fn create_tag(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<CreateTagQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let name = params.name.trim().to_string();
+    let target = params
+        .target
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let message = params.message.filter(|m| !m.trim().is_empty());
+
+    let result = git_repo.create_tag(
+        &name,
+        &target,
+        message.as_deref(),
+        params.sign.unwrap_or(false),
+        params.force.unwrap_or(false),
+    );
+    Ok(operation_response(
+        result,
+        format!("Created tag '{}' at {}", name, target),
+    ))
+}
+
+/// Delete a tag locally and/or on a remote
+pub async fn delete_tag(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<DeleteTagQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let local = params.local.unwrap_or(true);
+    let remote = params.remote.filter(|r| !r.is_empty());
+    if !local && remote.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "remote is required when local=false".to_string(),
+            }),
+        ));
+    }
+
+    let name = params.name.clone();
+    let remote_name = remote.clone();
+    // Deleting on a remote may wait on a credentials prompt
+    let result = tokio::task::spawn_blocking(move || {
+        git_repo.delete_tag(&name, local, remote_name.as_deref())
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Task join error: {}", e),
+            }),
+        )
+    })?;
+
+    let message = match (&remote, local) {
+        (Some(remote), true) => format!(
+            "Deleted tag '{}' locally and from '{}'",
+            params.name, remote
+        ),
+        (Some(remote), false) => format!("Deleted tag '{}' from '{}'", params.name, remote),
+        (None, _) => format!("Deleted tag '{}'", params.name),
+    };
+    Ok(operation_response(result, message))
+}
+
+/// Push selected tags (or all tags) to a remote
+pub async fn push_tags(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<PushTagsQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let names: Vec<String> = match params.names.as_deref().filter(|s| !s.is_empty()) {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!(
+                        "invalid names parameter (expected JSON array of strings): {}",
+                        e
+                    ),
+                }),
+            )
+        })?,
+        None => Vec::new(),
+    };
+    let remote = params
+        .remote
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "origin".to_string());
+
+    let (remote_name, tag_names) = (remote.clone(), names.clone());
+    // Pushing may wait on a credentials prompt
+    let result = tokio::task::spawn_blocking(move || git_repo.push_tags(&remote_name, &tag_names))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Task join error: {}", e),
+                }),
+            )
+        })?;
+
+    let message = if names.is_empty() {
+        format!("Pushed all tags to '{}'", remote)
+    } else {
+        format!("Pushed {} tag(s) to '{}'", names.len(), remote)
+    };
+    Ok(operation_response(result, message))
+}
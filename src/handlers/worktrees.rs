@@ -0,0 +1,185 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::git::discovery;
+use crate::git::worktrees::AddWorktreeOptions;
+use crate::models::{ErrorResponse, GitOperationResponse, Repo, WorktreeInfo};
+use crate::utils::open_repo;
+
+#[derive(Deserialize)]
+pub struct AddWorktreeQuery {
+    /// Folder to create; defaults to `<repo>-<branch>` next to the repository.
+    path: Option<String>,
+    /// Existing branch to check out.
+    branch: Option<String>,
+    /// Branch to create instead, at `start_point` (HEAD when omitted).
+    new_branch: Option<String>,
+    start_point: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RemoveWorktreeQuery {
+    path: String,
+    /// Also remove it with uncommitted changes or when locked.
+    force: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct WorktreePathQuery {
+    path: String,
+}
+
+fn join_error(e: tokio::task::JoinError) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("Task join error: {}", e),
+        }),
+    )
+}
+
+fn bad_request(e: anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    )
+}
+
+/// The main worktree and every linked worktree with branch, HEAD and
+/// locked/prunable state
+pub async fn list_worktrees(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<Vec<WorktreeInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let worktrees = tokio::task::spawn_blocking(move || git_repo.list_worktrees())
+        .await
+        .map_err(join_error)?
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to list worktrees: {}", e),
+                }),
+            )
+        })?;
+    Ok(Json(worktrees))
+}
+
+/// Add a linked worktree for a branch or a new branch. Returns it as a Repo
+/// so the UI can open it right away.
+pub async fn add_worktree(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<AddWorktreeQuery>,
+) -> Result<Json<Repo>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let opts = AddWorktreeOptions {
+        path: params
+            .path
+            .filter(|p| !p.trim().is_empty())
+            .map(PathBuf::from),
+        branch: params.branch,
+        new_branch: params.new_branch,
+        start_point: params.start_point,
+    };
+    // Checking out the new worktree can take a while on big repositories
+    let repo = tokio::task::spawn_blocking(move || {
+        git_repo
+            .add_worktree(&opts)
+            .map(|path| discovery::register(&path))
+    })
+    .await
+    .map_err(join_error)?
+    .map_err(bad_request)?;
+    Ok(Json(repo))
+}
+
+/// Remove a linked worktree and its folder
+pub async fn remove_worktree(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<RemoveWorktreeQuery>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let path = params.path.clone();
+    let force = params.force.unwrap_or(false);
+    let result = tokio::task::spawn_blocking(move || git_repo.remove_worktree(&path, force))
+        .await
+        .map_err(join_error)?;
+    match result {
+        Ok(output) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(format!("Removed worktree {}", params.path)),
+            output: Some(output),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
+
+/// Forget worktrees whose folder was deleted outside GitPow
+pub async fn prune_worktrees(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+) -> Result<Json<GitOperationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let result = tokio::task::spawn_blocking(move || git_repo.prune_worktrees())
+        .await
+        .map_err(join_error)?;
+    match result {
+        Ok(pruned) => Ok(Json(GitOperationResponse {
+            success: true,
+            message: Some(match pruned.len() {
+                0 => "No worktrees to prune".to_string(),
+                n => format!("Pruned {} worktree(s)", n),
+            }),
+            output: (!pruned.is_empty()).then(|| pruned.join("\n")),
+            error: None,
+        })),
+        Err(e) => Ok(Json(GitOperationResponse {
+            success: false,
+            message: None,
+            output: None,
+            error: Some(e.to_string()),
+        })),
+    }
+}
+
+/// A linked worktree as a Repo, to open it like any other repository
+pub async fn open_worktree(
+    State(config): State<Config>,
+    Path(repo_name): Path<String>,
+    Query(params): Query<WorktreePathQuery>,
+) -> Result<Json<Repo>, (StatusCode, Json<ErrorResponse>)> {
+    let git_repo = open_repo(&config, &repo_name)?;
+
+    let repo = tokio::task::spawn_blocking(move || {
+        let worktree = git_repo.find_linked_worktree(&params.path)?;
+        if worktree.prunable {
+            anyhow::bail!("The worktree folder {} no longer exists", worktree.path);
+        }
+        Ok(discovery::register(std::path::Path::new(&worktree.path)))
+    })
+    .await
+    .map_err(join_error)?
+    .map_err(bad_request)?;
+    Ok(Json(repo))
+}
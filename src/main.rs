@@ -19,21 +19,50 @@ use config::Config;
 use handlers::branches::{get_branch_ahead_behind, get_branch_creation, get_branches};
 use handlers::browse::browse_projects_root;
 use handlers::commits::{
-    get_commit_metrics, get_commits, get_commits_all_branches, get_commits_between, get_tags,
+    get_commit_metrics, get_commits, get_commits_all_branches, get_commits_between,
+};
+use handlers::conflicts::{
+    auto_resolve_conflicts, get_conflict_file, get_conflict_strategies, get_conflicts,
+    resolve_conflict, resolve_conflict_regions, save_conflict_strategies, set_rerere,
+};
+use handlers::credentials::{
+    answer_credential_prompt, cancel_credential_prompt, delete_credential,
+    get_credential_prompts, list_credentials, save_credential,
 };
-use handlers::conflicts::{get_conflict_file, get_conflicts, resolve_conflict};
 use handlers::diff::get_diff;
 use handlers::explorer::open_explorer;
 use handlers::files::{
     get_commit_files, get_file, get_file_creation, get_file_creation_batch, get_files, get_image,
+    get_tree,
+};
+use handlers::jobs::{
+    cancel_job, get_job, job_events, list_jobs, start_clone_job,
+    start_fetch_all_job, start_fetch_job, start_pull_job, start_push_job,
+    start_submodule_update_job,
 };
 use handlers::rebase::{get_rebase_preview, post_rebase_plan};
-use handlers::repos::{get_config, get_repos};
+use handlers::reflog::{get_lost_commits, get_reflog, recover_commit, restore_reflog_entry};
+use handlers::bisect::{get_bisect, mark_bisect, reset_bisect, start_bisect};
+use handlers::reset::{reset, reset_preview, restore_file};
+use handlers::remotes::{
+    add_remote, fetch_remote, get_stale_branches, list_remotes, remove_remote, rename_remote,
+    set_remote_refspecs, set_remote_url,
+};
+use handlers::journal::{get_journal, undo};
+use handlers::operations::{abort_operation, continue_operation, get_operation, skip_operation};
+use handlers::repos::{get_config, get_dashboard, get_repos, init_repo, rescan_repos};
+use handlers::search::search_code;
+use handlers::stats::{get_churn_stats, get_commit_activity, get_contributor_stats};
+use handlers::submodules::{init_submodules, list_submodules, open_submodule, sync_submodules};
+use handlers::worktrees::{
+    add_worktree, list_worktrees, open_worktree, prune_worktrees, remove_worktree,
+};
 use handlers::staging::{commit, get_status, stage, unstage};
+use handlers::tags::{create_tag, delete_tag, get_tags, push_tags};
 use handlers::fetch::fetch_repo;
 use handlers::git_ops::{
-    get_branch_status, pull_repo, push_repo, stash_apply, stash_drop, stash_list, stash_pop,
-    stash_push,
+    delete_remote_branch, get_branch_status, pull_repo, push_preview, push_repo, stash_apply,
+    stash_branch, stash_drop, stash_list, stash_pop, stash_push, stash_rename, stash_show,
 };
 
 #[tokio::main]
@@ -56,10 +85,29 @@ async fn main() {
         ))
         .service(static_dir);
 
+    // Event streams stay open for as long as the client listens, so they're
+    // mounted outside the request timeout.
+    let streams = Router::new()
+        .route("/api/jobs/events", get(job_events))
+        .layer(CorsLayer::permissive());
+
     let app = Router::new()
         .route("/api/config", get(get_config))
         .route("/api/browse/projects-root", get(browse_projects_root))
         .route("/api/repos", get(get_repos))
+        .route("/api/repos-init", post(init_repo))
+        .route("/api/repos-rescan", post(rescan_repos))
+        .route("/api/dashboard", get(get_dashboard))
+        .route("/api/credentials", get(list_credentials).post(save_credential))
+        .route("/api/credentials/delete", post(delete_credential))
+        .route("/api/credentials/prompts", get(get_credential_prompts))
+        .route("/api/credentials/prompts/:id", post(answer_credential_prompt))
+        .route("/api/credentials/prompts/:id/cancel", post(cancel_credential_prompt))
+        .route("/api/jobs", get(list_jobs))
+        .route("/api/jobs/clone", post(start_clone_job))
+        .route("/api/jobs/fetch-all", post(start_fetch_all_job))
+        .route("/api/jobs/:id", get(get_job))
+        .route("/api/jobs/:id/cancel", post(cancel_job))
         .route("/api/repos/:repo/branches", get(get_branches))
         // Ahead/behind endpoint uses query parameters for both repo and branch
         // to avoid any routing edge cases with slashes in branch names.
@@ -73,8 +121,15 @@ async fn main() {
         )
         .route("/api/repos/:repo/commits-between", get(get_commits_between))
         .route("/api/repos/:repo/commits/metrics", get(get_commit_metrics))
+        .route("/api/repos/:repo/stats/contributors", get(get_contributor_stats))
+        .route("/api/repos/:repo/stats/churn", get(get_churn_stats))
+        .route("/api/repos/:repo/stats/activity", get(get_commit_activity))
         .route("/api/repos/:repo/tags", get(get_tags))
+        .route("/api/repos/:repo/tags/create", post(create_tag))
+        .route("/api/repos/:repo/tags/delete", post(delete_tag))
+        .route("/api/repos/:repo/tags/push", post(push_tags))
         .route("/api/repos/:repo/files", get(get_files))
+        .route("/api/repos/:repo/tree", get(get_tree))
         .route("/api/repos/:repo/commit/files", get(get_commit_files))
         .route("/api/repos/:repo/file", get(get_file))
         .route("/api/repos/:repo/file-creation", get(get_file_creation))
@@ -83,31 +138,89 @@ async fn main() {
             get(get_file_creation_batch),
         )
         .route("/api/repos/:repo/image", get(get_image))
+        .route("/api/repos/:repo/search", get(search_code))
         .route("/api/repos/:repo/diff", get(get_diff))
         .route("/api/repos/:repo/status", get(get_status))
         .route("/api/repos/:repo/stage", post(stage))
         .route("/api/repos/:repo/unstage", post(unstage))
         .route("/api/repos/:repo/commit", post(commit))
         .route("/api/repos/:repo/fetch", post(fetch_repo))
+        .route("/api/repos/:repo/remotes", get(list_remotes))
+        .route("/api/repos/:repo/remotes/add", post(add_remote))
+        .route("/api/repos/:repo/remotes/remove", post(remove_remote))
+        .route("/api/repos/:repo/remotes/rename", post(rename_remote))
+        .route("/api/repos/:repo/remotes/set-url", post(set_remote_url))
+        .route("/api/repos/:repo/remotes/refspecs", post(set_remote_refspecs))
+        .route("/api/repos/:repo/remotes/fetch", post(fetch_remote))
+        .route("/api/repos/:repo/remotes/stale", get(get_stale_branches))
         .route("/api/repos/:repo/pull", post(pull_repo))
         .route("/api/repos/:repo/push", post(push_repo))
+        .route("/api/repos/:repo/push/preview", get(push_preview))
+        .route("/api/repos/:repo/push/delete", post(delete_remote_branch))
+        .route("/api/repos/:repo/jobs/fetch", post(start_fetch_job))
+        .route("/api/repos/:repo/jobs/pull", post(start_pull_job))
+        .route("/api/repos/:repo/jobs/push", post(start_push_job))
         .route("/api/repos/:repo/branch-status", get(get_branch_status))
+        .route("/api/repos/:repo/operation", get(get_operation))
+        .route("/api/repos/:repo/operation/continue", post(continue_operation))
+        .route("/api/repos/:repo/operation/skip", post(skip_operation))
+        .route("/api/repos/:repo/operation/abort", post(abort_operation))
+        .route("/api/repos/:repo/journal", get(get_journal))
+        .route("/api/repos/:repo/journal/undo", post(undo))
+        .route("/api/repos/:repo/reflog", get(get_reflog))
+        .route("/api/repos/:repo/reflog/restore", post(restore_reflog_entry))
+        .route("/api/repos/:repo/lost-commits", get(get_lost_commits))
+        .route("/api/repos/:repo/lost-commits/recover", post(recover_commit))
+        .route("/api/repos/:repo/reset", post(reset))
+        .route("/api/repos/:repo/reset/preview", get(reset_preview))
+        .route("/api/repos/:repo/restore-file", post(restore_file))
+        .route("/api/repos/:repo/bisect", get(get_bisect))
+        .route("/api/repos/:repo/bisect/start", post(start_bisect))
+        .route("/api/repos/:repo/bisect/mark", post(mark_bisect))
+        .route("/api/repos/:repo/bisect/reset", post(reset_bisect))
         .route("/api/repos/:repo/stash", get(stash_list))
         .route("/api/repos/:repo/stash/push", post(stash_push))
         .route("/api/repos/:repo/stash/pop", post(stash_pop))
         .route("/api/repos/:repo/stash/apply", post(stash_apply))
         .route("/api/repos/:repo/stash/drop", post(stash_drop))
+        .route("/api/repos/:repo/stash/show", get(stash_show))
+        .route("/api/repos/:repo/stash/branch", post(stash_branch))
+        .route("/api/repos/:repo/stash/rename", post(stash_rename))
         .route("/api/repos/:repo/rebase/preview", get(get_rebase_preview))
         .route("/api/repos/:repo/rebase/plan", post(post_rebase_plan))
         .route("/api/repos/:repo/conflicts", get(get_conflicts))
         .route("/api/repos/:repo/conflicts/file", get(get_conflict_file))
         .route("/api/repos/:repo/conflicts/resolve", post(resolve_conflict))
+        .route(
+            "/api/repos/:repo/conflicts/resolve-regions",
+            post(resolve_conflict_regions),
+        )
+        .route(
+            "/api/repos/:repo/conflicts/auto-resolve",
+            post(auto_resolve_conflicts),
+        )
+        .route("/api/repos/:repo/rerere", post(set_rerere))
+        .route(
+            "/api/conflict-strategies",
+            get(get_conflict_strategies).post(save_conflict_strategies),
+        )
+        .route("/api/repos/:repo/jobs/submodule-update", post(start_submodule_update_job))
+        .route("/api/repos/:repo/submodules", get(list_submodules))
+        .route("/api/repos/:repo/submodules/init", post(init_submodules))
+        .route("/api/repos/:repo/submodules/sync", post(sync_submodules))
+        .route("/api/repos/:repo/submodules/open", get(open_submodule))
+        .route("/api/repos/:repo/worktrees", get(list_worktrees))
+        .route("/api/repos/:repo/worktrees/add", post(add_worktree))
+        .route("/api/repos/:repo/worktrees/remove", post(remove_worktree))
+        .route("/api/repos/:repo/worktrees/prune", post(prune_worktrees))
+        .route("/api/repos/:repo/worktrees/open", get(open_worktree))
         .route("/api/repos/:repo/open-explorer", get(open_explorer))
         .layer(
             ServiceBuilder::new()
                 .layer(TimeoutLayer::new(Duration::from_secs(60))) // 60 second timeout for all requests
                 .layer(CorsLayer::permissive())
         )
+        .merge(streams)
         .with_state(app_state)
         .fallback_service(static_service);
 
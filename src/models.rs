@@ -5,6 +5,50 @@ use serde::{Deserialize, Serialize};
 pub struct Repo {
     pub id: String,
     pub name: String,
+    /// "repo", "worktree" or "bare".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Uncommitted or untracked changes; not set for bare repositories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dirty: Option<bool>,
+}
+
+/// One repository on the dashboard
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoSummary {
+    pub id: String,
+    pub name: String,
+    /// "repo", "worktree" or "bare".
+    pub kind: String,
+    /// Branch name, "HEAD" when detached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// e.g. "origin/main"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub behind: Option<usize>,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    pub stash_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_commit_date: Option<String>,
+    /// "merge", "rebase", "cherry-pick", "revert", "bisect" or "am".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<String>,
+    /// When any remote was last fetched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_fetch: Option<String>,
+    /// Why the repository couldn't be read; the other fields are then partial.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -15,6 +59,33 @@ pub struct BranchMetadata {
     pub is_unborn: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_commit_date: Option<String>,
+    /// Checked out in another worktree (its path), so it can't be checked out here.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree_path: Option<String>,
+}
+
+/// The main worktree or a linked one (`git worktree`)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeInfo {
+    pub path: String,
+    /// Name under .git/worktrees; not set for the main worktree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// HEAD commit SHA; not set for an unborn branch or a missing folder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    pub detached: bool,
+    pub is_main: bool,
+    /// The worktree the request was made for.
+    pub is_current: bool,
+    pub locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    /// Its folder is gone; `git worktree prune` will forget it.
+    pub prunable: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -107,7 +178,7 @@ pub struct CommitsResponse {
     pub branch_angles: Option<std::collections::HashMap<String, f64>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileChange {
     pub path: String,
@@ -131,6 +202,9 @@ pub struct DiffResponse {
     pub diff: String,
     pub hunks: Vec<DiffHunk>,
     pub file_path: String,
+    /// Set when the path is a submodule; `diff` then describes the pointer change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submodule: Option<SubmoduleDiff>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -142,13 +216,139 @@ pub struct StatusFile {
     pub status: String,
     pub staged: bool,
     pub unstaged: bool,
-    pub r#type: String, // modified, added, deleted, untracked, renamed
+    pub r#type: String, // modified, added, deleted, untracked, renamed, copied, type-changed, conflicted, ignored
+    /// Set when the path is a submodule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submodule: Option<SubmoduleState>,
+    /// Index (staged) side: unmodified, modified, added, deleted, renamed,
+    /// copied, type-changed or unmerged.
+    #[serde(default)]
+    pub index_state: String,
+    /// Working tree side, with the same values plus untracked and ignored.
+    #[serde(default)]
+    pub worktree_state: String,
+    /// Rename or copy similarity in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<ConflictInfo>,
+}
+
+/// The index stages of an unmerged path
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictInfo {
+    /// both-modified, both-added, both-deleted, added-by-us, added-by-them,
+    /// deleted-by-us, deleted-by-them or rename-rename
+    pub kind: String,
+    /// For rename/rename conflicts, the path both sides renamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<ConflictStage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ours: Option<ConflictStage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theirs: Option<ConflictStage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictStage {
+    pub mode: String,
+    pub sha: String,
+}
+
+/// Branch headers of a status response
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusBranch {
+    /// HEAD commit; None on an unborn branch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oid: Option<String>,
+    /// Current branch; None when detached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    pub detached: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub behind: Option<usize>,
+}
+
+/// What changed inside a submodule's working tree
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleState {
+    /// A different commit is checked out than the one in the index.
+    pub commit_changed: bool,
+    pub modified_content: bool,
+    pub untracked_content: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleInfo {
+    pub name: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Branch configured in .gitmodules.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit recorded in HEAD.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configured_sha: Option<String>,
+    /// Commit recorded in the index; differs from `configured_sha` when a new pointer is staged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_sha: Option<String>,
+    /// Commit checked out in the submodule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_out_sha: Option<String>,
+    /// Registered in .git/config (`git submodule init`).
+    pub initialized: bool,
+    pub checked_out: bool,
+    /// The checked-out commit isn't the one in the index; an update would move it.
+    pub out_of_date: bool,
+    /// Uncommitted or untracked changes inside the submodule.
+    pub dirty: bool,
+}
+
+/// A submodule pointer change, e.g. "a1b2c3d..c3d4e5f"
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleDiff {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_sha: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_sha: Option<String>,
+    pub range: String,
+    /// Commits added (new side) and removed (old side), newest first.
+    pub commits: Vec<SubmoduleCommit>,
+    /// The new commit doesn't contain the old one.
+    pub rewind: bool,
+    /// False when the submodule isn't cloned or lacks one of the commits.
+    pub commits_available: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleCommit {
+    pub sha: String,
+    pub summary: String,
+    /// Reachable from the new pointer only; otherwise from the old one only.
+    pub added: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatusResponse {
     pub files: Vec<StatusFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<StatusBranch>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -192,8 +392,24 @@ pub struct ImageResponse {
 #[serde(rename_all = "camelCase")]
 pub struct Tag {
     pub name: String,
+    /// Commit the tag ultimately points at (peeled through tag objects);
+    /// empty for tags of a tree or blob.
     pub sha: String,
+    /// The ref's direct target: the tag object for annotated tags, else the commit.
+    pub object_sha: String,
+    /// Tagger date for annotated tags, commit date for lightweight ones
+    /// (empty if it doesn't point at a commit).
     pub date: String,
+    pub annotated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tagger: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tagger_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// "unsigned", "signed" (not verified), or the result of verification:
+    /// "good", "bad", "unknown-key", "error".
+    pub signature_status: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -205,6 +421,120 @@ pub struct CommitMetric {
     pub impact_score: f64,
 }
 
+/// Per-author statistics over a ref and date range
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributorStatsResponse {
+    /// The ref that was walked
+    pub branch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    pub total_commits: usize,
+    /// Most commits first
+    pub contributors: Vec<ContributorStats>,
+}
+
+/// One author, with identities merged through .mailmap
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributorStats {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    /// Merge commits count as commits but add no lines or files.
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub files_touched: usize,
+    pub first_commit: String,
+    pub last_commit: String,
+    /// Distinct days (in the author's time zone) with a commit
+    pub active_days: usize,
+    /// Weeks with commits, oldest first
+    pub weeks: Vec<WeeklyActivity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyActivity {
+    /// Monday the week starts on, YYYY-MM-DD
+    pub week: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// Churn, hotspots and change coupling over a ref and date range
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChurnResponse {
+    pub branch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    pub commits_analyzed: usize,
+    /// Highest hotspot score first; only files that still exist at the ref
+    pub files: Vec<PathChurn>,
+    pub directories: Vec<PathChurn>,
+    /// Most shared commits first
+    pub coupling: Vec<ChangeCoupling>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathChurn {
+    /// File path, or directory path ("" for the repository root)
+    pub path: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Current size in lines at the ref; for directories, summed over the
+    /// files listed
+    pub lines: usize,
+    /// Normalized churn times normalized size, 0..1
+    pub hotspot_score: f64,
+}
+
+/// Two files that changed in the same commits
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeCoupling {
+    pub file_a: String,
+    pub file_b: String,
+    pub shared_commits: usize,
+    /// Shared commits over the average commit count of the two files, 0..1
+    pub degree: f64,
+}
+
+/// Commit counts over time for the whole history of a ref
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityResponse {
+    pub branch: String,
+    /// day, week, month or year
+    pub bucket: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    pub total_commits: usize,
+    /// Oldest first, with empty periods between the first and last included
+    pub buckets: Vec<ActivityBucket>,
+    /// Commits by weekday (7 rows, Monday first) and hour (24 columns), in
+    /// the author's time zone
+    pub punchcard: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityBucket {
+    /// YYYY-MM-DD for days and weeks (the Monday), YYYY-MM for months, YYYY for years
+    pub period: String,
+    pub commits: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RebasePreview {
@@ -249,6 +579,9 @@ pub struct RebasePlanResponse {
 pub struct ConflictFile {
     pub path: String,
     pub r#type: String,
+    /// For rename/rename conflicts, the path both sides renamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -256,6 +589,53 @@ pub struct ConflictFile {
 pub struct ConflictsResponse {
     pub files: Vec<ConflictFile>,
     pub has_conflicts: bool,
+    /// Files resolved by a strategy or by rerere during the current
+    /// merge/rebase. rerere results stay in `files` until reviewed and staged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_resolved: Vec<AutoResolvedFile>,
+    #[serde(default)]
+    pub rerere_enabled: bool,
+}
+
+/// How to resolve conflicts in files matching `pattern` automatically
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictStrategyRule {
+    /// File name glob (e.g. "*.lock") or, when it contains '/', a path
+    /// pattern relative to the repository root
+    pub pattern: String,
+    /// ours, theirs, union or command
+    pub strategy: String,
+    /// For "command": run in the repository root after taking our version,
+    /// with the conflicted path in `$GITPOW_CONFLICT_PATH`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoResolvedFile {
+    pub path: String,
+    /// ours, theirs, union, command or rerere
+    pub method: String,
+    /// The rule pattern that matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    pub staged: bool,
+    /// rerere replays an earlier resolution; check it before staging.
+    pub needs_review: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoResolveResponse {
+    pub resolved: Vec<AutoResolvedFile>,
+    /// Files a matching rule could not resolve, with `error` set
+    pub failed: Vec<AutoResolvedFile>,
+    /// Conflicts left for manual resolution
+    pub remaining: Vec<ConflictFile>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -266,13 +646,81 @@ pub struct ConflictFileResponse {
     pub theirs: String,
     pub result: String,
     pub file_path: String,
+    /// Conflict kind as in ConflictFile, or "resolved"
+    pub kind: String,
+    /// Conflict marker blocks in `result`, in file order
+    pub regions: Vec<ConflictRegion>,
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block of a conflicted file. Line numbers
+/// are 1-based positions in the working file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictRegion {
+    pub index: usize,
+    /// Line of the `<<<<<<<` marker
+    pub start_line: usize,
+    /// Lines including the markers
+    pub line_count: usize,
+    pub ours: ConflictChunk,
+    /// Only present with diff3 or zdiff3 markers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<ConflictChunk>,
+    pub theirs: ConflictChunk,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictChunk {
+    /// Text after the marker, e.g. "HEAD" or a branch name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub start_line: usize,
+    pub line_count: usize,
+    pub text: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResolveConflictRequest {
     pub path: String,
+    #[serde(default)]
     pub content: String,
+    /// Take a whole side instead of `content`: ours, theirs or delete.
+    /// Choosing the side that deleted the file removes it.
+    #[serde(default)]
+    pub resolution: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveRegionsRequest {
+    pub path: String,
+    pub resolutions: Vec<RegionResolution>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionResolution {
+    /// ConflictRegion index
+    pub index: usize,
+    /// ours, theirs, base, ours-theirs, theirs-ours or custom
+    pub choice: String,
+    /// Replacement text for "custom"
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveRegionsResponse {
+    pub success: bool,
+    /// Regions still unresolved
+    pub remaining: usize,
+    /// True once every region is resolved and the file was staged
+    pub staged: bool,
+    /// The file content after applying the resolutions
+    pub result: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -304,6 +752,11 @@ pub struct StashEntry {
     pub index: String,
     pub message: String,
     pub date: String,
+    pub sha: String,
+    /// Commit HEAD pointed at when the stash was created
+    pub base_sha: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -318,6 +771,78 @@ pub struct GitOperationResponse {
     pub error: Option<String>,
 }
 
+/// What a push would do to the remote branch, checked against the remote.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushPreview {
+    pub remote: String,
+    pub branch: String,
+    pub remote_ref: String,
+    pub local_sha: String,
+    /// Where the branch is on the remote now; None if it doesn't exist there.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_sha: Option<String>,
+    /// Our remote-tracking ref for it, as of the last fetch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_sha: Option<String>,
+    /// What a force-with-lease would expect; None means "must not exist".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_sha: Option<String>,
+    /// Whether a force-with-lease push would be accepted.
+    pub lease_ok: bool,
+    pub up_to_date: bool,
+    /// A plain push is enough; otherwise it needs force.
+    pub fast_forward: bool,
+    /// Whether we have the remote commit locally; if not, `discarded` is unknown.
+    pub remote_known: bool,
+    /// Commits on the remote branch a force push would discard, newest first.
+    pub discarded: Vec<Commit>,
+    pub discarded_count: usize,
+    /// SHAs of discarded commits that were never in our local history.
+    pub not_in_local_history: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of a pull, beyond the success flag.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PullResult {
+    /// "up-to-date", "fast-forwarded", "merged", "rebased" or "conflicted".
+    pub outcome: String,
+    /// "ff-only", "merge", "rebase" or "rebase-merges".
+    pub mode: String,
+    pub branch: String,
+    pub upstream: String,
+    pub old_head: String,
+    pub new_head: String,
+    /// Commits brought in from the upstream, newest first (capped).
+    pub commits: Vec<Commit>,
+    pub commit_count: usize,
+    /// Unresolved files when the outcome is "conflicted"; resolve them through
+    /// the conflicts endpoints, then continue or abort `in_progress`.
+    pub conflicts: Vec<ConflictFile>,
+    /// "merge" or "rebase" left in progress by a conflicted pull.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<String>,
+    /// "applied", "conflicted" (the changes stay in the stash) or "pending"
+    /// (reapplied when the conflicted rebase finishes).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autostash: Option<String>,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<PullResult>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchStatusResponse {
@@ -327,6 +852,162 @@ pub struct BranchStatusResponse {
     pub behind: usize,
     pub has_uncommitted: bool,
     pub stash_count: usize,
+    /// Merge, rebase, etc. in progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<OperationState>,
+}
+
+/// An operation left in progress, e.g. by conflicts
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationState {
+    /// merge, rebase, cherry-pick, revert, bisect or am
+    pub kind: String,
+    /// Branch being rebased
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit a rebase replays onto
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onto: Option<String>,
+    /// Commit(s) being merged, cherry-picked or reverted, or where a rebase stopped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub incoming: Vec<String>,
+    /// 1-based number of the current step
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_steps: Option<usize>,
+    /// The step git stopped at (the last done step)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<RebaseStep>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub done: Vec<RebaseStep>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub todo: Vec<RebaseStep>,
+    pub conflicted_files: usize,
+    pub can_continue: bool,
+    pub can_skip: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bisect: Option<BisectState>,
+}
+
+/// Where a bisect stands
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectState {
+    /// Branch (or commit) checked out before the bisect; reset returns to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_head: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bad: Option<String>,
+    pub good: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Only commits touching these paths are tested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    /// Commit checked out for testing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    /// Untested commits that may still be the first bad one, skipped ones excluded.
+    pub remaining: usize,
+    /// Tests left in the worst case.
+    pub estimated_steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_bad: Option<Commit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub first_bad_files: Vec<FileChange>,
+    /// When only skipped commits are left: the commits that may be the first
+    /// bad one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub possible_first_bad: Vec<String>,
+}
+
+/// One line of a rebase/sequencer todo list
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseStep {
+    /// pick, reword, edit, squash, fixup, drop, exec, label, merge, ... or
+    /// "apply" for `git am` patches
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+    /// Commit subject, or the argument of non-commit actions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The raw todo line
+    pub line: String,
+}
+
+/// A GitPow action recorded with the state it started from, so it can be undone
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: String,
+    /// What was done, e.g. "Commit" or "Drop stash@{0}"
+    pub operation: String,
+    pub timestamp: String,
+    /// HEAD before the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Checked-out branch before the operation, unless HEAD was detached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Whether the working tree (including untracked files) was saved
+    pub worktree_saved: bool,
+    pub undone: bool,
+    /// Set on entries that undid another entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undo_of: Option<String>,
+}
+
+/// One move of a ref, newest first in its reflog
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReflogEntry {
+    /// e.g. "HEAD@{2}" or "main@{0}"
+    pub selector: String,
+    pub index: usize,
+    /// None when the ref was created by this entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_sha: Option<String>,
+    pub new_sha: String,
+    /// checkout, commit, amend, rebase, reset, merge, pull, cherry-pick,
+    /// revert, branch, clone, fetch, push or other
+    pub action: String,
+    pub message: String,
+    pub committer: String,
+    pub email: String,
+    pub date: String,
+}
+
+/// A commit no branch, tag or stash entry reaches any more
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LostCommit {
+    /// "stash" for dropped stashes, otherwise "commit"
+    pub kind: String,
+    pub commit: Commit,
+}
+
+/// What a reset would do, before running it
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPreview {
+    /// "soft", "mixed", "hard" or "keep".
+    pub mode: String,
+    /// Branch that moves; not set when HEAD is detached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub old_head: String,
+    pub target: String,
+    /// Commits no branch, tag or remote branch reaches afterwards, newest
+    /// first (capped); only the reflog still finds them.
+    pub unreachable_commits: Vec<Commit>,
+    pub unreachable_count: usize,
+    /// Local changes a hard reset discards, including untracked files it overwrites.
+    pub lost_changes: Vec<StatusFile>,
+    /// Local changes that make a keep reset refuse to run.
+    pub blocked_by: Vec<StatusFile>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -334,3 +1015,245 @@ pub struct BranchStatusResponse {
 pub struct StashListResponse {
     pub entries: Vec<StashEntry>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StashFileChange {
+    pub path: String,
+    pub status: String,
+    /// File came from the stash's untracked-files commit
+    pub untracked: bool,
+    pub diff: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StashShowResponse {
+    pub entry: StashEntry,
+    pub files: Vec<StashFileChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCommitInfo {
+    pub sha: String,
+    pub author: String,
+    pub date: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeItem {
+    pub name: String,
+    pub path: String,
+    pub kind: String, // blob, tree, submodule, symlink
+    pub mode: String,
+    pub oid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_commit: Option<LastCommitInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeResponse {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    pub commit: String,
+    pub path: String,
+    pub tree_oid: String,
+    pub entries: Vec<TreeItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatchSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchLine {
+    pub line_number: usize,
+    pub text: String,
+    pub is_match: bool,
+    pub spans: Vec<SearchMatchSpan>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFileResult {
+    pub path: String,
+    pub match_count: usize,
+    pub lines: Vec<SearchLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    pub query: String,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    pub files: Vec<SearchFileResult>,
+    pub total_matches: usize,
+    pub truncated: bool,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteInfo {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub push_url: Option<String>,
+    pub fetch_refspecs: Vec<String>,
+    pub push_refspecs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFetchResult {
+    pub remote: String,
+    pub received_objects: usize,
+    /// Local refs created or moved by the fetch.
+    pub updated: Vec<String>,
+    /// Remote-tracking refs deleted because their source is gone.
+    pub pruned: Vec<String>,
+}
+
+/// Outcome of fetching one repository in a bulk fetch
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoFetchSummary {
+    pub id: String,
+    pub name: String,
+    pub remotes: Vec<RemoteFetchResult>,
+    /// Remotes skipped because they need credentials nobody has supplied.
+    pub skipped: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleRemoteBranch {
+    pub remote: String,
+    /// Short name, e.g. "origin/feature".
+    pub name: String,
+    pub ref_name: String,
+    pub sha: String,
+}
+
+/// A network operation waiting for the user to supply credentials.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialPrompt {
+    pub id: String,
+    pub url: String,
+    pub host: String,
+    /// "userpass" (HTTP username + password/token) or "passphrase" (SSH key).
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialAnswer {
+    pub username: Option<String>,
+    /// Password, token or key passphrase.
+    pub secret: String,
+    /// Save to the credential store on disk once accepted.
+    #[serde(default)]
+    pub remember: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCredentialRequest {
+    /// "token" (default), "password" or "passphrase".
+    pub kind: Option<String>,
+    /// Host (with port, if any) the token or password is for.
+    pub host: Option<String>,
+    /// Private key path a passphrase is for.
+    pub key_path: Option<String>,
+    pub username: Option<String>,
+    pub secret: String,
+    /// Persist to disk (default); false keeps it for this session only.
+    pub remember: Option<bool>,
+}
+
+/// A saved credential, without its secret.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialInfo {
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    pub persisted: bool,
+}
+
+/// A background network operation (fetch, pull, push or clone).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: String,
+    /// "fetch", "pull", "push" or "clone".
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    pub description: String,
+    /// "running", "succeeded", "failed" or "cancelled".
+    pub status: String,
+    pub progress: JobProgress,
+    /// Last line of remote output ("remote: Counting objects...").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Structured result for jobs that have one (pull: a PullResult).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+}
+
+/// Transfer counters as reported by libgit2.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    /// "connecting", "receiving", "resolving", "packing", "sending",
+    /// "integrating" (pull's merge/rebase step) or "bisecting".
+    pub stage: String,
+    pub total_objects: usize,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub local_objects: usize,
+    pub total_deltas: usize,
+    pub indexed_deltas: usize,
+    /// Objects written to the remote (push).
+    pub sent_objects: usize,
+    /// Bytes received (fetch, clone) or sent (push).
+    pub bytes: usize,
+    /// Repositories in a bulk fetch, and how many are done.
+    pub total_repos: usize,
+    pub completed_repos: usize,
+}
@@ -1,6 +1,11 @@
+use axum::{http::StatusCode, response::Json};
 use regex::Regex;
 use std::path::{Path, PathBuf};
 
+use crate::config::Config;
+use crate::git::repository::GitRepository;
+use crate::models::ErrorResponse;
+
 pub fn get_repo_path(name: &str, repos_root: &PathBuf) -> PathBuf {
     let candidate = Path::new(name);
     if candidate.is_absolute() {
@@ -15,6 +20,52 @@ pub fn get_repo_path(name: &str, repos_root: &PathBuf) -> PathBuf {
     repos_root.join(safe_name.as_ref())
 }
 
+/// Path of a repository under the repos root, or 404 if it isn't a directory.
+pub fn repo_path_for(
+    config: &Config,
+    repo_name: &str,
+) -> Result<PathBuf, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = PathBuf::from(&config.repos_root).join(repo_name);
+
+    if !repo_path.exists() || !repo_path.is_dir() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Repository not found".to_string(),
+            }),
+        ));
+    }
+    Ok(repo_path)
+}
+
+/// Open a repository under the repos root for a handler: 404 if it doesn't
+/// exist, 500 if it can't be opened.
+pub fn open_repo(
+    config: &Config,
+    repo_name: &str,
+) -> Result<GitRepository, (StatusCode, Json<ErrorResponse>)> {
+    let repo_path = repo_path_for(config, repo_name)?;
+    GitRepository::open(&repo_path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to open repository: {}", e),
+            }),
+        )
+    })
+}
+
+/// User-facing ID for a repository path. On Windows, strips any
+/// extended-length prefix (\\?\C:\...) for readability.
+pub fn repo_id(path: &Path) -> String {
+    let raw = path.to_string_lossy().to_string();
+    if cfg!(windows) && raw.starts_with(r"\\?\") {
+        raw[4..].to_string()
+    } else {
+        raw
+    }
+}
+
 pub fn normalize_sha(raw_sha: &str) -> String {
     let re = Regex::new(r"[0-9a-fA-F]{40}").unwrap();
     if let Some(caps) = re.find(raw_sha) {
@@ -23,3 +74,43 @@ pub fn normalize_sha(raw_sha: &str) -> String {
         raw_sha.to_string()
     }
 }
+
+/// Replace `path` with `contents` via a sibling temp file and a rename, so
+/// readers and crashes never see a half-written file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    replace_file(path, contents, false)
+}
+
+/// Like [`write_atomic`], for secrets: on unix the temp file is created
+/// owner-only (0600), so the contents are never readable by anyone else.
+pub fn write_atomic_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    replace_file(path, contents, true)
+}
+
+fn replace_file(path: &Path, contents: &[u8], private: bool) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+    // A temp file left by a crash keeps its old mode, so never reuse one.
+    let _ = std::fs::remove_file(&tmp);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    let written = options
+        .open(&tmp)
+        .and_then(|mut file| file.write_all(contents).and_then(|_| file.sync_all()))
+        .and_then(|_| std::fs::rename(&tmp, path));
+    written.inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
@@ -0,0 +1,171 @@
+//! Cloning over file:// and creating repositories from templates.
+
+use gitpow_rust::git::clone::{CloneOptions, InitOptions};
+use gitpow_rust::git::repository::GitRepository;
+use std::path::{Path, PathBuf};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gitpow-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// A source repository with three commits on main that serves partial clones.
+fn source_repo(dir: &Path) -> String {
+    let repo =
+        git2::Repository::init_opts(dir, git2::RepositoryInitOptions::new().initial_head("main"))
+            .unwrap();
+    repo.config()
+        .unwrap()
+        .set_bool("uploadpack.allowFilter", true)
+        .unwrap();
+    let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+    let mut parent = None;
+    for i in 1..=3 {
+        std::fs::write(dir.join("file.txt"), format!("version {}\n", i)).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents: Vec<git2::Commit> = parent
+            .iter()
+            .map(|p| repo.find_commit(*p).unwrap())
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        parent = Some(
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                &format!("commit {}", i),
+                &tree,
+                &parent_refs,
+            )
+            .unwrap(),
+        );
+    }
+    format!("file://{}", dir.to_string_lossy().replace('\\', "/"))
+}
+
+fn history_len(repo: &GitRepository) -> usize {
+    let mut revwalk = repo.repo.revwalk().unwrap();
+    revwalk.push_head().unwrap();
+    revwalk.count()
+}
+
+#[test]
+fn clone_full_history() {
+    let source = scratch_dir("clone-full-src");
+    let dest = scratch_dir("clone-full-dst");
+    let url = source_repo(&source);
+
+    let repo = GitRepository::clone_from(&url, &dest, &CloneOptions::default()).unwrap();
+
+    assert_eq!(history_len(&repo), 3);
+    assert!(!repo.repo.is_shallow());
+    assert_eq!(
+        std::fs::read_to_string(dest.join("file.txt")).unwrap(),
+        "version 3\n"
+    );
+    let _ = std::fs::remove_dir_all(source);
+    let _ = std::fs::remove_dir_all(dest);
+}
+
+#[test]
+fn shallow_clone_keeps_only_depth_commits() {
+    let source = scratch_dir("clone-shallow-src");
+    let dest = scratch_dir("clone-shallow-dst");
+    let url = source_repo(&source);
+
+    let opts = CloneOptions {
+        depth: Some(1),
+        ..Default::default()
+    };
+    let repo = GitRepository::clone_from(&url, &dest, &opts).unwrap();
+
+    assert!(repo.repo.is_shallow());
+    assert_eq!(history_len(&repo), 1);
+    let _ = std::fs::remove_dir_all(source);
+    let _ = std::fs::remove_dir_all(dest);
+}
+
+#[test]
+fn filtered_clone_is_partial() {
+    let source = scratch_dir("clone-filter-src");
+    let dest = scratch_dir("clone-filter-dst");
+    let url = source_repo(&source);
+
+    let opts = CloneOptions {
+        filter: Some("blob:none".to_string()),
+        ..Default::default()
+    };
+    let repo = GitRepository::clone_from(&url, &dest, &opts).unwrap();
+
+    let config = repo.repo.config().unwrap().snapshot().unwrap();
+    assert_eq!(
+        config
+            .get_string("remote.origin.partialclonefilter")
+            .unwrap(),
+        "blob:none"
+    );
+    assert_eq!(history_len(&repo), 3);
+    let _ = std::fs::remove_dir_all(source);
+    let _ = std::fs::remove_dir_all(dest);
+}
+
+#[test]
+fn failed_clone_removes_destination() {
+    let dest = scratch_dir("clone-missing-dst");
+    let missing = scratch_dir("clone-missing-src");
+
+    let opts = CloneOptions {
+        depth: Some(1),
+        ..Default::default()
+    };
+    let url = format!("file://{}", missing.to_string_lossy().replace('\\', "/"));
+    assert!(GitRepository::clone_from(&url, &dest, &opts).is_err());
+    assert!(!dest.exists());
+}
+
+#[test]
+fn init_with_templates_and_initial_commit() {
+    for (key, value) in [
+        ("GIT_AUTHOR_NAME", "Test"),
+        ("GIT_AUTHOR_EMAIL", "test@example.com"),
+        ("GIT_COMMITTER_NAME", "Test"),
+        ("GIT_COMMITTER_EMAIL", "test@example.com"),
+    ] {
+        std::env::set_var(key, value);
+    }
+    let dest = scratch_dir("init-template");
+
+    let opts = InitOptions {
+        default_branch: Some("trunk".to_string()),
+        initial_commit: true,
+        gitignore: Some("rust".to_string()),
+        readme: true,
+    };
+    let repo = GitRepository::init_at(&dest, &opts).unwrap();
+
+    let head = repo.repo.head().unwrap();
+    assert_eq!(head.name(), Some("refs/heads/trunk"));
+    let tree = head.peel_to_commit().unwrap().tree().unwrap();
+    assert!(tree.get_name(".gitignore").is_some());
+    assert!(tree.get_name("README.md").is_some());
+    assert!(std::fs::read_to_string(dest.join(".gitignore"))
+        .unwrap()
+        .contains("/target"));
+    let _ = std::fs::remove_dir_all(dest);
+}
+
+#[test]
+fn init_rejects_unknown_template() {
+    let dest = scratch_dir("init-bad-template");
+
+    let opts = InitOptions {
+        gitignore: Some("cobol".to_string()),
+        ..Default::default()
+    };
+    assert!(GitRepository::init_at(&dest, &opts).is_err());
+    assert!(!dest.exists());
+}
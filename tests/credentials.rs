@@ -0,0 +1,141 @@
+//! Credential chain against a local HTTP stand-in that demands Basic auth
+//! and records every Authorization header it is sent.
+
+use base64::Engine;
+use gitpow_rust::git::credentials::{save_credential, CredentialSession};
+use gitpow_rust::models::SaveCredentialRequest;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+
+/// The credential store is process-wide, so tests take turns with it.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Point the store at a scratch data dir before anything loads it, so the
+/// tests never read or change the developer's own credentials.
+fn isolated_store() -> MutexGuard<'static, ()> {
+    static DATA_DIR: Once = Once::new();
+    DATA_DIR.call_once(|| {
+        let dir = std::env::temp_dir().join(format!(
+            "gitpow-test-credentials-data-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::env::set_var("GITPOW_DATA_DIR", dir);
+    });
+    STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Accepts `user:password`; everything else gets a 401 challenge.
+fn auth_server(accept: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let host = listener.local_addr().unwrap().to_string();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut auth = None;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("Authorization: Basic ") {
+                    let decoded = base64::engine::general_purpose::STANDARD
+                        .decode(value.trim())
+                        .unwrap();
+                    auth = Some(String::from_utf8(decoded).unwrap());
+                }
+            }
+            let response = match &auth {
+                // Authenticated, but there's no repository to serve.
+                Some(a) if a == accept => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                _ => "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"test\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            };
+            if let Some(a) = auth {
+                log.lock().unwrap().push(a);
+            }
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    (host, seen)
+}
+
+fn scratch_repo(name: &str, host: &str) -> (PathBuf, git2::Repository) {
+    let dir = std::env::temp_dir().join(format!("gitpow-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let repo = git2::Repository::init(&dir).unwrap();
+    repo.remote("origin", &format!("http://{}/repo.git", host))
+        .unwrap();
+    (dir, repo)
+}
+
+/// Fetch origin without prompting; the fetch itself always fails.
+fn fetch(repo: &git2::Repository) {
+    let session = CredentialSession::new(repo, false);
+    let result = {
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(session.callbacks());
+        repo.find_remote("origin")
+            .unwrap()
+            .fetch(&[] as &[&str], Some(&mut fetch_options), None)
+    };
+    assert!(session.finish(result).is_err());
+}
+
+#[test]
+fn stored_token_is_sent() {
+    let _store = isolated_store();
+    let (host, seen) = auth_server("git:s3cret-token");
+    save_credential(SaveCredentialRequest {
+        kind: Some("token".to_string()),
+        host: Some(host.clone()),
+        key_path: None,
+        username: None,
+        secret: "s3cret-token".to_string(),
+        remember: Some(false),
+    })
+    .unwrap();
+    let (dir, repo) = scratch_repo("stored", &host);
+
+    fetch(&repo);
+
+    assert_eq!(
+        seen.lock().unwrap().first().map(String::as_str),
+        Some("git:s3cret-token")
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn credential_helper_follows_rejected_token() {
+    let _store = isolated_store();
+    let (host, seen) = auth_server("helper-user:from-helper");
+    save_credential(SaveCredentialRequest {
+        kind: Some("token".to_string()),
+        host: Some(host.clone()),
+        key_path: None,
+        username: Some("token-user".to_string()),
+        secret: "stale".to_string(),
+        remember: Some(false),
+    })
+    .unwrap();
+    let (dir, repo) = scratch_repo("helper", &host);
+    repo.config()
+        .unwrap()
+        .set_str(
+            "credential.helper",
+            "!f() { echo username=helper-user; echo password=from-helper; }; f",
+        )
+        .unwrap();
+
+    fetch(&repo);
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.first().map(String::as_str), Some("token-user:stale"));
+    assert!(seen.iter().any(|a| a == "helper-user:from-helper"));
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,137 @@
+//! Undo journal round trips: refs, HEAD, index and worktree come back.
+
+use gitpow_rust::git::repository::GitRepository;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gitpow-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// A repository on main with two commits; returns their SHAs, oldest first.
+fn repo_with_history(dir: &Path) -> Vec<String> {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.name", "Test"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    (1..=2)
+        .map(|i| {
+            std::fs::write(dir.join("file.txt"), format!("version {}\n", i)).unwrap();
+            git(dir, &["add", "file.txt"]);
+            git(dir, &["commit", "-q", "-m", &format!("commit {}", i)]);
+            git(dir, &["rev-parse", "HEAD"])
+        })
+        .collect()
+}
+
+#[test]
+fn undo_restores_head_index_and_worktree() {
+    let dir = scratch_dir("journal-roundtrip");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    std::fs::write(dir.join("file.txt"), "staged edit\n").unwrap();
+    git(&dir, &["add", "file.txt"]);
+    std::fs::write(dir.join("file.txt"), "worktree edit\n").unwrap();
+    std::fs::write(dir.join("untracked.txt"), "keep me\n").unwrap();
+
+    repo.journaled("Throw everything away", || {
+        git(&dir, &["reset", "-q", "--hard", &shas[0]]);
+        git(&dir, &["clean", "-q", "-f"]);
+        Ok(())
+    })
+    .unwrap();
+    assert!(!dir.join("untracked.txt").exists());
+
+    let undo = repo.undo_last().unwrap();
+    assert_eq!(git(&dir, &["rev-parse", "HEAD"]), shas[1]);
+    assert_eq!(git(&dir, &["symbolic-ref", "HEAD"]), "refs/heads/main");
+    assert_eq!(git(&dir, &["show", ":file.txt"]), "staged edit");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("file.txt")).unwrap(),
+        "worktree edit\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("untracked.txt")).unwrap(),
+        "keep me\n"
+    );
+
+    let history = repo.journal_history().unwrap();
+    assert_eq!(history[0].id, undo.id);
+    assert_eq!(history[0].undo_of.as_deref(), Some(history[1].id.as_str()));
+    assert!(history[1].undone);
+    assert_eq!(history[1].operation, "Throw everything away");
+    // The undo itself isn't offered as the next thing to undo.
+    assert!(repo.undo_last().is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn undo_restores_deleted_and_removes_created_branches() {
+    let dir = scratch_dir("journal-branches");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    git(&dir, &["branch", "topic", &shas[0]]);
+
+    repo.journaled("Shuffle branches", || {
+        git(&dir, &["branch", "-q", "-D", "topic"]);
+        git(&dir, &["branch", "created"]);
+        Ok(())
+    })
+    .unwrap();
+    repo.undo_last().unwrap();
+
+    assert_eq!(git(&dir, &["rev-parse", "topic"]), shas[0]);
+    assert_eq!(git(&dir, &["branch", "--list", "created"]), "");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn undo_leaves_branches_of_other_worktrees_alone() {
+    let dir = scratch_dir("journal-worktrees");
+    let linked = scratch_dir("journal-worktrees-linked");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    git(
+        &dir,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "side",
+            &linked.to_string_lossy(),
+            &shas[1],
+        ],
+    );
+
+    repo.journaled("Move both", || {
+        git(&dir, &["update-ref", "refs/heads/side", &shas[0]]);
+        git(&dir, &["branch", "-q", "other"]);
+        Ok(())
+    })
+    .unwrap();
+    repo.undo_last().unwrap();
+
+    // The linked worktree owns "side"; only this worktree's changes are undone.
+    assert_eq!(git(&dir, &["rev-parse", "side"]), shas[0]);
+    assert_eq!(git(&dir, &["branch", "--list", "other"]), "");
+    let _ = std::fs::remove_dir_all(linked);
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,105 @@
+//! Reflog browsing, restoring HEAD from it and recovering lost commits.
+
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::reset::ResetMode;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gitpow-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// A repository on main with three commits; returns their SHAs, oldest first.
+fn repo_with_history(dir: &Path) -> Vec<String> {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.name", "Test"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    (1..=3)
+        .map(|i| {
+            std::fs::write(dir.join("file.txt"), format!("version {}\n", i)).unwrap();
+            git(dir, &["add", "file.txt"]);
+            git(dir, &["commit", "-q", "-m", &format!("commit {}", i)]);
+            git(dir, &["rev-parse", "HEAD"])
+        })
+        .collect()
+}
+
+#[test]
+fn commit_dropped_by_reset_is_lost_and_recoverable() {
+    let dir = scratch_dir("reflog-lost");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+
+    // Journaled, so the undo snapshot still references the dropped commit.
+    repo.reset(&shas[1], ResetMode::Hard).unwrap();
+
+    let lost = repo.lost_commits().unwrap();
+    assert!(lost
+        .iter()
+        .any(|c| c.commit.sha == shas[2] && c.kind == "commit"));
+    assert!(lost.iter().all(|c| c.commit.sha != shas[1]));
+
+    repo.recover_commit(&shas[2], Some("rescued")).unwrap();
+    assert_eq!(git(&dir, &["rev-parse", "rescued"]), shas[2]);
+    let lost = repo.lost_commits().unwrap();
+    assert!(lost.iter().all(|c| c.commit.sha != shas[2]));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn restore_head_to_reflog_entry() {
+    let dir = scratch_dir("reflog-restore");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    git(&dir, &["reset", "-q", "--hard", &shas[0]]);
+
+    let reflog = repo.reflog(None, Some(2)).unwrap();
+    assert_eq!(reflog.len(), 2);
+    assert_eq!(reflog[0].selector, "HEAD@{0}");
+    assert_eq!(reflog[0].action, "reset");
+    assert_eq!(reflog[0].new_sha, shas[0]);
+    assert_eq!(reflog[1].new_sha, shas[2]);
+
+    repo.restore_reflog_entry(None, 1).unwrap();
+    assert_eq!(git(&dir, &["rev-parse", "HEAD"]), shas[2]);
+    assert_eq!(git(&dir, &["symbolic-ref", "HEAD"]), "refs/heads/main");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("file.txt")).unwrap(),
+        "version 3\n"
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn restore_branch_that_is_not_checked_out() {
+    let dir = scratch_dir("reflog-branch");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    git(&dir, &["branch", "topic", &shas[0]]);
+    git(&dir, &["branch", "-f", "topic", &shas[1]]);
+
+    // Moving a branch that isn't checked out leaves the worktree alone.
+    repo.restore_reflog_entry(Some("topic"), 1).unwrap();
+    assert_eq!(git(&dir, &["rev-parse", "topic"]), shas[0]);
+    assert_eq!(git(&dir, &["rev-parse", "HEAD"]), shas[2]);
+    assert!(repo.restore_reflog_entry(Some("topic"), 99).is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
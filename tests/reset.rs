@@ -0,0 +1,130 @@
+//! Reset previews: commits left unreachable and local changes at stake.
+
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::reset::ResetMode;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gitpow-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// main: c1 (file.txt, gone.txt) -> c2 (removes gone.txt) -> c3.
+/// Returns the SHAs, oldest first.
+fn repo_with_history(dir: &Path) -> Vec<String> {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.name", "Test"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    let mut shas = Vec::new();
+    for i in 1..=3 {
+        std::fs::write(dir.join("file.txt"), format!("version {}\n", i)).unwrap();
+        match i {
+            1 => std::fs::write(dir.join("gone.txt"), "old\n").unwrap(),
+            2 => {
+                git(dir, &["rm", "-q", "gone.txt"]);
+            }
+            _ => {}
+        }
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", &format!("commit {}", i)]);
+        shas.push(git(dir, &["rev-parse", "HEAD"]));
+    }
+    shas
+}
+
+fn paths(files: &[gitpow_rust::models::StatusFile]) -> Vec<&str> {
+    let mut paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn preview_lists_commits_only_the_branch_reaches() {
+    let dir = scratch_dir("reset-unreachable");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+
+    let preview = repo.reset_preview(&shas[0], ResetMode::Soft).unwrap();
+    assert_eq!(preview.branch.as_deref(), Some("main"));
+    assert_eq!(preview.old_head, shas[2]);
+    assert_eq!(preview.target, shas[0]);
+    assert_eq!(preview.unreachable_count, 2);
+    let listed: Vec<&str> = preview
+        .unreachable_commits
+        .iter()
+        .map(|c| c.sha.as_str())
+        .collect();
+    assert_eq!(listed, [shas[2].as_str(), shas[1].as_str()]);
+    assert!(preview.lost_changes.is_empty() && preview.blocked_by.is_empty());
+
+    // Commits another ref still reaches aren't lost.
+    git(&dir, &["tag", "keep", &shas[1]]);
+    let preview = repo.reset_preview(&shas[0], ResetMode::Soft).unwrap();
+    assert_eq!(preview.unreachable_count, 1);
+    assert_eq!(preview.unreachable_commits[0].sha, shas[2]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn hard_preview_lists_lost_changes_and_keep_preview_blockers() {
+    let dir = scratch_dir("reset-changes");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    std::fs::write(dir.join("file.txt"), "local edit\n").unwrap();
+    // Untracked: overwritten when the target has the path, otherwise left alone.
+    std::fs::write(dir.join("gone.txt"), "untracked\n").unwrap();
+    std::fs::write(dir.join("notes.txt"), "untracked\n").unwrap();
+
+    let hard = repo.reset_preview(&shas[0], ResetMode::Hard).unwrap();
+    assert_eq!(paths(&hard.lost_changes), ["file.txt", "gone.txt"]);
+    assert!(hard.blocked_by.is_empty());
+
+    let keep = repo.reset_preview(&shas[0], ResetMode::Keep).unwrap();
+    assert!(keep.lost_changes.is_empty());
+    assert_eq!(paths(&keep.blocked_by), ["file.txt", "gone.txt"]);
+
+    let mixed = repo.reset_preview(&shas[0], ResetMode::Mixed).unwrap();
+    assert!(mixed.lost_changes.is_empty() && mixed.blocked_by.is_empty());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn hard_reset_moves_the_branch_and_can_be_undone() {
+    let dir = scratch_dir("reset-run");
+    let shas = repo_with_history(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+    std::fs::write(dir.join("file.txt"), "local edit\n").unwrap();
+
+    repo.reset(&shas[0], ResetMode::Hard).unwrap();
+    assert_eq!(git(&dir, &["rev-parse", "main"]), shas[0]);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("file.txt")).unwrap(),
+        "version 1\n"
+    );
+
+    repo.undo_last().unwrap();
+    assert_eq!(git(&dir, &["rev-parse", "main"]), shas[2]);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("file.txt")).unwrap(),
+        "local edit\n"
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
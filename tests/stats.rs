@@ -0,0 +1,227 @@
+//! Contributor, churn and activity statistics over a history with fixed dates.
+
+use gitpow_rust::git::repository::GitRepository;
+use gitpow_rust::git::stats::{parse_date_bound, ChurnOptions, TimeBucket};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gitpow-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// Commit everything as `author` <`email`> at `date` (RFC3339).
+fn commit_as(dir: &Path, author: &str, email: &str, date: &str, message: &str) {
+    git(dir, &["add", "-A"]);
+    let output = Command::new("git")
+        .args(["commit", "-q", "-m", message])
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", author)
+        .env("GIT_AUTHOR_EMAIL", email)
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_NAME", author)
+        .env("GIT_COMMITTER_EMAIL", email)
+        .env("GIT_COMMITTER_DATE", date)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git commit: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// main, three commits touching a.txt and b.txt together:
+///   Alice, Mon 2024-01-01 10:00 UTC (also adds a .mailmap)
+///   Bob,   Wed 2024-01-03 15:00 +02:00
+///   Alice under an old address, Sat 2024-02-10 09:00 -05:00
+fn repo_with_authors(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "-q", "-b", "main"]);
+    std::fs::write(
+        dir.join(".mailmap"),
+        "Alice <alice@example.com> <alice@old.example.com>\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+    std::fs::write(dir.join("b.txt"), "one\n").unwrap();
+    commit_as(
+        dir,
+        "Alice",
+        "alice@example.com",
+        "2024-01-01T10:00:00+00:00",
+        "add files",
+    );
+    std::fs::write(dir.join("a.txt"), "one\nTWO\nthree\n").unwrap();
+    std::fs::write(dir.join("b.txt"), "one\ntwo\n").unwrap();
+    commit_as(
+        dir,
+        "Bob",
+        "bob@example.com",
+        "2024-01-03T15:00:00+02:00",
+        "edit files",
+    );
+    std::fs::write(dir.join("a.txt"), "one\nTWO\nthree\nfour\nfive\n").unwrap();
+    std::fs::write(dir.join("b.txt"), "one\ntwo\nthree\n").unwrap();
+    commit_as(
+        dir,
+        "A. Smith",
+        "alice@old.example.com",
+        "2024-02-10T09:00:00-05:00",
+        "extend files",
+    );
+}
+
+#[test]
+fn date_bounds_accept_bare_dates_and_rfc3339() {
+    assert_eq!(parse_date_bound("2024-01-02", false).unwrap(), 1704153600);
+    assert_eq!(
+        parse_date_bound("2024-01-02", true).unwrap(),
+        1704153600 + 86399
+    );
+    assert_eq!(
+        parse_date_bound("2024-01-02T02:00:00+02:00", false).unwrap(),
+        1704153600
+    );
+    assert!(parse_date_bound("2024-13-01", false).is_err());
+    assert!(parse_date_bound("yesterday", true).is_err());
+
+    assert!(TimeBucket::parse("Month").is_ok());
+    assert!(TimeBucket::parse("fortnight").is_err());
+}
+
+#[test]
+fn contributors_are_merged_through_mailmap() {
+    let dir = scratch_dir("stats-contributors");
+    repo_with_authors(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+
+    let stats = repo.contributor_stats("HEAD", None, None).unwrap();
+    assert_eq!(stats.total_commits, 3);
+    assert_eq!(stats.contributors.len(), 2);
+    let alice = stats
+        .contributors
+        .iter()
+        .find(|c| c.email == "alice@example.com")
+        .unwrap();
+    assert_eq!(alice.name, "Alice");
+    assert_eq!(alice.commits, 2);
+    // .mailmap (1) + a.txt (3) + b.txt (1), then a.txt (2) + b.txt (1)
+    assert_eq!(alice.lines_added, 8);
+    assert_eq!(alice.lines_removed, 0);
+    let weeks: Vec<_> = alice.weeks.iter().map(|w| w.week.as_str()).collect();
+    assert_eq!(weeks, ["2024-01-01", "2024-02-05"]);
+    let bob = stats
+        .contributors
+        .iter()
+        .find(|c| c.email == "bob@example.com")
+        .unwrap();
+    assert_eq!(bob.commits, 1);
+    assert_eq!((bob.lines_added, bob.lines_removed), (2, 1));
+
+    // The first commit falls before the range
+    let ranged = repo
+        .contributor_stats("HEAD", Some("2024-01-02"), Some("2024-02-10"))
+        .unwrap();
+    assert_eq!(ranged.total_commits, 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn activity_buckets_fill_empty_periods() {
+    let dir = scratch_dir("stats-activity");
+    repo_with_authors(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+
+    let monthly = repo
+        .commit_activity("HEAD", TimeBucket::Month, None, None, None, None)
+        .unwrap();
+    assert_eq!(monthly.total_commits, 3);
+    let months: Vec<_> = monthly
+        .buckets
+        .iter()
+        .map(|b| (b.period.as_str(), b.commits))
+        .collect();
+    assert_eq!(months, [("2024-01", 2), ("2024-02", 1)]);
+
+    let weekly = repo
+        .commit_activity("HEAD", TimeBucket::Week, None, None, None, None)
+        .unwrap();
+    let weeks: Vec<_> = weekly
+        .buckets
+        .iter()
+        .map(|b| (b.period.as_str(), b.commits))
+        .collect();
+    assert_eq!(
+        weeks,
+        [
+            ("2024-01-01", 2),
+            ("2024-01-08", 0),
+            ("2024-01-15", 0),
+            ("2024-01-22", 0),
+            ("2024-01-29", 0),
+            ("2024-02-05", 1),
+        ]
+    );
+    // Weekday and hour in the author's own time zone, Monday first
+    assert_eq!(weekly.punchcard[0][10], 1);
+    assert_eq!(weekly.punchcard[2][15], 1);
+    assert_eq!(weekly.punchcard[5][9], 1);
+
+    let bob_only = repo
+        .commit_activity("HEAD", TimeBucket::Day, Some("Bob"), None, None, None)
+        .unwrap();
+    assert_eq!(bob_only.total_commits, 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn churn_counts_commits_and_coupling() {
+    let dir = scratch_dir("stats-churn");
+    repo_with_authors(&dir);
+    let repo = GitRepository::open(&dir).unwrap();
+
+    let churn = repo
+        .churn_stats(
+            "HEAD",
+            &ChurnOptions {
+                since: None,
+                until: None,
+                limit: 10,
+                max_coupling_files: 10,
+                min_shared_commits: 2,
+            },
+        )
+        .unwrap();
+    assert_eq!(churn.commits_analyzed, 3);
+    let a = churn.files.iter().find(|f| f.path == "a.txt").unwrap();
+    assert_eq!(a.commits, 3);
+    assert_eq!((a.lines_added, a.lines_removed), (6, 1));
+    assert_eq!(a.lines, 5);
+    assert_eq!(churn.coupling.len(), 1);
+    let pair = &churn.coupling[0];
+    let mut files = [pair.file_a.as_str(), pair.file_b.as_str()];
+    files.sort();
+    assert_eq!(files, ["a.txt", "b.txt"]);
+    assert_eq!(pair.shared_commits, 3);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}